@@ -0,0 +1,1168 @@
+//! Datagram sockets addressed by [`UniAddr`].
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+use std::ffi::c_int;
+#[cfg(feature = "feat-socket2")]
+use std::net::SocketAddr;
+use std::net::UdpSocket;
+#[cfg(unix)]
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, IntoRawFd, OwnedFd, RawFd};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(feature = "feat-tokio")]
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+use std::{fmt, io};
+
+#[cfg(feature = "feat-tokio")]
+use tokio::io::ReadBuf;
+
+use crate::{ToUniAddrs, UniAddr, UniAddrInner};
+
+#[non_exhaustive]
+/// A datagram socket, either a UDP socket or a Unix datagram socket.
+///
+/// Peer addresses are exchanged as [`UniAddr`], so code sending and
+/// receiving datagrams does not need to match on the socket type.
+///
+/// # Examples
+///
+/// ```rust
+/// use uni_addr::UniAddr;
+///
+/// let server = UniAddr::new("127.0.0.1:0").unwrap().bind_datagram().unwrap();
+/// let client = UniAddr::new("127.0.0.1:0").unwrap().bind_datagram().unwrap();
+///
+/// client.send_to(b"ping", &server.local_addr().unwrap()).unwrap();
+///
+/// let mut buf = [0; 4];
+/// let (len, peer_addr) = server.recv_from(&mut buf).unwrap();
+/// assert_eq!(&buf[..len], b"ping");
+/// assert_eq!(peer_addr, client.local_addr().unwrap());
+/// ```
+pub enum StdDatagram {
+    /// See [`UdpSocket`].
+    Udp(UdpSocket),
+
+    #[cfg(unix)]
+    /// See [`UnixDatagram`].
+    Unix(UnixDatagram),
+}
+
+impl fmt::Debug for StdDatagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Udp(socket) => socket.fmt(f),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.fmt(f),
+        }
+    }
+}
+
+impl From<UdpSocket> for StdDatagram {
+    fn from(socket: UdpSocket) -> Self {
+        Self::Udp(socket)
+    }
+}
+
+#[cfg(unix)]
+impl From<UnixDatagram> for StdDatagram {
+    fn from(socket: UnixDatagram) -> Self {
+        Self::Unix(socket)
+    }
+}
+
+#[cfg(unix)]
+impl AsFd for StdDatagram {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Self::Udp(socket) => socket.as_fd(),
+            Self::Unix(socket) => socket.as_fd(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for StdDatagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for StdDatagram {
+    fn into_raw_fd(self) -> RawFd {
+        OwnedFd::from(self).into_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl From<StdDatagram> for OwnedFd {
+    fn from(socket: StdDatagram) -> Self {
+        match socket {
+            StdDatagram::Udp(socket) => socket.into(),
+            StdDatagram::Unix(socket) => socket.into(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl TryFrom<OwnedFd> for StdDatagram {
+    type Error = io::Error;
+
+    /// Wraps a datagram socket file descriptor, detecting whether it is a UDP
+    /// or a Unix datagram socket.
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        if crate::unix::getsockopt_int(fd.as_fd(), libc::SO_TYPE)? != libc::SOCK_DGRAM {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the file descriptor is not a datagram socket",
+            ));
+        }
+
+        // `getsockname` fails for a socket of another address family.
+        let socket = UdpSocket::from(fd);
+
+        if socket.local_addr().is_ok() {
+            return Ok(Self::Udp(socket));
+        }
+
+        let socket = UnixDatagram::from(OwnedFd::from(socket));
+
+        if socket.local_addr().is_ok() {
+            return Ok(Self::Unix(socket));
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the datagram socket has an unsupported address family",
+        ))
+    }
+}
+
+impl StdDatagram {
+    /// Creates a datagram socket bound to the given address.
+    ///
+    /// Each address is tried in turn until one succeeds. Host names are
+    /// resolved with [`UdpSocket::bind`], which tries each resolved address in
+    /// turn, too.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::bind`] and [`UnixDatagram::bind_addr`]. Returns the
+    /// error of the last address if none succeeds.
+    pub fn bind<A: ToUniAddrs>(addr: A) -> io::Result<Self> {
+        crate::each_addr(addr, Self::bind_one)
+    }
+
+    fn bind_one(addr: &UniAddr) -> io::Result<Self> {
+        match addr.as_inner() {
+            UniAddrInner::Inet(addr) => UdpSocket::bind(addr).map(Self::Udp),
+            #[cfg(unix)]
+            UniAddrInner::Unix(addr) => UnixDatagram::bind_addr(addr).map(Self::Unix),
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrInner::Vsock(..) => Err(unsupported_vsock()),
+            #[cfg(unix)]
+            UniAddrInner::Fd(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "file descriptor addresses are not supported by datagram sockets",
+            )),
+            #[cfg(windows)]
+            UniAddrInner::Pipe(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "named pipe addresses are not supported by datagram sockets",
+            )),
+            UniAddrInner::Host(host) => UdpSocket::bind(&**host).map(Self::Udp),
+        }
+    }
+
+    /// Connects the socket to a remote address, so that [`send`](Self::send)
+    /// and [`recv`](Self::recv) can be used.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::connect`] and [`UnixDatagram::connect_addr`]. Also
+    /// returns an error if the address type does not match the socket type.
+    /// Each address is tried in turn, like [`bind`](Self::bind).
+    pub fn connect<A: ToUniAddrs>(&self, addr: A) -> io::Result<()> {
+        crate::each_addr(addr, |addr| self.connect_one(addr))
+    }
+
+    fn connect_one(&self, addr: &UniAddr) -> io::Result<()> {
+        match (self, addr.as_inner()) {
+            (Self::Udp(socket), UniAddrInner::Inet(addr)) => socket.connect(addr),
+            (Self::Udp(socket), UniAddrInner::Host(host)) => socket.connect(&**host),
+            #[cfg(unix)]
+            (Self::Unix(socket), UniAddrInner::Unix(addr)) => socket.connect_addr(addr),
+            #[cfg(any(unix, windows))]
+            _ => Err(mismatched_address_type()),
+        }
+    }
+
+    /// Sends data on the socket to the given address, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::send_to`] and [`UnixDatagram::send_to_addr`]. Also
+    /// returns an error if the address type does not match the socket type.
+    pub fn send_to(&self, buf: &[u8], addr: &UniAddr) -> io::Result<usize> {
+        match (self, addr.as_inner()) {
+            (Self::Udp(socket), UniAddrInner::Inet(addr)) => socket.send_to(buf, addr),
+            (Self::Udp(socket), UniAddrInner::Host(host)) => socket.send_to(buf, &**host),
+            #[cfg(unix)]
+            (Self::Unix(socket), UniAddrInner::Unix(addr)) => socket.send_to_addr(buf, addr),
+            #[cfg(any(unix, windows))]
+            _ => Err(mismatched_address_type()),
+        }
+    }
+
+    /// Receives a single datagram, returning the number of bytes read and the
+    /// address of the sender.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::recv_from`] and [`UnixDatagram::recv_from`].
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, UniAddr)> {
+        match self {
+            Self::Udp(socket) => socket
+                .recv_from(buf)
+                .map(|(len, addr)| (len, UniAddr::from(addr))),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket
+                .recv_from(buf)
+                .map(|(len, addr)| (len, UniAddr::from(addr))),
+        }
+    }
+
+    /// Sends data on the socket to the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::send`] and [`UnixDatagram::send`].
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Udp(socket) => socket.send(buf),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.send(buf),
+        }
+    }
+
+    /// Receives a single datagram from the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::recv`] and [`UnixDatagram::recv`].
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Udp(socket) => socket.recv(buf),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.recv(buf),
+        }
+    }
+
+    /// Returns the local address of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::local_addr`] and [`UnixDatagram::local_addr`].
+    pub fn local_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Udp(socket) => socket.local_addr().map(UniAddr::from),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.local_addr().map(UniAddr::from),
+        }
+    }
+
+    /// Returns the address of the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::peer_addr`] and [`UnixDatagram::peer_addr`].
+    pub fn peer_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Udp(socket) => socket.peer_addr().map(UniAddr::from),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.peer_addr().map(UniAddr::from),
+        }
+    }
+
+    /// Moves the socket into or out of nonblocking mode.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::set_nonblocking`] and
+    /// [`UnixDatagram::set_nonblocking`].
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.set_nonblocking(nonblocking),
+        }
+    }
+
+    /// Sets the read timeout of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::set_read_timeout`] and
+    /// [`UnixDatagram::set_read_timeout`].
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.set_read_timeout(timeout),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.set_read_timeout(timeout),
+        }
+    }
+
+    /// Sets the write timeout of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::set_write_timeout`] and
+    /// [`UnixDatagram::set_write_timeout`].
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.set_write_timeout(timeout),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.set_write_timeout(timeout),
+        }
+    }
+
+    /// Returns the value of the `SO_ERROR` option, clearing it.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::take_error`] and [`UnixDatagram::take_error`].
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        match self {
+            Self::Udp(socket) => socket.take_error(),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.take_error(),
+        }
+    }
+
+    /// Creates a new independently owned handle to the underlying socket.
+    ///
+    /// # Errors
+    ///
+    /// See [`UdpSocket::try_clone`] and [`UnixDatagram::try_clone`].
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Udp(socket) => socket.try_clone().map(Self::Udp),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.try_clone().map(Self::Unix),
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    /// Enables or disables `SO_PASSCRED` on a Unix datagram socket, so that
+    /// [`recv_from_with_creds`](Self::recv_from_with_creds) receives the
+    /// credentials of the sender of each datagram.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::Unsupported`] for UDP sockets, otherwise see
+    /// `setsockopt(2)`.
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        match self {
+            Self::Udp(_) => Err(unsupported_creds()),
+            Self::Unix(socket) => crate::unix::setsockopt_int(
+                socket.as_fd(),
+                libc::SO_PASSCRED,
+                c_int::from(passcred),
+            ),
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    /// Likes [`recv_from`](Self::recv_from), but also returns the credentials
+    /// of the sender, as checked by the kernel.
+    ///
+    /// The credentials are only present with
+    /// [`set_passcred`](Self::set_passcred) enabled.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::Unsupported`] for UDP sockets, otherwise see
+    /// `recvmsg(2)`.
+    pub fn recv_from_with_creds(
+        &self,
+        buf: &mut [u8],
+    ) -> io::Result<(usize, UniAddr, Option<crate::unix::UCred>)> {
+        match self {
+            Self::Udp(_) => Err(unsupported_creds()),
+            Self::Unix(socket) => crate::unix::recv_from_with_creds(socket.as_fd(), buf)
+                .map(|(len, addr, ucred)| (len, UniAddr::from(addr), ucred)),
+        }
+    }
+
+    #[cfg(feature = "feat-socket2")]
+    /// Sets the hop limit of outgoing unicast datagrams, i.e., `IP_TTL` for
+    /// IPv4 sockets or `IPV6_UNICAST_HOPS` for IPv6 ones.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::Unsupported`] for Unix datagram sockets, otherwise see
+    /// `setsockopt(2)`.
+    pub fn set_unicast_hops(&self, hops: u32) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => set_unicast_hops(&socket.into(), socket.local_addr()?, hops),
+            #[cfg(unix)]
+            Self::Unix(_) => Err(unsupported_unicast_hops()),
+        }
+    }
+
+    #[cfg(feature = "feat-socket2")]
+    /// Returns the hop limit of outgoing unicast datagrams, see
+    /// [`set_unicast_hops`](Self::set_unicast_hops).
+    ///
+    /// # Errors
+    ///
+    /// See [`set_unicast_hops`](Self::set_unicast_hops).
+    pub fn unicast_hops(&self) -> io::Result<u32> {
+        match self {
+            Self::Udp(socket) => unicast_hops(&socket.into(), socket.local_addr()?),
+            #[cfg(unix)]
+            Self::Unix(_) => Err(unsupported_unicast_hops()),
+        }
+    }
+}
+
+#[cfg(feature = "feat-tokio")]
+#[non_exhaustive]
+/// The asynchronous counterpart of [`StdDatagram`], built on Tokio.
+///
+/// # Examples
+///
+/// ```rust
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use uni_addr::UniAddr;
+/// use uni_addr::datagram::Datagram;
+///
+/// let server = Datagram::bind(&UniAddr::new("127.0.0.1:0").unwrap()).await.unwrap();
+/// let client = Datagram::bind(&UniAddr::new("127.0.0.1:0").unwrap()).await.unwrap();
+///
+/// client.send_to(b"ping", &server.local_addr().unwrap()).await.unwrap();
+///
+/// let mut buf = [0; 4];
+/// let (len, peer_addr) = server.recv_from(&mut buf).await.unwrap();
+/// assert_eq!(&buf[..len], b"ping");
+/// assert_eq!(peer_addr, client.local_addr().unwrap());
+/// # }
+/// ```
+pub enum Datagram {
+    /// See [`tokio::net::UdpSocket`].
+    Udp(tokio::net::UdpSocket),
+
+    #[cfg(unix)]
+    /// See [`tokio::net::UnixDatagram`].
+    Unix(tokio::net::UnixDatagram),
+}
+
+#[cfg(feature = "feat-tokio")]
+impl fmt::Debug for Datagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Udp(socket) => socket.fmt(f),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "feat-tokio")]
+impl From<tokio::net::UdpSocket> for Datagram {
+    fn from(socket: tokio::net::UdpSocket) -> Self {
+        Self::Udp(socket)
+    }
+}
+
+#[cfg(all(unix, feature = "feat-tokio"))]
+impl From<tokio::net::UnixDatagram> for Datagram {
+    fn from(socket: tokio::net::UnixDatagram) -> Self {
+        Self::Unix(socket)
+    }
+}
+
+#[cfg(all(unix, feature = "feat-tokio"))]
+impl AsFd for Datagram {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Self::Udp(socket) => socket.as_fd(),
+            Self::Unix(socket) => socket.as_fd(),
+        }
+    }
+}
+
+#[cfg(all(unix, feature = "feat-tokio"))]
+impl AsRawFd for Datagram {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+#[cfg(feature = "feat-tokio")]
+impl Datagram {
+    /// Creates a datagram socket bound to the given address.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::bind`] and [`tokio::net::UdpSocket::bind`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub async fn bind<A: ToUniAddrs>(addr: A) -> io::Result<Self> {
+        let mut last_err = None;
+
+        for addr in addr.to_uni_addrs()? {
+            match Self::bind_one(&addr).await {
+                Ok(socket) => return Ok(socket),
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(crate::no_addresses))
+    }
+
+    async fn bind_one(addr: &UniAddr) -> io::Result<Self> {
+        match addr.as_inner() {
+            UniAddrInner::Inet(addr) => tokio::net::UdpSocket::bind(addr).await.map(Self::Udp),
+            #[cfg(unix)]
+            UniAddrInner::Unix(addr) => {
+                let socket = UnixDatagram::bind_addr(addr)?;
+
+                socket.set_nonblocking(true)?;
+
+                tokio::net::UnixDatagram::from_std(socket).map(Self::Unix)
+            }
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrInner::Vsock(..) => Err(unsupported_vsock()),
+            #[cfg(unix)]
+            UniAddrInner::Fd(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "file descriptor addresses are not supported by datagram sockets",
+            )),
+            #[cfg(windows)]
+            UniAddrInner::Pipe(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "named pipe addresses are not supported by datagram sockets",
+            )),
+            UniAddrInner::Host(host) => tokio::net::UdpSocket::bind(&**host).await.map(Self::Udp),
+        }
+    }
+
+    /// Creates a [`Datagram`] from a [`StdDatagram`], which will be set to
+    /// nonblocking mode.
+    ///
+    /// # Errors
+    ///
+    /// See [`tokio::net::UdpSocket::from_std`] and
+    /// [`tokio::net::UnixDatagram::from_std`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    pub fn from_std(socket: StdDatagram) -> io::Result<Self> {
+        socket.set_nonblocking(true)?;
+
+        match socket {
+            StdDatagram::Udp(socket) => tokio::net::UdpSocket::from_std(socket).map(Self::Udp),
+            #[cfg(unix)]
+            StdDatagram::Unix(socket) => tokio::net::UnixDatagram::from_std(socket).map(Self::Unix),
+        }
+    }
+
+    /// Turns the [`Datagram`] into a [`StdDatagram`], which will be left in
+    /// nonblocking mode.
+    ///
+    /// # Errors
+    ///
+    /// See [`tokio::net::UdpSocket::into_std`] and
+    /// [`tokio::net::UnixDatagram::into_std`].
+    pub fn into_std(self) -> io::Result<StdDatagram> {
+        match self {
+            Self::Udp(socket) => socket.into_std().map(StdDatagram::Udp),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.into_std().map(StdDatagram::Unix),
+        }
+    }
+
+    /// Connects the socket to a remote address.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::connect`].
+    pub async fn connect<A: ToUniAddrs>(&self, addr: A) -> io::Result<()> {
+        let mut last_err = None;
+
+        for addr in addr.to_uni_addrs()? {
+            match self.connect_one(&addr).await {
+                Ok(()) => return Ok(()),
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.unwrap_or_else(crate::no_addresses))
+    }
+
+    async fn connect_one(&self, addr: &UniAddr) -> io::Result<()> {
+        match (self, addr.as_inner()) {
+            (Self::Udp(socket), UniAddrInner::Inet(addr)) => socket.connect(addr).await,
+            (Self::Udp(socket), UniAddrInner::Host(host)) => socket.connect(&**host).await,
+            #[cfg(unix)]
+            (Self::Unix(socket), UniAddrInner::Unix(addr)) => match addr.as_pathname() {
+                Some(pathname) => socket.connect(pathname),
+                None => with_std_unix_datagram(socket, |socket| socket.connect_addr(addr)),
+            },
+            #[cfg(any(unix, windows))]
+            _ => Err(mismatched_address_type()),
+        }
+    }
+
+    /// Sends data on the socket to the given address.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::send_to`].
+    pub async fn send_to(&self, buf: &[u8], addr: &UniAddr) -> io::Result<usize> {
+        match (self, addr.as_inner()) {
+            (Self::Udp(socket), UniAddrInner::Inet(addr)) => socket.send_to(buf, addr).await,
+            (Self::Udp(socket), UniAddrInner::Host(host)) => socket.send_to(buf, &**host).await,
+            #[cfg(unix)]
+            (Self::Unix(socket), UniAddrInner::Unix(addr)) => match addr.as_pathname() {
+                Some(pathname) => socket.send_to(buf, pathname).await,
+                None => {
+                    socket
+                        .async_io(tokio::io::Interest::WRITABLE, || {
+                            with_std_unix_datagram(socket, |socket| socket.send_to_addr(buf, addr))
+                        })
+                        .await
+                }
+            },
+            #[cfg(any(unix, windows))]
+            _ => Err(mismatched_address_type()),
+        }
+    }
+
+    /// Receives a single datagram, returning the number of bytes read and the
+    /// address of the sender.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::recv_from`].
+    pub async fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, UniAddr)> {
+        match self {
+            Self::Udp(socket) => socket
+                .recv_from(buf)
+                .await
+                .map(|(len, addr)| (len, UniAddr::from(addr))),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket
+                .recv_from(buf)
+                .await
+                .map(|(len, addr)| (len, UniAddr::from(addr))),
+        }
+    }
+
+    /// Sends data on the socket to the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::send`].
+    pub async fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Udp(socket) => socket.send(buf).await,
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.send(buf).await,
+        }
+    }
+
+    /// Receives a single datagram from the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::recv`].
+    pub async fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Udp(socket) => socket.recv(buf).await,
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.recv(buf).await,
+        }
+    }
+
+    /// Attempts to send data on the socket to the given address.
+    ///
+    /// Unlike [`send_to`](Self::send_to), host names are not resolved here.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::send_to`]. Also returns an error if the address is a
+    /// host name.
+    pub fn poll_send_to(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+        addr: &UniAddr,
+    ) -> Poll<io::Result<usize>> {
+        match (self, addr.as_inner()) {
+            (Self::Udp(socket), UniAddrInner::Inet(addr)) => socket.poll_send_to(cx, buf, *addr),
+            (Self::Udp(_), UniAddrInner::Host(_)) => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "The host name address must be resolved before sending",
+            ))),
+            #[cfg(unix)]
+            (Self::Unix(socket), UniAddrInner::Unix(addr)) => match addr.as_pathname() {
+                Some(pathname) => socket.poll_send_to(cx, buf, pathname),
+                None => loop {
+                    ready!(socket.poll_send_ready(cx))?;
+
+                    match socket.try_io(tokio::io::Interest::WRITABLE, || {
+                        with_std_unix_datagram(socket, |socket| socket.send_to_addr(buf, addr))
+                    }) {
+                        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                        ret => return Poll::Ready(ret),
+                    }
+                },
+            },
+            #[cfg(any(unix, windows))]
+            _ => Poll::Ready(Err(mismatched_address_type())),
+        }
+    }
+
+    /// Attempts to receive a single datagram, returning the address of the
+    /// sender.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::recv_from`].
+    pub fn poll_recv_from(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<UniAddr>> {
+        match self {
+            Self::Udp(socket) => socket.poll_recv_from(cx, buf).map_ok(UniAddr::from),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.poll_recv_from(cx, buf).map_ok(UniAddr::from),
+        }
+    }
+
+    /// Attempts to send data on the socket to the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::send`].
+    pub fn poll_send(&self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self {
+            Self::Udp(socket) => socket.poll_send(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.poll_send(cx, buf),
+        }
+    }
+
+    /// Attempts to receive a single datagram from the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::recv`].
+    pub fn poll_recv(&self, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self {
+            Self::Udp(socket) => socket.poll_recv(cx, buf),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.poll_recv(cx, buf),
+        }
+    }
+
+    /// Waits for the socket to become readable.
+    ///
+    /// # Errors
+    ///
+    /// See [`tokio::net::UdpSocket::readable`] and
+    /// [`tokio::net::UnixDatagram::readable`].
+    pub async fn readable(&self) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.readable().await,
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.readable().await,
+        }
+    }
+
+    /// Waits for the socket to become writable.
+    ///
+    /// # Errors
+    ///
+    /// See [`tokio::net::UdpSocket::writable`] and
+    /// [`tokio::net::UnixDatagram::writable`].
+    pub async fn writable(&self) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => socket.writable().await,
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.writable().await,
+        }
+    }
+
+    /// Returns the local address of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::local_addr`].
+    pub fn local_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Udp(socket) => socket.local_addr().map(UniAddr::from),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.local_addr().map(UniAddr::from),
+        }
+    }
+
+    /// Returns the address of the connected peer.
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::peer_addr`].
+    pub fn peer_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Udp(socket) => socket.peer_addr().map(UniAddr::from),
+            #[cfg(unix)]
+            Self::Unix(socket) => socket.peer_addr().map(UniAddr::from),
+        }
+    }
+
+    #[cfg(feature = "feat-socket2")]
+    /// See [`StdDatagram::set_unicast_hops`].
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::set_unicast_hops`].
+    pub fn set_unicast_hops(&self, hops: u32) -> io::Result<()> {
+        match self {
+            Self::Udp(socket) => set_unicast_hops(&socket.into(), socket.local_addr()?, hops),
+            #[cfg(unix)]
+            Self::Unix(_) => Err(unsupported_unicast_hops()),
+        }
+    }
+
+    #[cfg(feature = "feat-socket2")]
+    /// See [`StdDatagram::unicast_hops`].
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::unicast_hops`].
+    pub fn unicast_hops(&self) -> io::Result<u32> {
+        match self {
+            Self::Udp(socket) => unicast_hops(&socket.into(), socket.local_addr()?),
+            #[cfg(unix)]
+            Self::Unix(_) => Err(unsupported_unicast_hops()),
+        }
+    }
+}
+
+#[cfg(feature = "feat-socket2")]
+/// `IP_TTL` silently does nothing for IPv6 sockets, and vice versa, so pick
+/// the option matching the address family.
+fn set_unicast_hops(
+    socket: &socket2::SockRef<'_>,
+    local_addr: SocketAddr,
+    hops: u32,
+) -> io::Result<()> {
+    match local_addr {
+        SocketAddr::V4(_) => socket.set_ttl_v4(hops),
+        SocketAddr::V6(_) => socket.set_unicast_hops_v6(hops),
+    }
+}
+
+#[cfg(feature = "feat-socket2")]
+fn unicast_hops(socket: &socket2::SockRef<'_>, local_addr: SocketAddr) -> io::Result<u32> {
+    match local_addr {
+        SocketAddr::V4(_) => socket.ttl_v4(),
+        SocketAddr::V6(_) => socket.unicast_hops_v6(),
+    }
+}
+
+#[cfg(all(unix, feature = "feat-socket2"))]
+fn unsupported_unicast_hops() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "hop limits are only available for UDP sockets",
+    )
+}
+
+#[cfg(all(unix, feature = "feat-tokio"))]
+/// Tokio only accepts pathnames as Unix socket addresses, so operations
+/// targeting abstract addresses go through a duplicated [`UnixDatagram`]
+/// handle sharing the same underlying socket.
+fn with_std_unix_datagram<F, R>(socket: &tokio::net::UnixDatagram, f: F) -> io::Result<R>
+where
+    F: FnOnce(&UnixDatagram) -> io::Result<R>,
+{
+    f(&UnixDatagram::from(socket.as_fd().try_clone_to_owned()?))
+}
+
+#[cfg(all(
+    feature = "feat-vsock",
+    any(target_os = "android", target_os = "linux")
+))]
+fn unsupported_vsock() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "vsock addresses are not supported by datagram sockets",
+    )
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+fn unsupported_creds() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "sender credentials are only available for Unix datagram sockets",
+    )
+}
+
+#[cfg(any(unix, windows))]
+pub(crate) fn mismatched_address_type() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the address type does not match the socket type",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("127.0.0.1:0", "127.0.0.1:0")]
+    #[case("[::1]:0", "[::1]:0")]
+    #[case("localhost:0", "localhost:0")]
+    #[cfg_attr(
+        unix,
+        case(
+            "unix:///tmp/test_StdDatagram_send_to_recv_from_1.socket",
+            "unix:///tmp/test_StdDatagram_send_to_recv_from_2.socket"
+        )
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case(
+            "unix://@test_StdDatagram_send_to_recv_from_1.socket",
+            "unix://@test_StdDatagram_send_to_recv_from_2.socket"
+        )
+    )]
+    fn test_StdDatagram_send_to_recv_from(#[case] server: &str, #[case] client: &str) {
+        let server = UniAddr::new(server).unwrap().bind_datagram().unwrap();
+        let client = UniAddr::new(client).unwrap().bind_datagram().unwrap();
+
+        let server_addr = server.local_addr().unwrap();
+        let client_addr = client.local_addr().unwrap();
+
+        client.send_to(b"ping", &server_addr).unwrap();
+
+        let mut buf = [0; 16];
+        let (len, peer_addr) = server.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"ping");
+        assert_eq!(peer_addr, client_addr);
+
+        server.connect(&peer_addr).unwrap();
+        client.connect(&server_addr).unwrap();
+        assert_eq!(server.peer_addr().unwrap(), client_addr);
+
+        server.send(b"pong").unwrap();
+
+        let len = client.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"pong");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_StdDatagram_bind_each_addr() {
+        let addrs = vec![
+            // TEST-NET-1, not assigned to any local interface.
+            UniAddr::new("192.0.2.1:0").unwrap(),
+            UniAddr::new("127.0.0.1:0").unwrap(),
+        ];
+
+        let socket = StdDatagram::bind(&addrs).unwrap();
+        assert!(matches!(socket, StdDatagram::Udp(_)));
+
+        let socket = StdDatagram::bind("127.0.0.1:0").unwrap();
+        assert!(matches!(socket, StdDatagram::Udp(_)));
+
+        let _ = StdDatagram::bind(Vec::new()).unwrap_err();
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[rstest]
+    #[case(
+        "unix:///tmp/test_StdDatagram_recv_from_with_creds_1.socket",
+        "unix:///tmp/test_StdDatagram_recv_from_with_creds_2.socket"
+    )]
+    #[case(
+        "unix://@test_StdDatagram_recv_from_with_creds_1.socket",
+        "unix://@test_StdDatagram_recv_from_with_creds_2.socket"
+    )]
+    fn test_StdDatagram_recv_from_with_creds(#[case] server: &str, #[case] client: &str) {
+        for addr in [server, client] {
+            if let Some(pathname) = addr
+                .strip_prefix("unix://")
+                .filter(|addr| addr.starts_with('/'))
+            {
+                let _ = std::fs::remove_file(pathname);
+            }
+        }
+
+        let server = UniAddr::new(server).unwrap().bind_datagram().unwrap();
+        let client = UniAddr::new(client).unwrap().bind_datagram().unwrap();
+
+        server.set_passcred(true).unwrap();
+        client
+            .send_to(b"ping", &server.local_addr().unwrap())
+            .unwrap();
+
+        let mut buf = [0; 16];
+        let (len, peer_addr, ucred) = server.recv_from_with_creds(&mut buf).unwrap();
+
+        assert_eq!(&buf[..len], b"ping");
+        assert_eq!(peer_addr, client.local_addr().unwrap());
+        assert_eq!(ucred.unwrap().pid, Some(std::process::id()));
+
+        // The socket of the sender is still reachable.
+        server.send_to(b"pong", &peer_addr).unwrap();
+        let len = client.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"pong");
+
+        let socket = StdDatagram::bind("127.0.0.1:0").unwrap();
+        assert_eq!(
+            socket.set_passcred(true).unwrap_err().kind(),
+            io::ErrorKind::Unsupported
+        );
+    }
+
+    #[cfg(feature = "feat-socket2")]
+    #[rstest]
+    #[case("127.0.0.1:0")]
+    #[case("[::1]:0")]
+    fn test_StdDatagram_unicast_hops(#[case] addr: &str) {
+        let socket = StdDatagram::bind(addr).unwrap();
+
+        socket.set_unicast_hops(42).unwrap();
+        assert_eq!(socket.unicast_hops().unwrap(), 42);
+
+        #[cfg(unix)]
+        {
+            let socket = StdDatagram::from(UnixDatagram::unbound().unwrap());
+            assert_eq!(
+                socket.set_unicast_hops(42).unwrap_err().kind(),
+                io::ErrorKind::Unsupported
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_StdDatagram_OwnedFd_conversion() {
+        let socket = StdDatagram::bind("127.0.0.1:0").unwrap();
+        let local_addr = socket.local_addr().unwrap();
+
+        let socket = StdDatagram::try_from(OwnedFd::from(socket)).unwrap();
+        assert!(matches!(socket, StdDatagram::Udp(_)));
+        assert_eq!(socket.local_addr().unwrap(), local_addr);
+
+        let socket = StdDatagram::from(UnixDatagram::unbound().unwrap());
+
+        let socket = StdDatagram::try_from(OwnedFd::from(socket)).unwrap();
+        assert!(matches!(socket, StdDatagram::Unix(_)));
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let _ = StdDatagram::try_from(OwnedFd::from(listener)).unwrap_err();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_StdDatagram_mismatched_address_type() {
+        let udp = UniAddr::new("127.0.0.1:0")
+            .unwrap()
+            .bind_datagram()
+            .unwrap();
+        let unix =
+            UniAddr::new("unix:///tmp/test_StdDatagram_mismatched_address_type.socket").unwrap();
+
+        assert_eq!(
+            udp.send_to(b"ping", &unix).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            udp.connect(&unix).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[cfg(feature = "feat-tokio")]
+    #[rstest]
+    #[case("127.0.0.1:0", "127.0.0.1:0")]
+    #[case("[::1]:0", "[::1]:0")]
+    #[cfg_attr(
+        unix,
+        case(
+            "unix:///tmp/test_Datagram_send_to_recv_from_1.socket",
+            "unix:///tmp/test_Datagram_send_to_recv_from_2.socket"
+        )
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case(
+            "unix://@test_Datagram_send_to_recv_from_1.socket",
+            "unix://@test_Datagram_send_to_recv_from_2.socket"
+        )
+    )]
+    #[tokio::test]
+    async fn test_Datagram_send_to_recv_from(#[case] server: &str, #[case] client: &str) {
+        let server = Datagram::bind(&UniAddr::new(server).unwrap())
+            .await
+            .unwrap();
+        let client = Datagram::bind(&UniAddr::new(client).unwrap())
+            .await
+            .unwrap();
+
+        let server_addr = server.local_addr().unwrap();
+        let client_addr = client.local_addr().unwrap();
+
+        client.send_to(b"ping", &server_addr).await.unwrap();
+
+        let mut buf = [0; 16];
+        let (len, peer_addr) = server.recv_from(&mut buf).await.unwrap();
+        assert_eq!(&buf[..len], b"ping");
+        assert_eq!(peer_addr, client_addr);
+
+        let len = std::future::poll_fn(|cx| server.poll_send_to(cx, b"pong", &peer_addr))
+            .await
+            .unwrap();
+        assert_eq!(len, 4);
+
+        let mut read_buf = ReadBuf::new(&mut buf);
+        let peer_addr = std::future::poll_fn(|cx| client.poll_recv_from(cx, &mut read_buf))
+            .await
+            .unwrap();
+        assert_eq!(read_buf.filled(), b"pong");
+        assert_eq!(peer_addr, server_addr);
+
+        server.connect(&client_addr).await.unwrap();
+        client.connect(&server_addr).await.unwrap();
+        assert_eq!(client.peer_addr().unwrap(), server_addr);
+
+        client.send(b"ping").await.unwrap();
+
+        let len = server.recv(&mut buf).await.unwrap();
+        assert_eq!(&buf[..len], b"ping");
+    }
+}
@@ -0,0 +1,176 @@
+//! A tiny HTTP health / readiness responder.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use std::sync::Arc;
+//!
+//! use uni_addr::UniAddr;
+//!
+//! let is_ready = Arc::new(AtomicBool::new(false));
+//!
+//! let server = uni_addr::health::serve(&UniAddr::new("127.0.0.1:0").unwrap(), {
+//!     let is_ready = is_ready.clone();
+//!
+//!     move || is_ready.load(Ordering::Relaxed)
+//! })
+//! .unwrap();
+//!
+//! // Probes now get `503 Service Unavailable` from `server.local_addr()`...
+//!
+//! is_ready.store(true, Ordering::Relaxed);
+//!
+//! // ... and `200 OK` from now on.
+//! # drop(server);
+//! ```
+
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+use crate::server::{Connection, Limits, ServerHandle};
+use crate::{ToUniAddrs, UniAddr};
+
+/// The maximum size of the request head we read before responding.
+const MAX_REQUEST_HEAD_LEN: usize = 8192;
+
+/// How long a probe may take to send its request or read the response, so that
+/// idle clients cannot hold a handler thread forever.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The maximum number of probes handled at the same time.
+const MAX_CONNECTIONS: usize = 64;
+
+/// Binds `addr` and answers every request with a minimal HTTP/1.0 response,
+/// `200 OK` if `status_fn` returns `true` and `503 Service Unavailable`
+/// otherwise.
+///
+/// The request method and path are ignored, so any path can be configured for
+/// the probe. Connections are handled on background threads, at most 64 at a
+/// time, and are closed after 5 seconds without progress.
+///
+/// # Errors
+///
+/// Failed to bind the address, or failed to spawn the accepting thread.
+pub fn serve<A, F>(addr: A, status_fn: F) -> io::Result<HealthServer>
+where
+    A: ToUniAddrs,
+    F: Fn() -> bool + Send + Sync + 'static,
+{
+    let limits = Limits {
+        timeout: Some(TIMEOUT),
+        max_connections: Some(MAX_CONNECTIONS),
+    };
+
+    crate::server::spawn(addr, "uni-addr-health", limits, move |mut conn| {
+        let _ = respond(&mut conn, status_fn());
+    })
+    .map(HealthServer)
+}
+
+fn respond(conn: &mut Box<dyn Connection>, is_healthy: bool) -> io::Result<()> {
+    let mut buf = [0; MAX_REQUEST_HEAD_LEN];
+    let mut len = 0;
+
+    // Read until the end of the request head, so that the peer does not see a
+    // connection reset when we close the connection with unread data.
+    while len < buf.len() && !buf[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+        match conn.read(&mut buf[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+
+    let response: &[u8] = if is_healthy {
+        b"HTTP/1.0 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 3\r\nConnection: close\r\n\r\nOK\n"
+    } else {
+        b"HTTP/1.0 503 Service Unavailable\r\nContent-Type: text/plain\r\nContent-Length: 12\r\nConnection: close\r\n\r\nUnavailable\n"
+    };
+
+    conn.write_all(response)?;
+    conn.flush()
+}
+
+#[derive(Debug)]
+/// A running health server spawned with [`serve`].
+///
+/// The server stops accepting new connections when this is dropped or
+/// [`shutdown`](Self::shutdown) is called.
+pub struct HealthServer(ServerHandle);
+
+impl HealthServer {
+    #[inline]
+    /// Returns the address the server is actually bound to.
+    pub const fn local_addr(&self) -> &UniAddr {
+        self.0.local_addr()
+    }
+
+    #[inline]
+    /// Stops the server, waiting for the accepting thread to exit.
+    pub fn shutdown(self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("127.0.0.1:0")]
+    #[cfg_attr(unix, case("unix:///tmp/test_health_serve.socket"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case("unix://@test_health_serve.socket")
+    )]
+    fn test_health_serve(#[case] addr: &str) {
+        let is_healthy = Arc::new(AtomicBool::new(false));
+
+        let server = serve(addr, {
+            let is_healthy = is_healthy.clone();
+
+            move || is_healthy.load(Ordering::Relaxed)
+        })
+        .unwrap();
+
+        let request = |expected: &str| {
+            let mut conn = crate::server::connect(server.local_addr()).unwrap();
+
+            conn.write_all(b"GET /healthz HTTP/1.0\r\n\r\n").unwrap();
+
+            let mut response = String::new();
+            conn.read_to_string(&mut response).unwrap();
+
+            assert!(
+                response.starts_with(expected),
+                "unexpected response: {response:?}"
+            );
+        };
+
+        request("HTTP/1.0 503 Service Unavailable\r\n");
+
+        is_healthy.store(true, Ordering::Relaxed);
+
+        request("HTTP/1.0 200 OK\r\n");
+    }
+
+    #[test]
+    fn test_health_serve_idle_client() {
+        let server = serve("127.0.0.1:0", || true).unwrap();
+
+        // Never sends a request.
+        let _idle = crate::server::connect(server.local_addr()).unwrap();
+
+        let mut conn = crate::server::connect(server.local_addr()).unwrap();
+
+        conn.write_all(b"GET /healthz HTTP/1.0\r\n\r\n").unwrap();
+
+        let mut response = String::new();
+        conn.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.0 200 OK\r\n"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Converting Unicode host names to ASCII, i.e., punycode A-labels like
+//! `xn--bcher-kva.de` for `bücher.de`.
+//!
+//! Labels are lowercased, but not otherwise mapped or normalized as UTS #46
+//! does, so inputs are expected in NFC already. See
+//! <https://www.rfc-editor.org/rfc/rfc3492>.
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+/// Converts each non-ASCII label of `hostname` to an A-label, leaving ASCII
+/// labels untouched.
+///
+/// Returns `None` if a label cannot be encoded. The result is not validated as
+/// a host name.
+pub(crate) fn to_ascii(hostname: &str) -> Option<String> {
+    let labels = hostname
+        .split('.')
+        .map(|label| {
+            if label.is_ascii() {
+                return Some(label.to_owned());
+            }
+
+            let label = label
+                .chars()
+                .flat_map(char::to_lowercase)
+                .collect::<Vec<_>>();
+
+            encode(&label).map(|encoded| format!("xn--{encoded}"))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(labels.join("."))
+}
+
+/// Encodes `input` with the Punycode algorithm, see RFC 3492, section 6.3.
+fn encode(input: &[char]) -> Option<String> {
+    let mut output = input.iter().filter(|c| c.is_ascii()).collect::<String>();
+
+    let basic = u32::try_from(output.len()).ok()?;
+    let mut handled = basic;
+
+    if basic > 0 {
+        output.push('-');
+    }
+
+    // Named after RFC 3492: `n`, `m`, `q`, `k` and `t` respectively.
+    let mut code_point = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+
+    while (handled as usize) < input.len() {
+        let next_code_point = input
+            .iter()
+            .map(|&ch| u32::from(ch))
+            .filter(|&ch| ch >= code_point)
+            .min()?;
+
+        delta = delta.checked_add((next_code_point - code_point).checked_mul(handled + 1)?)?;
+        code_point = next_code_point;
+
+        for &ch in input {
+            let ch = u32::from(ch);
+
+            if ch < code_point {
+                delta = delta.checked_add(1)?;
+            }
+
+            if ch == code_point {
+                let mut value = delta;
+                let mut position = BASE;
+
+                loop {
+                    let threshold = position.saturating_sub(bias).clamp(T_MIN, T_MAX);
+
+                    if value < threshold {
+                        break;
+                    }
+
+                    output.push(digit(threshold + (value - threshold) % (BASE - threshold)));
+                    value = (value - threshold) / (BASE - threshold);
+                    position += BASE;
+                }
+
+                output.push(digit(value));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+
+        delta = delta.checked_add(1)?;
+        code_point += 1;
+    }
+
+    Some(output)
+}
+
+/// See RFC 3492, section 6.1.
+const fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+
+    delta += delta / num_points;
+
+    let mut k = 0;
+
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+
+    k + (BASE - T_MIN + 1) * delta / (delta + SKEW)
+}
+
+/// Returns the basic code point for the digit `d`, i.e., `a`-`z` and `0`-`9`.
+fn digit(d: u32) -> char {
+    #[allow(clippy::cast_possible_truncation)]
+    let d = d as u8;
+
+    char::from(if d < 26 { b'a' + d } else { b'0' + d - 26 })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("example.com", "example.com")]
+    #[case("bücher.de", "xn--bcher-kva.de")]
+    #[case("BÜCHER.de", "xn--bcher-kva.de")]
+    #[case("münchen.example", "xn--mnchen-3ya.example")]
+    #[case("español.com", "xn--espaol-zwa.com")]
+    #[case("例え.テスト", "xn--r8jz45g.xn--zckzah")]
+    #[case("☃", "xn--n3h")]
+    fn test_to_ascii(#[case] hostname: &str, #[case] expected: &str) {
+        assert_eq!(to_ascii(hostname).unwrap(), expected);
+    }
+}
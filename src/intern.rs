@@ -0,0 +1,158 @@
+//! Interning host name addresses, so that repeated parses of the same host
+//! name share one allocation.
+//!
+//! Proxies parse the same handful of upstream host names over and over. An
+//! [`InternPool`] keeps the host name addresses it has parsed, and hands out
+//! the same [`Arc<str>`] again when the same input comes by, skipping the
+//! validation as well.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use uni_addr::intern::InternPool;
+//!
+//! let pool = InternPool::new();
+//!
+//! let a = pool.parse("example.com:443").unwrap();
+//! let b = pool.parse("example.com:443").unwrap();
+//!
+//! assert_eq!(a, b);
+//! assert_eq!(pool.len(), 1);
+//! ```
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use crate::{ParseError, UniAddr, UniAddrInner};
+
+#[derive(Debug, Default)]
+/// A pool of interned host name addresses.
+///
+/// Only host name addresses are interned: other addresses don't allocate, or
+/// are rarely parsed repeatedly. The pool grows until
+/// [`clear`](Self::clear)ed.
+pub struct InternPool {
+    hosts: Mutex<HashSet<Arc<str>>>,
+}
+
+impl InternPool {
+    #[inline]
+    /// Creates an empty [`InternPool`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process-wide [`InternPool`].
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<InternPool> = OnceLock::new();
+
+        GLOBAL.get_or_init(Self::new)
+    }
+
+    /// Creates a new [`UniAddr`] from its string representation, like
+    /// [`UniAddr::new`], sharing the host name with previous parses of the
+    /// same input.
+    ///
+    /// # Errors
+    ///
+    /// See [`UniAddr::new`].
+    pub fn parse(&self, addr: &str) -> Result<UniAddr, ParseError> {
+        if let Some(host) = self.lock().get(addr) {
+            return Ok(UniAddr::from_inner(UniAddrInner::Host(host.clone())));
+        }
+
+        let parsed = UniAddr::new(addr)?;
+
+        match parsed.as_inner() {
+            // Not for a converted Unicode host name, which is not `addr`.
+            UniAddrInner::Host(host) if **host == *addr => {
+                let mut hosts = self.lock();
+
+                // Another thread may have interned it meanwhile.
+                let host = if let Some(interned) = hosts.get(addr) {
+                    interned.clone()
+                } else {
+                    hosts.insert(host.clone());
+                    host.clone()
+                };
+
+                Ok(UniAddr::from_inner(UniAddrInner::Host(host)))
+            }
+            _ => Ok(parsed),
+        }
+    }
+
+    /// Returns the number of interned host names.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks if no host names are interned.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all interned host names.
+    ///
+    /// Addresses parsed before keep their host names alive.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashSet<Arc<str>>> {
+        // The set is always consistent, even if a panic poisoned the lock.
+        self.hosts
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_of(addr: &UniAddr) -> Option<&Arc<str>> {
+        match addr.as_inner() {
+            UniAddrInner::Host(host) => Some(host),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_shares_host() {
+        let pool = InternPool::new();
+
+        let a = pool.parse("example.com:443").unwrap();
+        let b = pool.parse("example.com:443").unwrap();
+        let c = pool.parse("example.com:80").unwrap();
+
+        assert!(Arc::ptr_eq(host_of(&a).unwrap(), host_of(&b).unwrap()));
+        assert_eq!(a, UniAddr::new("example.com:443").unwrap());
+        assert_eq!(c, UniAddr::new("example.com:80").unwrap());
+        assert_eq!(pool.len(), 2);
+
+        pool.clear();
+        assert!(pool.is_empty());
+
+        let d = pool.parse("example.com:443").unwrap();
+        assert!(!Arc::ptr_eq(host_of(&a).unwrap(), host_of(&d).unwrap()));
+        assert_eq!(a, d);
+    }
+
+    #[test]
+    fn test_parse_not_interned() {
+        let pool = InternPool::new();
+
+        assert_eq!(
+            pool.parse("127.0.0.1:8080").unwrap(),
+            UniAddr::new("127.0.0.1:8080").unwrap()
+        );
+        assert_eq!(
+            pool.parse("bücher.de:443").unwrap(),
+            UniAddr::new("bücher.de:443").unwrap()
+        );
+        let _ = pool.parse("-invalid:443").unwrap_err();
+
+        assert!(pool.is_empty());
+    }
+}
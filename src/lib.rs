@@ -2,13 +2,45 @@
 #![allow(clippy::must_use_candidate)]
 
 use std::borrow::Cow;
-use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs};
+use std::ffi::{OsStr, OsString};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6, ToSocketAddrs};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::{fmt, io};
 
+pub mod datagram;
+#[cfg(feature = "feat-health")]
+pub mod health;
+#[cfg(feature = "feat-idna")]
+mod idna;
+#[cfg(feature = "feat-intern")]
+pub mod intern;
+#[doc(hidden)]
+pub mod macros;
+#[cfg(feature = "feat-multiaddr")]
+mod multiaddr;
+#[cfg(feature = "feat-otel")]
+pub mod otel;
+#[cfg(feature = "feat-pin")]
+pub mod pin;
+#[cfg(feature = "feat-proxy-protocol")]
+pub mod proxy_protocol;
+pub mod retry;
+#[cfg(feature = "feat-serde")]
+pub mod serde_tagged;
+#[cfg(any(feature = "feat-health", feature = "feat-testing"))]
+mod server;
+#[cfg(feature = "feat-sni")]
+pub mod sni;
+#[cfg(all(feature = "feat-systemd", target_os = "linux"))]
+pub mod systemd;
+#[cfg(feature = "feat-testing")]
+pub mod testing;
 #[cfg(unix)]
 pub mod unix;
+mod wire;
+#[cfg(feature = "feat-x509")]
+pub mod x509;
 
 /// The prefix for Unix domain socket URIs.
 ///
@@ -16,49 +48,162 @@ pub mod unix;
 /// - `unix://@abstract.unix.socket` for an abstract socket address.
 pub const UNIX_URI_PREFIX: &str = "unix://";
 
+#[cfg(all(
+    feature = "feat-vsock",
+    any(target_os = "android", target_os = "linux")
+))]
+/// The prefix for VM socket (`AF_VSOCK`) URIs, like `vsock://2:1234`.
+pub const VSOCK_URI_PREFIX: &str = "vsock://";
+
+/// The prefix for inherited file descriptor URIs, like `fd://3`.
+pub const FD_URI_PREFIX: &str = "fd://";
+
+/// The prefix for Windows named pipe URIs, like
+/// `pipe://\\.\pipe\my-service`.
+pub const PIPE_URI_PREFIX: &str = "pipe://";
+
+/// The prefix for TCP URIs, like `tcp://example.com:4222`, see
+/// [`UniAddr::new_uri`].
+pub const TCP_URI_PREFIX: &str = "tcp://";
+
+/// The prefix for host name addresses in the alternate form of
+/// [`Display`](fmt::Display), like `dns://example.com:443`.
+pub const DNS_URI_PREFIX: &str = "dns://";
+
+/// The prefix for UDP URIs, like `udp://example.com:514`, see
+/// [`UniAddr::new_uri`].
+pub const UDP_URI_PREFIX: &str = "udp://";
+
 wrapper_lite::wrapper!(
     #[wrapper_impl(Debug)]
     #[wrapper_impl(Display)]
     #[wrapper_impl(AsRef)]
     #[wrapper_impl(Deref)]
-    #[repr(align(cache))]
     #[derive(Clone, PartialEq, Eq, Hash)]
     /// A unified address type that can represent:
     ///
     /// - [`std::net::SocketAddr`]
     /// - [`unix::SocketAddr`] (a wrapper over
     ///   [`std::os::unix::net::SocketAddr`])
+    /// - An inherited file descriptor number.
+    /// - A Windows named pipe path.
     /// - A host name with port. See [`ToSocketAddrs`].
     ///
     /// # Parsing Behaviour
     ///
     /// - Checks if the address started with [`UNIX_URI_PREFIX`]: parse as a UDS
     ///   address.
+    /// - With the `feat-vsock` feature on Linux, checks if the address started
+    ///   with `vsock://`: parse as a VM socket address like `vsock://2:1234`.
+    /// - Checks if the address started with [`FD_URI_PREFIX`]: parse as an
+    ///   inherited file descriptor number like `fd://3`.
+    /// - Checks if the address started with [`PIPE_URI_PREFIX`]: parse as a
+    ///   Windows named pipe path, see [`UniAddr::new_pipe`].
+    /// - Checks if the address started with [`TCP_URI_PREFIX`]: parse the rest
+    ///   as a network socket address or a host name, like
+    ///   [`UniAddr::new_uri`] does.
+    /// - Checks if the address started with [`DNS_URI_PREFIX`]: parse the rest
+    ///   as a host name, see the alternate form of [`Display`](fmt::Display)
+    ///   below.
     /// - Checks if the address is started with a alphabetic character (a-z,
     ///   A-Z): treat as a host name. Notes that we will not validate if the
     ///   host name is valid.
     /// - Tries to parse as a network socket address.
     /// - Otherwise, treats the input as a host name.
+    ///
+    /// # Formatting
+    ///
+    /// [`Display`](fmt::Display) writes the string representation, like
+    /// [`UniAddr::to_str`]. The alternate form, i.e. `{:#}`, qualifies network
+    /// socket addresses and host names with a scheme as well, like
+    /// `tcp://192.0.2.1:80` and `dns://example.com:80`, so that the kind of
+    /// address is unambiguous. Both re-parse with [`UniAddr::new`], except for
+    /// host names without port, see [`UniAddr::new_optional_port`].
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let addr = UniAddr::new("example.com:80").unwrap();
+    ///
+    /// assert_eq!(format!("{addr}"), "example.com:80");
+    /// assert_eq!(format!("{addr:#}"), "dns://example.com:80");
+    /// assert_eq!(UniAddr::new(&format!("{addr:#}")).unwrap(), addr);
+    /// ```
     pub struct UniAddr(UniAddrInner);
 );
 
+// Addresses are passed around and stored in routing tables by value, keep
+// them no larger than the common case, a `SocketAddr`.
+const _: () = assert!(std::mem::size_of::<UniAddr>() <= std::mem::size_of::<SocketAddr>());
+
+wrapper_lite::wrapper!(
+    #[wrapper_impl(Debug)]
+    #[wrapper_impl(Display)]
+    #[wrapper_impl(AsRef)]
+    #[wrapper_impl(Deref)]
+    #[wrapper_impl(From)]
+    #[repr(align(cache))]
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    /// A [`UniAddr`] padded and aligned to the cache line size, for addresses
+    /// shared between threads which are otherwise prone to false sharing.
+    ///
+    /// [`UniAddr`] itself is not aligned, keeping dense collections like
+    /// routing tables small.
+    pub struct CacheAlignedUniAddr(UniAddr);
+);
+
+impl From<CacheAlignedUniAddr> for UniAddr {
+    fn from(addr: CacheAlignedUniAddr) -> Self {
+        addr.inner
+    }
+}
+
 impl From<SocketAddr> for UniAddr {
     fn from(addr: SocketAddr) -> Self {
         UniAddr::from_inner(UniAddrInner::Inet(addr))
     }
 }
 
+impl From<SocketAddrV4> for UniAddr {
+    fn from(addr: SocketAddrV4) -> Self {
+        UniAddr::from(SocketAddr::V4(addr))
+    }
+}
+
+impl From<SocketAddrV6> for UniAddr {
+    fn from(addr: SocketAddrV6) -> Self {
+        UniAddr::from(SocketAddr::V6(addr))
+    }
+}
+
+impl From<(IpAddr, u16)> for UniAddr {
+    fn from(addr: (IpAddr, u16)) -> Self {
+        UniAddr::from(SocketAddr::from(addr))
+    }
+}
+
+impl From<(Ipv4Addr, u16)> for UniAddr {
+    fn from(addr: (Ipv4Addr, u16)) -> Self {
+        UniAddr::from(SocketAddr::from(addr))
+    }
+}
+
+impl From<(Ipv6Addr, u16)> for UniAddr {
+    fn from(addr: (Ipv6Addr, u16)) -> Self {
+        UniAddr::from(SocketAddr::from(addr))
+    }
+}
+
 #[cfg(unix)]
 impl From<std::os::unix::net::SocketAddr> for UniAddr {
     fn from(addr: std::os::unix::net::SocketAddr) -> Self {
-        UniAddr::from_inner(UniAddrInner::Unix(addr.into()))
+        UniAddr::from(unix::SocketAddr::from(addr))
     }
 }
 
 #[cfg(all(unix, feature = "feat-tokio"))]
 impl From<tokio::net::unix::SocketAddr> for UniAddr {
     fn from(addr: tokio::net::unix::SocketAddr) -> Self {
-        UniAddr::from_inner(UniAddrInner::Unix(unix::SocketAddr::from(addr.into())))
+        UniAddr::from(unix::SocketAddr::from(addr.into()))
     }
 }
 
@@ -80,21 +225,20 @@ impl TryFrom<&socket2::SockAddr> for UniAddr {
             return Ok(Self::from(addr));
         }
 
-        #[cfg(unix)]
-        if let Some(addr) = addr.as_unix() {
-            return Ok(Self::from(addr));
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        if let Some((cid, port)) = addr.as_vsock_address() {
+            return Ok(Self::new_vsock(cid, port));
         }
 
         #[cfg(unix)]
-        if addr.is_unnamed() {
-            return Ok(Self::from(crate::unix::SocketAddr::new_unnamed()));
-        }
-
-        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
-        if let Some(addr) = addr.as_abstract_namespace() {
-            return crate::unix::SocketAddr::new_abstract(addr).map(Self::from);
+        {
+            crate::unix::SocketAddr::try_from(addr).map(Self::from)
         }
 
+        #[cfg(not(unix))]
         Err(io::Error::new(
             io::ErrorKind::Other,
             "unsupported address type",
@@ -120,6 +264,21 @@ impl TryFrom<&UniAddr> for socket2::SockAddr {
             UniAddrInner::Inet(addr) => Ok(socket2::SockAddr::from(*addr)),
             #[cfg(unix)]
             UniAddrInner::Unix(addr) => socket2::SockAddr::unix(addr.to_os_string()),
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrInner::Vsock(cid, port) => Ok(socket2::SockAddr::vsock(*cid, *port)),
+            #[cfg(unix)]
+            UniAddrInner::Fd(_) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The file descriptor address cannot be converted to SockAddr",
+            )),
+            #[cfg(windows)]
+            UniAddrInner::Pipe(_) => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "The named pipe address cannot be converted to SockAddr",
+            )),
             UniAddrInner::Host(_) => Err(io::Error::new(
                 io::ErrorKind::Other,
                 "The host name address must be resolved before converting to SockAddr",
@@ -131,7 +290,7 @@ impl TryFrom<&UniAddr> for socket2::SockAddr {
 #[cfg(unix)]
 impl From<crate::unix::SocketAddr> for UniAddr {
     fn from(addr: crate::unix::SocketAddr) -> Self {
-        UniAddr::from_inner(UniAddrInner::Unix(addr))
+        UniAddr::from_inner(UniAddrInner::Unix(Arc::new(addr)))
     }
 }
 
@@ -143,12 +302,95 @@ impl FromStr for UniAddr {
     }
 }
 
+impl TryFrom<&OsStr> for UniAddr {
+    type Error = ParseError;
+
+    fn try_from(addr: &OsStr) -> Result<Self, Self::Error> {
+        Self::new_os(addr)
+    }
+}
+
+impl TryFrom<OsString> for UniAddr {
+    type Error = ParseError;
+
+    fn try_from(addr: OsString) -> Result<Self, Self::Error> {
+        Self::new_os(&addr)
+    }
+}
+
+impl PartialEq<SocketAddr> for UniAddr {
+    fn eq(&self, other: &SocketAddr) -> bool {
+        matches!(self.as_inner(), UniAddrInner::Inet(addr) if addr == other)
+    }
+}
+
+impl PartialEq<UniAddr> for SocketAddr {
+    fn eq(&self, other: &UniAddr) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<(IpAddr, u16)> for UniAddr {
+    fn eq(&self, other: &(IpAddr, u16)) -> bool {
+        *self == SocketAddr::from(*other)
+    }
+}
+
+impl PartialEq<UniAddr> for (IpAddr, u16) {
+    fn eq(&self, other: &UniAddr) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<str> for UniAddr {
+    /// Compares the string representation of the address with `other`, see
+    /// [`UniAddr::to_str`].
+    fn eq(&self, other: &str) -> bool {
+        self.to_str() == other
+    }
+}
+
+impl PartialEq<UniAddr> for str {
+    fn eq(&self, other: &UniAddr) -> bool {
+        other == self
+    }
+}
+
+impl PartialEq<&str> for UniAddr {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<UniAddr> for &str {
+    fn eq(&self, other: &UniAddr) -> bool {
+        other == *self
+    }
+}
+
 #[cfg(feature = "feat-serde")]
 impl serde::Serialize for UniAddr {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
+        // A compact enum encoding for binary formats, see `serde_tagged`.
+        if !serializer.is_human_readable() {
+            return serde_tagged::serialize(self, serializer);
+        }
+
+        // A port-less address, see `UniAddr::new_optional_port`, which is not a
+        // valid address string, but a valid `{ host = "example.com" }` map.
+        if let UniAddrInner::Host(host) = self.as_inner() {
+            if self.port().is_none() {
+                use serde::ser::SerializeMap;
+
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("host", &**host)?;
+                return map.end();
+            }
+        }
+
         serializer.serialize_str(&self.to_str())
     }
 }
@@ -159,7 +401,112 @@ impl<'de> serde::Deserialize<'de> for UniAddr {
     where
         D: serde::Deserializer<'de>,
     {
-        Self::new(&String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+        if !deserializer.is_human_readable() {
+            return serde_tagged::deserialize_or_legacy(deserializer);
+        }
+
+        deserializer.deserialize_any(UniAddrVisitor)
+    }
+}
+
+#[cfg(feature = "feat-serde")]
+/// Deserializes a [`UniAddr`] from its string representation, or from a map
+/// like `{ host = "example.com", port = 8080 }`, where a missing port means a
+/// port-less address, see [`UniAddr::new_optional_port`].
+struct UniAddrVisitor;
+
+#[cfg(feature = "feat-serde")]
+impl<'de> serde::de::Visitor<'de> for UniAddrVisitor {
+    type Value = UniAddr;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an address string, or a map with `host` and `port`")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        UniAddr::new(v).map_err(E::custom)
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        use serde::de::Error;
+
+        let (mut host, mut port) = (None::<String>, None::<Port>);
+
+        while let Some(key) = map.next_key::<String>()? {
+            match &*key {
+                "host" if host.is_none() => host = Some(map.next_value()?),
+                "port" if port.is_none() => port = Some(map.next_value()?),
+                "host" | "port" => {
+                    return Err(A::Error::custom(format!("duplicate field `{key}`")))
+                }
+                _ => return Err(A::Error::unknown_field(&key, &["host", "port"])),
+            }
+        }
+
+        let host = host.ok_or_else(|| A::Error::missing_field("host"))?;
+
+        // The host is a host name or an IP address, without port. Never a URI,
+        // which `new_optional_port` parses with `UniAddr::new`, removing the
+        // file of a Unix socket.
+        if host.contains("://") {
+            return Err(A::Error::custom(ParseError::InvalidHost));
+        }
+
+        let addr = UniAddr::new_optional_port(&host).map_err(A::Error::custom)?;
+
+        if addr.port().is_some() {
+            return Err(A::Error::custom(ParseError::InvalidHost));
+        }
+
+        match port {
+            Some(Port(port)) => addr
+                .with_port(port)
+                .ok_or_else(|| A::Error::custom(ParseError::InvalidHost)),
+            None => Ok(addr),
+        }
+    }
+}
+
+#[cfg(feature = "feat-serde")]
+/// A port number, also accepted as a numeric string as some configuration
+/// sources, e.g., environment variables, have strings only.
+struct Port(u16);
+
+#[cfg(feature = "feat-serde")]
+impl<'de> serde::Deserialize<'de> for Port {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct PortVisitor;
+
+        impl serde::de::Visitor<'_> for PortVisitor {
+            type Value = Port;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a port number")
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                u16::try_from(v)
+                    .map(Port)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                u16::try_from(v)
+                    .map(Port)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(v), &self))
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                v.parse()
+                    .map(Port)
+                    .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(PortVisitor)
     }
 }
 
@@ -171,151 +518,785 @@ impl UniAddr {
     ///
     /// Not a valid address string.
     pub fn new(addr: &str) -> Result<Self, ParseError> {
-        if addr.is_empty() {
-            return Err(ParseError::Empty);
-        }
-
         #[cfg(unix)]
         if let Some(addr) = addr.strip_prefix(UNIX_URI_PREFIX) {
             return unix::SocketAddr::new(addr)
-                .map(UniAddrInner::Unix)
-                .map(Self::from_inner)
+                .map(Self::from)
                 .map_err(ParseError::InvalidUDSAddress);
         }
 
+        UniAddrRef::new(addr).map(|addr| addr.to_owned())
+    }
+
+    /// Creates a new [`UniAddr`] from its string representation as an
+    /// [`OsStr`], like a command line argument or an environment variable.
+    ///
+    /// The path of a `unix://` address may not be valid UTF-8 on Unix-like
+    /// systems, while other addresses must be.
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string, see [`UniAddr::new`]. Also returns
+    /// [`ParseError::InvalidHost`] if the input is not valid UTF-8 while it
+    /// must be.
+    pub fn new_os(addr: &OsStr) -> Result<Self, ParseError> {
+        #[cfg(unix)]
+        return Self::from_bytes(std::os::unix::ffi::OsStrExt::as_bytes(addr));
+
         #[cfg(not(unix))]
-        if let Some(_addr) = addr.strip_prefix(UNIX_URI_PREFIX) {
+        return addr
+            .to_str()
+            .ok_or(ParseError::InvalidHost)
+            .and_then(Self::new);
+    }
+
+    /// Creates a new [`UniAddr`] from its string representation as bytes, like
+    /// a field of a network protocol message.
+    ///
+    /// The path of a `unix://` address is taken as is, and may not be valid
+    /// UTF-8, while other addresses must be.
+    ///
+    /// # Errors
+    ///
+    /// See [`UniAddr::new_os`].
+    pub fn from_bytes(addr: &[u8]) -> Result<Self, ParseError> {
+        if let Some(path) = addr.strip_prefix(UNIX_URI_PREFIX.as_bytes()) {
+            #[cfg(not(unix))]
+            let _ = path;
+
+            #[cfg(unix)]
+            return unix::SocketAddr::new(<OsStr as std::os::unix::ffi::OsStrExt>::from_bytes(
+                path,
+            ))
+            .map(Self::from)
+            .map_err(ParseError::InvalidUDSAddress);
+
+            #[cfg(not(unix))]
             return Err(ParseError::Unsupported);
         }
 
-        let Some((host, port)) = addr.rsplit_once(':') else {
-            return Err(ParseError::InvalidPort);
-        };
+        std::str::from_utf8(addr)
+            .map_err(|_| ParseError::InvalidHost)
+            .and_then(Self::new)
+    }
 
-        let Ok(port) = port.parse::<u16>() else {
-            return Err(ParseError::InvalidPort);
-        };
+    #[inline]
+    /// Creates a new [`UniAddr`] for the IPv4 loopback address `127.0.0.1`
+    /// with the given port.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// assert_eq!(UniAddr::localhost(8080), "127.0.0.1:8080");
+    /// ```
+    pub const fn localhost(port: u16) -> Self {
+        Self::from_inner(UniAddrInner::Inet(SocketAddr::V4(SocketAddrV4::new(
+            Ipv4Addr::LOCALHOST,
+            port,
+        ))))
+    }
 
-        // Short-circuit: IPv4 address starts with a digit.
-        if host.chars().next().is_some_and(|c| c.is_ascii_digit()) {
-            return Ipv4Addr::from_str(host)
-                .map(|ip| SocketAddr::V4(SocketAddrV4::new(ip, port)))
-                .map(UniAddrInner::Inet)
-                .map(Self::from_inner)
-                .map_err(|_| ParseError::InvalidHost)
-                .or_else(|_| {
-                    // A host name may also start with a digit.
-                    Self::new_host(addr, Some((host, port)))
-                });
-        }
+    #[inline]
+    /// Creates a new [`UniAddr`] for the IPv4 unspecified address `0.0.0.0`
+    /// with the given port, i.e. all IPv4 interfaces when bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// assert_eq!(UniAddr::unspecified_v4(8080), "0.0.0.0:8080");
+    /// ```
+    pub const fn unspecified_v4(port: u16) -> Self {
+        Self::from_inner(UniAddrInner::Inet(SocketAddr::V4(SocketAddrV4::new(
+            Ipv4Addr::UNSPECIFIED,
+            port,
+        ))))
+    }
 
-        // Short-circuit: if starts with '[' and ends with ']', may be an IPv6 address
-        // and can never be a host.
-        if let Some(ipv6_addr) = host.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
-            return Ipv6Addr::from_str(ipv6_addr)
-                .map(|ip| SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0)))
-                .map(UniAddrInner::Inet)
-                .map(Self::from_inner)
-                .map_err(|_| ParseError::InvalidHost);
-        }
+    #[inline]
+    /// Creates a new [`UniAddr`] for the IPv6 unspecified address `::` with
+    /// the given port, i.e. all IPv6 interfaces when bound, or all interfaces
+    /// when the socket is dual-stack.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// assert_eq!(UniAddr::unspecified_v6(8080), "[::]:8080");
+    /// ```
+    pub const fn unspecified_v6(port: u16) -> Self {
+        Self::from_inner(UniAddrInner::Inet(SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::UNSPECIFIED,
+            port,
+            0,
+            0,
+        ))))
+    }
 
-        // Fallback: check if is a valid host name.
-        Self::new_host(addr, Some((host, port)))
+    #[cfg(all(
+        feature = "feat-vsock",
+        any(target_os = "android", target_os = "linux")
+    ))]
+    #[inline]
+    /// Creates a new [`UniAddr`] from a VM socket (`AF_VSOCK`) context ID
+    /// (CID) and port.
+    pub const fn new_vsock(cid: u32, port: u32) -> Self {
+        Self::from_inner(UniAddrInner::Vsock(cid, port))
     }
 
-    /// Creates a new [`UniAddr`] from a string containing a host name and port,
-    /// like `example.com:8080`.
+    #[cfg(windows)]
+    /// Creates a new [`UniAddr`] from a Windows named pipe path, like
+    /// `\\.\pipe\my-service`.
     ///
     /// # Errors
     ///
-    /// - [`ParseError::InvalidHost`] if the host name is invalid.
-    /// - [`ParseError::InvalidPort`] if the port is invalid.
-    pub fn new_host(addr: &str, parsed: Option<(&str, u16)>) -> Result<Self, ParseError> {
-        let (hostname, _port) = match parsed {
-            Some((hostname, port)) => (hostname, port),
-            None => addr
-                .rsplit_once(':')
-                .ok_or(ParseError::InvalidPort)
-                .and_then(|(hostname, port)| {
-                    let Ok(port) = port.parse::<u16>() else {
-                        return Err(ParseError::InvalidPort);
-                    };
-
-                    Ok((hostname, port))
-                })?,
+    /// [`ParseError::InvalidHost`] if the path is not in the form of
+    /// `\\<server>\pipe\<name>`, or is longer than 256 characters.
+    pub fn new_pipe(path: &str) -> Result<Self, ParseError> {
+        validate_pipe(path)?;
+
+        Ok(Self::from_inner(UniAddrInner::Pipe(Arc::from(path))))
+    }
+
+    /// Creates a new [`UniAddr`] from its string representation, optionally
+    /// prefixed with [`TCP_URI_PREFIX`] or [`UDP_URI_PREFIX`], returning the
+    /// requested [`Transport`] along with the address.
+    ///
+    /// The transport is `None` if there is no such prefix, and the input is
+    /// then parsed like [`UniAddr::new`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::{Transport, UniAddr};
+    /// let (addr, transport) = UniAddr::new_uri("udp://127.0.0.1:514").unwrap();
+    ///
+    /// assert_eq!(addr.to_str(), "127.0.0.1:514");
+    /// assert_eq!(transport, Some(Transport::Datagram));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string, see [`UniAddr::new`]. Also returns
+    /// [`ParseError::InvalidHost`] if the address following a `tcp://` or
+    /// `udp://` prefix is neither a network socket address nor a host name.
+    pub fn new_uri(addr: &str) -> Result<(Self, Option<Transport>), ParseError> {
+        let (addr, transport) = if let Some(addr) = addr.strip_prefix(TCP_URI_PREFIX) {
+            (addr, Transport::Stream)
+        } else if let Some(addr) = addr.strip_prefix(UDP_URI_PREFIX) {
+            (addr, Transport::Datagram)
+        } else {
+            return Self::new(addr).map(|addr| (addr, None));
         };
 
-        Self::validate_host_name(hostname.as_bytes()).map_err(|()| ParseError::InvalidHost)?;
+        let addr = Self::new(addr)?;
 
-        Ok(Self::from_inner(UniAddrInner::Host(Arc::from(addr))))
+        match addr.as_inner() {
+            UniAddrInner::Inet(_) | UniAddrInner::Host(_) => Ok((addr, Some(transport))),
+            #[allow(unreachable_patterns)]
+            _ => Err(ParseError::InvalidHost),
+        }
     }
 
-    // https://github.com/rustls/pki-types/blob/b8c04aa6b7a34875e2c4a33edc9b78d31da49523/src/server_name.rs
-    const fn validate_host_name(input: &[u8]) -> Result<(), ()> {
-        enum State {
-            Start,
-            Next,
-            NumericOnly { len: usize },
-            NextAfterNumericOnly,
-            Subsequent { len: usize },
-            Hyphen { len: usize },
+    /// Creates a new [`UniAddr`] from its string representation, restricted
+    /// or relaxed by `options`.
+    ///
+    /// With the default options, this is the same as [`UniAddr::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::{ParseOptions, UniAddr, UnixPolicy};
+    /// let options = ParseOptions::new()
+    ///     .with_unix(UnixPolicy::Reject)
+    ///     .with_default_port(443);
+    ///
+    /// let addr = UniAddr::parse_with("example.com", &options).unwrap();
+    /// assert_eq!(addr.to_str(), "example.com:443");
+    ///
+    /// let _ = UniAddr::parse_with("unix:///run/app.socket", &options).unwrap_err();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string, see [`UniAddr::new`]. Also returns
+    ///
+    /// - [`ParseError::InvalidHost`] if the input is longer than the maximum
+    ///   length, or is a host name while host names are not allowed.
+    /// - [`ParseError::Unsupported`] if the input is a Unix domain socket
+    ///   address while those are not allowed.
+    pub fn parse_with(addr: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        if options.max_len.is_some_and(|max_len| addr.len() > max_len) {
+            return Err(ParseError::InvalidHost);
         }
 
-        use State::{Hyphen, Next, NextAfterNumericOnly, NumericOnly, Start, Subsequent};
+        if options.wildcard != WildcardPolicy::Reject {
+            // Not a bare IPv6 address like `::1`.
+            if let Some(port) = addr
+                .strip_prefix("*:")
+                .or_else(|| addr.strip_prefix(':'))
+                .filter(|port| !port.contains(':'))
+            {
+                let Ok(port) = port.parse::<u16>() else {
+                    return Err(ParseError::InvalidPort);
+                };
 
-        /// "Labels must be 63 characters or less."
-        const MAX_LABEL_LENGTH: usize = 63;
+                let ip = if options.wildcard == WildcardPolicy::DualStack {
+                    IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+                } else {
+                    IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+                };
 
-        /// <https://devblogs.microsoft.com/oldnewthing/20120412-00/?p=7873>
-        const MAX_NAME_LENGTH: usize = 253;
+                return Ok(Self::from(SocketAddr::new(ip, port)));
+            }
+        }
 
-        let mut state = Start;
+        if let Some(path) = addr.strip_prefix(UNIX_URI_PREFIX) {
+            if options.unix == UnixPolicy::Reject {
+                return Err(ParseError::Unsupported);
+            }
 
-        if input.len() > MAX_NAME_LENGTH {
-            return Err(());
+            #[cfg(not(unix))]
+            let _ = path;
+
+            #[cfg(unix)]
+            if options.unix == UnixPolicy::Strict {
+                return unix::SocketAddr::new_strict(path)
+                    .map(Self::from)
+                    .map_err(ParseError::InvalidUDSAddress);
+            }
         }
 
-        let mut idx = 0;
-        while idx < input.len() {
-            let ch = input[idx];
-            state = match (state, ch) {
-                (Start | Next | NextAfterNumericOnly | Hyphen { .. }, b'.') => {
-                    return Err(());
-                }
-                (Subsequent { .. }, b'.') => Next,
-                (NumericOnly { .. }, b'.') => NextAfterNumericOnly,
-                (Subsequent { len } | NumericOnly { len } | Hyphen { len }, _)
-                    if len >= MAX_LABEL_LENGTH =>
-                {
-                    return Err(());
-                }
-                (Start | Next | NextAfterNumericOnly, b'0'..=b'9') => NumericOnly { len: 1 },
-                (NumericOnly { len }, b'0'..=b'9') => NumericOnly { len: len + 1 },
-                (Start | Next | NextAfterNumericOnly, b'a'..=b'z' | b'A'..=b'Z' | b'_') => {
-                    Subsequent { len: 1 }
-                }
-                (Subsequent { len } | NumericOnly { len } | Hyphen { len }, b'-') => {
-                    Hyphen { len: len + 1 }
+        let (addr, trailing_dot) = match options.trailing_dot {
+            TrailingDotPolicy::Reject => (Cow::Borrowed(addr), false),
+            TrailingDotPolicy::Strip => strip_trailing_dot(addr),
+        };
+
+        let mut addr = match options.default_port {
+            Some(default_port) => Self::new_with_default_port(&addr, default_port)?,
+            None => Self::new(&addr)?,
+        };
+
+        // Only host names may have a trailing dot.
+        if trailing_dot && !matches!(addr.as_inner(), UniAddrInner::Host(_)) {
+            return Err(ParseError::InvalidHost);
+        }
+
+        if options.lowercase_host {
+            if let UniAddrInner::Host(host) = addr.as_inner() {
+                if host.bytes().any(|b| b.is_ascii_uppercase()) {
+                    addr =
+                        Self::from_inner(UniAddrInner::Host(Arc::from(host.to_ascii_lowercase())));
                 }
-                (
-                    Subsequent { len } | NumericOnly { len } | Hyphen { len },
-                    b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'0'..=b'9',
-                ) => Subsequent { len: len + 1 },
-                _ => return Err(()),
-            };
+            }
+        }
+
+        if !options.allow_host && matches!(addr.as_inner(), UniAddrInner::Host(_)) {
+            return Err(ParseError::InvalidHost);
+        }
+
+        Ok(addr)
+    }
+
+    #[cfg(unix)]
+    /// Resolves a network interface name with port, like `eth0:8080`, into
+    /// socket addresses of the current IPv4 and IPv6 addresses of that
+    /// interface, e.g., for servers listening on one NIC only.
+    ///
+    /// IPv6 link-local addresses are scoped to the interface. Filter the
+    /// results by [`UniAddr::family`] to bind to one address family only.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use uni_addr::UniAddr;
+    /// for addr in UniAddr::new_interface("eth0:8080").unwrap() {
+    ///     println!("listening on {addr}");
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// - The port is missing or invalid, see [`ParseError::InvalidPort`].
+    /// - There is no such interface.
+    /// - See `getifaddrs(3)`.
+    pub fn new_interface(addr: &str) -> io::Result<Vec<Self>> {
+        let Some((interface, port)) = addr.rsplit_once(':') else {
+            return Err(ParseError::InvalidPort.into());
+        };
+
+        let Ok(port) = port.parse::<u16>() else {
+            return Err(ParseError::InvalidPort.into());
+        };
+
+        unix::interface_addrs(interface, port)
+            .map(|addrs| addrs.into_iter().map(Self::from).collect())
+    }
+
+    /// Tolerantly parses a control string like `metrics.local:8125/myprefix`,
+    /// returning the address and the remainder after the first `/` following
+    /// the authority, if any.
+    ///
+    /// Unix domain socket and named pipe addresses are paths themselves, so
+    /// they are never split and the remainder is always `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let (addr, rest) = UniAddr::split_authority("metrics.local:8125/myprefix").unwrap();
+    ///
+    /// assert_eq!(addr.to_str(), "metrics.local:8125");
+    /// assert_eq!(rest, Some("myprefix"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The part before the remainder is not a valid address. See
+    /// [`UniAddr::new`].
+    pub fn split_authority(addr: &str) -> Result<(Self, Option<&str>), ParseError> {
+        if addr.starts_with(UNIX_URI_PREFIX) || addr.starts_with(PIPE_URI_PREFIX) {
+            return Self::new(addr).map(|addr| (addr, None));
+        }
+
+        // The scheme, if any, contains slashes itself.
+        let mut scheme_len = 0;
+
+        for prefix in [TCP_URI_PREFIX, DNS_URI_PREFIX, FD_URI_PREFIX] {
+            if addr.starts_with(prefix) {
+                scheme_len = prefix.len();
+            }
+        }
+
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        if addr.starts_with(VSOCK_URI_PREFIX) {
+            scheme_len = VSOCK_URI_PREFIX.len();
+        }
+
+        match addr[scheme_len..].split_once('/') {
+            Some((authority, rest)) => {
+                Self::new(&addr[..scheme_len + authority.len()]).map(|addr| (addr, Some(rest)))
+            }
+            None => Self::new(addr).map(|addr| (addr, None)),
+        }
+    }
+
+    /// Creates a new [`UniAddr`] from a string containing a host name and port,
+    /// like `example.com:8080`.
+    ///
+    /// # Errors
+    ///
+    /// - [`ParseError::InvalidHost`] if the host name is invalid.
+    /// - [`ParseError::InvalidPort`] if the port is invalid.
+    #[deprecated(
+        note = "`parsed` is ignored now, use `UniAddr::new` or `UniAddr::new_host_parts` instead"
+    )]
+    pub fn new_host(addr: &str, parsed: Option<(&str, u16)>) -> Result<Self, ParseError> {
+        let _ = parsed;
+
+        let Some((hostname, port)) = addr.rsplit_once(':') else {
+            return Err(ParseError::InvalidPort);
+        };
+
+        if port.parse::<u16>().is_err() {
+            return Err(ParseError::InvalidPort);
+        }
+
+        Self::new_host_validated(addr, hostname)
+    }
+
+    /// Creates a new [`UniAddr`] from a host name and a port, like
+    /// `("example.com", 8080)`.
+    ///
+    /// # Errors
+    ///
+    /// [`ParseError::InvalidHost`] if the host name is invalid.
+    pub fn new_host_parts(hostname: &str, port: u16) -> Result<Self, ParseError> {
+        Self::new_host_validated(&format!("{hostname}:{port}"), hostname)
+    }
+
+    /// Creates a new [`UniAddr`] from its string representation, filling in
+    /// `default_port` if a host name or IP address comes without one, like
+    /// `example.com`, `192.0.2.1`, `::1` or `[::1]`.
+    ///
+    /// Fully specified inputs, and URIs like `unix:///path/to/socket`, are
+    /// parsed like [`UniAddr::new`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let addr = UniAddr::new_with_default_port("example.com", 443).unwrap();
+    /// assert_eq!(addr.to_str(), "example.com:443");
+    ///
+    /// let addr = UniAddr::new_with_default_port("example.com:8443", 443).unwrap();
+    /// assert_eq!(addr.to_str(), "example.com:8443");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string, see [`UniAddr::new`].
+    pub fn new_with_default_port(addr: &str, default_port: u16) -> Result<Self, ParseError> {
+        let addr = Self::new_optional_port(addr)?;
+
+        if addr.port().is_some() {
+            return Ok(addr);
+        }
+
+        // Other types of addresses have no port at all.
+        Ok(addr.with_port(default_port).unwrap_or(addr))
+    }
+
+    /// Creates a new [`UniAddr`] from its string representation, where the
+    /// port of a host name or IP address is optional, like `example.com`,
+    /// `192.0.2.1`, `::1` or `[::1]`.
+    ///
+    /// A port-less address is kept as [`UniAddrInner::Host`] without a port
+    /// (IPv6 addresses in brackets): [`UniAddr::port`] returns `None`, and
+    /// [`UniAddr::with_port`] fills one in. Other inputs are parsed like
+    /// [`UniAddr::new`] does.
+    ///
+    /// A port-less address is not a valid address string for
+    /// [`UniAddr::new`], so serde uses a map like `{ host = "example.com" }`
+    /// for it instead. Resolving it fails, fill in a port first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let addr = UniAddr::new_optional_port("::1").unwrap();
+    /// assert_eq!(addr.to_str(), "[::1]");
+    /// assert_eq!(addr.port(), None);
+    ///
+    /// let addr = addr.with_port(8080).unwrap();
+    /// assert_eq!(addr.to_str(), "[::1]:8080");
+    /// assert_eq!(addr.port(), Some(8080));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string, see [`UniAddr::new`].
+    pub fn new_optional_port(addr: &str) -> Result<Self, ParseError> {
+        if addr.contains("://") {
+            return Self::new(addr);
+        }
+
+        // A bare IPv6 address contains colons itself.
+        let ip = match IpAddr::from_str(addr) {
+            Ok(ip) => ip,
+            Err(_) => match addr.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                // Keep the zone as is, resolved again when the port is filled in.
+                Some(ipv6_addr) if ipv6_addr.contains('%') => {
+                    let (ipv6_addr, _) = split_zone(ipv6_addr)?;
+
+                    Ipv6Addr::from_str(ipv6_addr).map_err(|_| ParseError::InvalidHost)?;
+
+                    return Ok(Self::from_inner(UniAddrInner::Host(Arc::from(addr))));
+                }
+                Some(ipv6_addr) => Ipv6Addr::from_str(ipv6_addr)
+                    .map_err(|_| ParseError::InvalidHost)?
+                    .into(),
+                None if addr.contains(':') => return Self::new(addr),
+                None if addr.is_empty() => return Err(ParseError::Empty),
+                None => return Self::new_host_validated(addr, addr),
+            },
+        };
+
+        let addr = match ip {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{ip}]"),
+        };
+
+        Ok(Self::from_inner(UniAddrInner::Host(Arc::from(addr))))
+    }
+
+    /// Creates a host name address from its string representation, i.e., a
+    /// host name with port, or a host name or an IP address without port, see
+    /// [`UniAddr::new_optional_port`].
+    ///
+    /// Unlike [`UniAddr::new`], this rejects any other type of address, and
+    /// never touches the file system, for decoding untrusted input.
+    pub(crate) fn from_host_str(addr: &str) -> Result<Self, ParseError> {
+        if let Ok(addr) = UniAddrRef::new_host(addr) {
+            return Ok(addr.to_owned());
+        }
+
+        // Never a URI, which `new_optional_port` parses with `UniAddr::new`.
+        if addr.contains("://") {
+            return Err(ParseError::InvalidHost);
+        }
+
+        let parsed = Self::new_optional_port(addr)?;
+
+        match parsed.as_inner() {
+            // Not a network socket address, nor another spelling of the IP
+            // address.
+            UniAddrInner::Host(host) if **host == *addr => Ok(parsed),
+            _ => Err(ParseError::InvalidHost),
+        }
+    }
+
+    /// Returns the port of a network socket address or a host name address,
+    /// or `None` if there is none, see [`UniAddr::new_optional_port`].
+    ///
+    /// Other types of addresses, including VM socket addresses whose ports are
+    /// 32-bit, have no such port either.
+    pub fn port(&self) -> Option<u16> {
+        match self.as_inner() {
+            UniAddrInner::Inet(addr) => Some(addr.port()),
+            UniAddrInner::Host(addr) => addr.rsplit_once(':')?.1.parse().ok(),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Returns the port like [`UniAddr::port`] does, for callers that need
+    /// one.
+    ///
+    /// # Errors
+    ///
+    /// [`ParseError::InvalidPort`] if there is no port.
+    pub fn require_port(&self) -> Result<u16, ParseError> {
+        self.port().ok_or(ParseError::InvalidPort)
+    }
+
+    /// Returns a copy of the network socket address or host name address with
+    /// the port set to `port`, or `None` for other types of addresses.
+    ///
+    /// A port-less IP address becomes a network socket address.
+    pub fn with_port(&self, port: u16) -> Option<Self> {
+        match self.as_inner() {
+            UniAddrInner::Inet(addr) => Some(Self::from(SocketAddr::new(addr.ip(), port))),
+            UniAddrInner::Host(addr) => {
+                let host = match self.port() {
+                    Some(_) => addr.rsplit_once(':')?.0,
+                    None => addr,
+                };
+
+                Self::new(&format!("{host}:{port}")).ok()
+            }
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Creates a new [`UniAddr`] from `addr`, whose port has been checked, after
+    /// validating its host name part `hostname`.
+    fn new_host_validated(addr: &str, hostname: &str) -> Result<Self, ParseError> {
+        #[cfg(feature = "feat-idna")]
+        if !hostname.is_ascii() {
+            let ascii = idna::to_ascii(hostname).ok_or(ParseError::InvalidHost)?;
+
+            // `addr` is the host name followed by the port, if any.
+            let addr = format!("{ascii}{}", &addr[hostname.len()..]);
+
+            return Self::new_host_validated(&addr, &ascii);
+        }
+
+        Self::validate_host_name(hostname.as_bytes()).map_err(|()| ParseError::InvalidHost)?;
+
+        Ok(Self::from_inner(UniAddrInner::Host(Arc::from(addr))))
+    }
+
+    /// Checks a host name without port, like
+    /// <https://github.com/rustls/pki-types/blob/b8c04aa6b7a34875e2c4a33edc9b78d31da49523/src/server_name.rs>:
+    /// dot-separated labels of letters, digits, `-` and `_`, where a label
+    /// is 1 to 63 bytes, neither starts nor ends with `-`, and the last label
+    /// is not all digits.
+    const fn validate_host_name(input: &[u8]) -> Result<(), ()> {
+        const LETTER: u8 = 1;
+        const DIGIT: u8 = 2;
+        const HYPHEN: u8 = 3;
+        const DOT: u8 = 4;
+
+        /// The class of each byte, 0 for the invalid ones.
+        const CLASSES: [u8; 256] = {
+            let mut classes = [0; 256];
+
+            let mut byte = 0;
+            while byte < 256 {
+                #[allow(clippy::cast_possible_truncation)]
+                let ch = byte as u8;
+
+                classes[byte] = match ch {
+                    b'a'..=b'z' | b'A'..=b'Z' | b'_' => LETTER,
+                    b'0'..=b'9' => DIGIT,
+                    b'-' => HYPHEN,
+                    b'.' => DOT,
+                    _ => 0,
+                };
+                byte += 1;
+            }
+
+            classes
+        };
+
+        /// "Labels must be 63 characters or less."
+        const MAX_LABEL_LENGTH: usize = 63;
+
+        /// <https://devblogs.microsoft.com/oldnewthing/20120412-00/?p=7873>
+        const MAX_NAME_LENGTH: usize = 253;
+
+        const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+        const HIGHS: u64 = u64::from_ne_bytes([0x80; 8]);
+
+        /// Sets the high bit of each byte of `word` in `lo..=hi`, given all
+        /// bytes are ASCII, so that no addition carries into the next byte.
+        const fn in_range(word: u64, lo: u8, hi: u8) -> u64 {
+            let ge_lo = word + (0x80 - lo as u64) * ONES;
+            let gt_hi = word + (0x7F - hi as u64) * ONES;
+
+            ge_lo & !gt_hi & HIGHS
+        }
+
+        if input.len() > MAX_NAME_LENGTH {
+            return Err(());
+        }
+
+        let mut label_len = 0;
+        let mut label_numeric = true;
+        let mut last_hyphen = false;
+
+        let mut idx = 0;
+        while idx < input.len() {
+            // Fast path: 8 letters or digits in a row, which is the common case.
+            if idx + 8 <= input.len() {
+                let word = u64::from_ne_bytes([
+                    input[idx],
+                    input[idx + 1],
+                    input[idx + 2],
+                    input[idx + 3],
+                    input[idx + 4],
+                    input[idx + 5],
+                    input[idx + 6],
+                    input[idx + 7],
+                ]);
+
+                if word & HIGHS == 0 {
+                    let digits = in_range(word, b'0', b'9');
+                    let letters = in_range(word, b'a', b'z')
+                        | in_range(word, b'A', b'Z')
+                        | in_range(word, b'_', b'_');
+
+                    if digits | letters == HIGHS {
+                        label_len += 8;
+                        if label_len > MAX_LABEL_LENGTH {
+                            return Err(());
+                        }
+
+                        label_numeric = label_numeric && digits == HIGHS;
+                        last_hyphen = false;
+                        idx += 8;
+                        continue;
+                    }
+                }
+            }
+
+            match CLASSES[input[idx] as usize] {
+                DOT => {
+                    if label_len == 0 || last_hyphen {
+                        return Err(());
+                    }
+
+                    label_len = 0;
+                    label_numeric = true;
+                    last_hyphen = false;
+                }
+                class => {
+                    if class == 0 || label_len >= MAX_LABEL_LENGTH {
+                        return Err(());
+                    }
+
+                    if class == HYPHEN && label_len == 0 {
+                        return Err(());
+                    }
+
+                    label_len += 1;
+                    label_numeric = label_numeric && class == DIGIT;
+                    last_hyphen = class == HYPHEN;
+                }
+            }
             idx += 1;
         }
 
-        if matches!(
-            state,
-            Start | Hyphen { .. } | NumericOnly { .. } | NextAfterNumericOnly | Next
-        ) {
+        // Empty, a trailing dot or hyphen, or an all-numeric last label.
+        if label_len == 0 || last_hyphen || label_numeric {
             return Err(());
         }
 
         Ok(())
     }
 
+    /// Checks if two addresses are equal, ignoring the ASCII case of host
+    /// names, as DNS does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let a = UniAddr::new("ExAmPlE.com:80").unwrap();
+    /// let b = UniAddr::new("example.com:80").unwrap();
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.eq_ignore_host_case(&b));
+    /// ```
+    pub fn eq_ignore_host_case(&self, other: &Self) -> bool {
+        match (self.as_inner(), other.as_inner()) {
+            (UniAddrInner::Host(a), UniAddrInner::Host(b)) => a.eq_ignore_ascii_case(b),
+            _ => self == other,
+        }
+    }
+
+    /// Applies the [`LocalhostPolicy`] to this address, returning the candidate
+    /// addresses in order of preference.
+    ///
+    /// Only the host name `localhost` (case-insensitive) is affected; any
+    /// other address is returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::{LocalhostPolicy, UniAddr};
+    /// let addr = UniAddr::new("localhost:8080").unwrap();
+    ///
+    /// assert_eq!(
+    ///     addr.normalize_localhost(LocalhostPolicy::Dual),
+    ///     [
+    ///         UniAddr::new("[::1]:8080").unwrap(),
+    ///         UniAddr::new("127.0.0.1:8080").unwrap(),
+    ///     ]
+    /// );
+    /// ```
+    pub fn normalize_localhost(&self, policy: LocalhostPolicy) -> Vec<Self> {
+        let port = match self.as_inner() {
+            UniAddrInner::Host(addr) => match addr.rsplit_once(':') {
+                Some((hostname, port)) if hostname.eq_ignore_ascii_case("localhost") => {
+                    port.parse::<u16>().ok()
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let Some(port) = port else {
+            return vec![self.clone()];
+        };
+
+        let v4 = || Self::from(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port));
+        let v6 = || Self::from(SocketAddr::new(Ipv6Addr::LOCALHOST.into(), port));
+
+        match policy {
+            LocalhostPolicy::Keep => vec![self.clone()],
+            LocalhostPolicy::Ipv4 => vec![v4()],
+            LocalhostPolicy::Ipv6 => vec![v6()],
+            LocalhostPolicy::Dual => vec![v6(), v4()],
+        }
+    }
+
     /// Resolves the address if it is a host name.
     ///
     /// By default, we utilize the method [`ToSocketAddrs::to_socket_addrs`]
@@ -326,6 +1307,8 @@ impl UniAddr {
     /// # Errors
     ///
     /// Resolution failure, or if no socket address resolved.
+    /// [`io::ErrorKind::InvalidInput`] for a host name address without port,
+    /// see [`UniAddr::new_optional_port`].
     pub fn blocking_resolve_socket_addrs(&mut self) -> io::Result<()> {
         self.blocking_resolve_socket_addrs_with(ToSocketAddrs::to_socket_addrs)
     }
@@ -336,12 +1319,18 @@ impl UniAddr {
     /// # Errors
     ///
     /// Resolution failure, or if no socket address resolved.
+    /// [`io::ErrorKind::InvalidInput`] for a host name address without port,
+    /// see [`UniAddr::new_optional_port`].
     pub fn blocking_resolve_socket_addrs_with<F, A>(&mut self, f: F) -> io::Result<()>
     where
         F: FnOnce(&str) -> io::Result<A>,
         A: Iterator<Item = SocketAddr>,
     {
         if let UniAddrInner::Host(addr) = self.as_inner() {
+            if self.port().is_none() {
+                return Err(missing_port());
+            }
+
             let resolved = f(addr)?.next().ok_or_else(|| {
                 io::Error::new(
                     io::ErrorKind::Other,
@@ -365,8 +1354,14 @@ impl UniAddr {
     /// # Errors
     ///
     /// Resolution failure, or if no socket address resolved.
+    /// [`io::ErrorKind::InvalidInput`] for a host name address without port,
+    /// see [`UniAddr::new_optional_port`].
     pub async fn resolve_socket_addrs(&mut self) -> io::Result<()> {
         if let UniAddrInner::Host(addr) = self.as_inner() {
+            if self.port().is_none() {
+                return Err(missing_port());
+            }
+
             let addr = addr.clone();
             let resolved = tokio::task::spawn_blocking(move || addr.to_socket_addrs())
                 .await??
@@ -384,226 +1379,2883 @@ impl UniAddr {
         Ok(())
     }
 
-    #[inline]
-    /// Serializes the address to a string.
-    pub fn to_str(&self) -> Cow<'_, str> {
-        self.as_inner().to_str()
+    /// Likes [`blocking_resolve_socket_addrs`], but retries failed resolutions
+    /// following the given [`RetryPolicy`](retry::RetryPolicy).
+    ///
+    /// Failures like `NXDOMAIN`, which is common when a service starts before
+    /// the DNS records propagate, are considered transient. Failures caused by
+    /// the address itself, like a host name without port
+    /// ([`io::ErrorKind::InvalidInput`]), are not retried.
+    ///
+    /// [`blocking_resolve_socket_addrs`]: Self::blocking_resolve_socket_addrs
+    ///
+    /// # Errors
+    ///
+    /// The last resolution failure once the deadline is reached, or a
+    /// permanent failure at once.
+    pub fn blocking_resolve_socket_addrs_with_retry(
+        &mut self,
+        policy: &retry::RetryPolicy,
+    ) -> io::Result<()> {
+        if let UniAddrInner::Host(_) = self.as_inner() {
+            *self = retry::blocking_retry(policy, || {
+                let mut addr = self.clone();
+
+                addr.blocking_resolve_socket_addrs().map(|()| addr)
+            })?;
+        }
+
+        Ok(())
     }
-}
 
-#[non_exhaustive]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-/// See [`UniAddr`].
-///
-/// Generally, you should use [`UniAddr`] instead of this type directly, as
-/// we expose this type only for easier pattern matching. A valid [`UniAddr`]
-/// can be constructed only through [`FromStr`] implementation.
-pub enum UniAddrInner {
-    /// See [`SocketAddr`].
-    Inet(SocketAddr),
+    #[cfg(feature = "feat-tokio")]
+    /// Likes [`resolve_socket_addrs`], but retries failed resolutions
+    /// following the given [`RetryPolicy`](retry::RetryPolicy).
+    ///
+    /// See [`blocking_resolve_socket_addrs_with_retry`] for more details.
+    ///
+    /// [`resolve_socket_addrs`]: Self::resolve_socket_addrs
+    /// [`blocking_resolve_socket_addrs_with_retry`]: Self::blocking_resolve_socket_addrs_with_retry
+    ///
+    /// # Errors
+    ///
+    /// The last resolution failure once the deadline is reached, or a
+    /// permanent failure at once.
+    pub async fn resolve_socket_addrs_with_retry(
+        &mut self,
+        policy: &retry::RetryPolicy,
+    ) -> io::Result<()> {
+        if let UniAddrInner::Host(_) = self.as_inner() {
+            *self = retry::retry(policy, || {
+                let mut addr = self.clone();
 
-    #[cfg(unix)]
-    /// See [`SocketAddr`](crate::unix::SocketAddr).
-    Unix(crate::unix::SocketAddr),
+                async move { addr.resolve_socket_addrs().await.map(|()| addr) }
+            })
+            .await?;
+        }
 
-    /// A host name with port.
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    /// Checks if the current process may bind this address, failing early with
+    /// an actionable error instead of a bare `EACCES` from the kernel.
     ///
-    /// Please refer to [`ToSocketAddrs`], and
-    /// [`UniAddr::blocking_resolve_socket_addrs`], etc to resolve the
-    /// address when needed.
-    Host(Arc<str>),
-}
+    /// Ports below 1024 (or `net.ipv4.ip_unprivileged_port_start` on Linux)
+    /// require root, or the `CAP_NET_BIND_SERVICE` capability on Linux. Other
+    /// addresses, and host names without a valid port, always pass.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::PermissionDenied`] if the port is privileged and the
+    /// process lacks the permission to bind it.
+    pub fn check_privileged_port(&self) -> io::Result<()> {
+        let port = match self.as_inner() {
+            UniAddrInner::Inet(addr) => addr.port(),
+            UniAddrInner::Host(addr) => match addr.rsplit_once(':').map(|(_, port)| port.parse()) {
+                Some(Ok(port)) => port,
+                _ => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
 
-impl fmt::Display for UniAddrInner {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.to_str().fmt(f)
+        unix::check_privileged_port(port)
     }
-}
 
-impl UniAddrInner {
     #[inline]
-    /// Serializes the address to a string.
-    pub fn to_str(&self) -> Cow<'_, str> {
-        match self {
-            Self::Inet(addr) => addr.to_string().into(),
-            #[cfg(unix)]
-            Self::Unix(addr) => addr
-                .to_os_string_impl(UNIX_URI_PREFIX, "@")
-                .to_string_lossy()
-                .to_string()
-                .into(),
-            Self::Host(host) => Cow::Borrowed(host),
-        }
+    /// Creates a datagram socket bound to this address.
+    ///
+    /// See [`StdDatagram::bind`](datagram::StdDatagram::bind).
+    ///
+    /// # Errors
+    ///
+    /// See [`StdDatagram::bind`](datagram::StdDatagram::bind).
+    pub fn bind_datagram(&self) -> io::Result<datagram::StdDatagram> {
+        datagram::StdDatagram::bind(self)
     }
-}
 
-#[derive(Debug)]
+    #[cfg(feature = "feat-multiaddr")]
+    /// Creates a new [`UniAddr`] from the text form of a libp2p-style
+    /// multiaddr.
+    ///
+    /// The supported subset is `/ip4/<ip>/tcp/<port>`, `/ip6/<ip>/tcp/<port>`,
+    /// `/dns/<host>/tcp/<port>` (also `dns4` and `dns6`, whose family hint is
+    /// dropped) and `/unix/<path>`, where the path is percent-encoded. An
+    /// `/ip6zone/<zone>` component may precede `/ip6`. Without
+    /// the `/tcp/<port>` part, it is a port-less address, see
+    /// [`new_optional_port`](Self::new_optional_port).
+    ///
+    /// # Errors
+    ///
+    /// - [`ParseError::Unsupported`] for other protocols.
+    /// - [`ParseError::InvalidHost`] / [`ParseError::InvalidPort`] if the
+    ///   address or the port is invalid.
+    /// - [`ParseError::InvalidUDSAddress`] if the Unix socket path is invalid.
+    pub fn from_multiaddr(addr: &str) -> Result<Self, ParseError> {
+        multiaddr::parse(addr)
+    }
+
+    #[cfg(feature = "feat-multiaddr")]
+    /// Returns the text form of the libp2p-style multiaddr of this address,
+    /// see [`from_multiaddr`](Self::from_multiaddr).
+    ///
+    /// Returns `None` for addresses outside the supported subset, e.g.,
+    /// abstract Unix socket addresses.
+    pub fn to_multiaddr(&self) -> Option<String> {
+        multiaddr::format(self)
+    }
+
+    #[cfg(feature = "feat-testing")]
+    /// Returns the `n`-th fake IPv4 address, for building synthetic fixtures.
+    ///
+    /// The addresses are deterministic and unique: `192.0.2.{n % 256}` (in
+    /// TEST-NET-1) with port `1024 + n / 256`. For `n` beyond the port range,
+    /// i.e., at least `256 * 64512`, see [`fake_inet6`](Self::fake_inet6).
+    pub fn fake_inet(n: u32) -> Self {
+        match u16::try_from(n / 256)
+            .ok()
+            .and_then(|port| port.checked_add(1024))
+        {
+            #[allow(clippy::cast_possible_truncation)]
+            Some(port) => Self::from(SocketAddr::new(
+                Ipv4Addr::new(192, 0, 2, n as u8).into(),
+                port,
+            )),
+            None => Self::fake_inet6(n),
+        }
+    }
+
+    #[cfg(feature = "feat-testing")]
+    /// Returns the `n`-th fake IPv6 address, `[2001:db8::{n}]:8080` (in the
+    /// documentation prefix), for building synthetic fixtures.
+    pub fn fake_inet6(n: u32) -> Self {
+        Self::from(SocketAddr::new(
+            Ipv6Addr::from(0x2001_0db8_0000_0000_0000_0000_0000_0000 | u128::from(n)).into(),
+            8080,
+        ))
+    }
+
+    #[cfg(feature = "feat-testing")]
+    #[allow(clippy::missing_panics_doc)]
+    /// Returns the `n`-th fake host name address, `host-{n}.example.com:443`
+    /// (in the domain reserved for documentation), for building synthetic
+    /// fixtures.
+    pub fn fake_host(n: u32) -> Self {
+        // The host name is always valid.
+        Self::new_host_parts(&format!("host-{n}.example.com"), 443).unwrap()
+    }
+
+    #[cfg(all(feature = "feat-testing", unix))]
+    #[allow(clippy::missing_panics_doc)]
+    /// Returns the `n`-th fake Unix socket address,
+    /// `{temp_dir}/uni-addr-fake-{n}.socket`, for building synthetic fixtures.
+    ///
+    /// Unlike [`UniAddr::new`], this never removes an existing file.
+    pub fn fake_unix(n: u32) -> Self {
+        let pathname = std::env::temp_dir().join(format!("uni-addr-fake-{n}.socket"));
+
+        // Fails only if the temporary directory path is too long.
+        Self::from(std::os::unix::net::SocketAddr::from_pathname(pathname).unwrap())
+    }
+
+    #[cfg(feature = "feat-otel")]
+    /// Returns the OpenTelemetry semantic-convention attributes describing
+    /// this address as the server side of a connection.
+    ///
+    /// - `server.address`: the IP address, host name or Unix socket path.
+    /// - `server.port`: the port, if any.
+    /// - `network.transport`: `unix` for Unix domain socket addresses,
+    ///   otherwise `tcp`.
+    /// - `network.type`: `ipv4` or `ipv6`, for IP addresses only.
+    ///
+    /// See [`otel`] for the attribute keys.
+    pub fn otel_attributes(&self) -> Vec<(&'static str, otel::AttributeValue<'_>)> {
+        otel::attributes(self)
+    }
+
+    #[cfg(feature = "feat-x509")]
+    /// Returns the subjectAltName entry a certificate must contain to be valid
+    /// for this address, i.e., a DNS name for a host name address and an IP
+    /// address for a network socket address.
+    ///
+    /// Returns `None` for Unix domain socket addresses.
+    pub fn as_san(&self) -> Option<x509::SubjectAltName<'_>> {
+        x509::as_san(self)
+    }
+
+    #[cfg(feature = "feat-x509")]
+    /// Checks if any of the given subjectAltName entries of a certificate is
+    /// valid for this address.
+    ///
+    /// See [`SubjectAltName::matches`](x509::SubjectAltName::matches).
+    pub fn matches_san<'a, I>(&self, sans: I) -> bool
+    where
+        I: IntoIterator<Item = x509::SubjectAltName<'a>>,
+    {
+        self.as_san()
+            .is_some_and(|expected| sans.into_iter().any(|san| san.matches(&expected)))
+    }
+
+    /// Encodes the address in the SOCKS5 wire format (RFC 1928): the address
+    /// type (`ATYP`), the address and the port in network byte order.
+    ///
+    /// IP addresses are encoded as `IPv4` / `IPv6` ones, and host names as
+    /// `DOMAINNAME` ones.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::InvalidInput`] for other types of addresses, or a host
+    /// name longer than 255 bytes.
+    pub fn to_socks5_bytes(&self) -> io::Result<Vec<u8>> {
+        let (mut bytes, port) = match self.as_inner() {
+            UniAddrInner::Inet(SocketAddr::V4(addr)) => {
+                ([&[0x01], &addr.ip().octets()[..]].concat(), addr.port())
+            }
+            UniAddrInner::Inet(SocketAddr::V6(addr)) => {
+                ([&[0x04], &addr.ip().octets()[..]].concat(), addr.port())
+            }
+            UniAddrInner::Host(addr) => {
+                let (host, port) = addr
+                    .rsplit_once(':')
+                    .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+                    .ok_or_else(|| io::Error::from(ParseError::InvalidPort))?;
+
+                // Valid host names are at most 253 bytes long.
+                let Ok(len) = u8::try_from(host.len()) else {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "host name too long for a SOCKS5 address",
+                    ));
+                };
+
+                ([&[0x03, len], host.as_bytes()].concat(), port)
+            }
+            #[allow(unreachable_patterns)]
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "only IP addresses and host names can be encoded as SOCKS5 addresses",
+                ))
+            }
+        };
+
+        bytes.extend_from_slice(&port.to_be_bytes());
+
+        Ok(bytes)
+    }
+
+    /// Decodes an address in the SOCKS5 wire format, see
+    /// [`to_socks5_bytes`](Self::to_socks5_bytes), returning it and the number
+    /// of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::UnexpectedEof`] if `bytes` is truncated.
+    /// - [`io::ErrorKind::InvalidData`] for an unknown address type or an
+    ///   invalid host name.
+    pub fn from_socks5_bytes(bytes: &[u8]) -> io::Result<(Self, usize)> {
+        let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated SOCKS5 address");
+
+        let (&atyp, rest) = bytes.split_first().ok_or_else(truncated)?;
+
+        let addr_len = match atyp {
+            0x01 => 4,
+            0x03 => 1 + usize::from(*rest.first().ok_or_else(truncated)?),
+            0x04 => 16,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unknown SOCKS5 address type",
+                ))
+            }
+        };
+
+        let (addr, port) = rest
+            .get(..addr_len + 2)
+            .ok_or_else(truncated)?
+            .split_at(addr_len);
+        let port = u16::from_be_bytes([port[0], port[1]]);
+
+        let addr = match (atyp, addr) {
+            (0x01, &[a, b, c, d]) => {
+                Self::from(SocketAddr::new(Ipv4Addr::new(a, b, c, d).into(), port))
+            }
+            (0x04, _) => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(addr);
+
+                Self::from(SocketAddr::new(Ipv6Addr::from(octets).into(), port))
+            }
+            // Some clients send IP addresses as domain names.
+            _ => std::str::from_utf8(&addr[1..])
+                .ok()
+                .and_then(|host| match host.parse::<IpAddr>() {
+                    Ok(ip) => Some(Self::from(SocketAddr::new(ip, port))),
+                    Err(_) => Self::new_host_parts(host, port).ok(),
+                })
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid SOCKS5 domain name")
+                })?,
+        };
+
+        Ok((addr, 1 + addr_len + 2))
+    }
+
+    /// Encodes the address in a stable, versioned binary format, for
+    /// embedding into custom protocols.
+    ///
+    /// Version 1 is a version byte `0x01`, a tag byte, and the payload of the
+    /// tag, with integers in network byte order:
+    ///
+    /// | Tag    | Address            | Payload                                   |
+    /// |--------|--------------------|-------------------------------------------|
+    /// | `0x01` | IPv4               | 4 octets, port (`u16`)                    |
+    /// | `0x02` | IPv6               | 16 octets, port (`u16`), flow info and scope ID (`u32`) |
+    /// | `0x03` | Unix, pathname     | length (`u16`), path bytes                |
+    /// | `0x04` | Unix, abstract     | length (`u16`), name bytes                |
+    /// | `0x05` | Unix, unnamed      | none                                      |
+    /// | `0x06` | VM socket          | CID (`u32`), port (`u32`)                 |
+    /// | `0x07` | File descriptor    | number (`i32`)                            |
+    /// | `0x08` | Windows named pipe | length (`u16`), UTF-8 path                |
+    /// | `0x09` | Host name          | length (`u16`), UTF-8 host name and port  |
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let addr = UniAddr::new("192.0.2.1:8080").unwrap();
+    /// let bytes = addr.to_wire_bytes();
+    ///
+    /// assert_eq!(bytes, [0x01, 0x01, 192, 0, 2, 1, 0x1f, 0x90]);
+    /// assert_eq!(UniAddr::from_wire_bytes(&bytes).unwrap(), (addr, 8));
+    /// ```
+    pub fn to_wire_bytes(&self) -> Vec<u8> {
+        wire::encode(self)
+    }
+
+    /// Decodes an address encoded by [`to_wire_bytes`](Self::to_wire_bytes),
+    /// returning it and the number of bytes consumed.
+    ///
+    /// # Errors
+    ///
+    /// - [`io::ErrorKind::UnexpectedEof`] if `bytes` is truncated.
+    /// - [`io::ErrorKind::InvalidData`] for an unknown version or address
+    ///   type, or an invalid address.
+    /// - [`io::ErrorKind::Unsupported`] if the type of the address is
+    ///   unsupported on this platform.
+    pub fn from_wire_bytes(bytes: &[u8]) -> io::Result<(Self, usize)> {
+        wire::decode(bytes)
+    }
+
+    /// Returns the address family of the socket this address is for.
+    ///
+    /// Host names, inherited file descriptors and named pipes have no known
+    /// family, see [`AddressFamily::Unspecified`].
+    pub const fn family(&self) -> AddressFamily {
+        match self.as_inner() {
+            UniAddrInner::Inet(SocketAddr::V4(_)) => AddressFamily::Inet,
+            UniAddrInner::Inet(SocketAddr::V6(_)) => AddressFamily::Inet6,
+            #[cfg(unix)]
+            UniAddrInner::Unix(_) => AddressFamily::Unix,
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrInner::Vsock(..) => AddressFamily::Vsock,
+            #[allow(unreachable_patterns)]
+            _ => AddressFamily::Unspecified,
+        }
+    }
+
+    #[inline]
+    /// Serializes the address to a string.
+    ///
+    /// This allocates for every type of address but a host name one, see
+    /// [`UniAddrInner::to_str`].
+    pub fn to_str(&self) -> Cow<'_, str> {
+        self.as_inner().to_str()
+    }
+
+    #[inline]
+    /// Returns an object that displays the address with the identifying part
+    /// masked, for logging client addresses where privacy rules forbid
+    /// recording them in full.
+    ///
+    /// - The last octet of an IPv4 address is masked, like `192.0.2.xxx:443`.
+    /// - An IPv6 address is truncated to its /48 prefix, like
+    ///   `[2001:db8:1::xxx]:443`.
+    /// - Only the file name of a Unix socket path is kept, like
+    ///   `unix://.../app.socket`.
+    ///
+    /// Loopback and unspecified IP addresses, host names and other addresses
+    /// are displayed as is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use uni_addr::UniAddr;
+    /// let addr = UniAddr::new("192.0.2.1:443").unwrap();
+    ///
+    /// assert_eq!(addr.display_redacted().to_string(), "192.0.2.xxx:443");
+    /// ```
+    pub const fn display_redacted(&self) -> Redacted<'_> {
+        Redacted(self)
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// See [`UniAddr`].
+///
+/// Generally, you should use [`UniAddr`] instead of this type directly, as
+/// we expose this type only for easier pattern matching. A valid [`UniAddr`]
+/// can be constructed only through [`FromStr`] implementation.
+pub enum UniAddrInner {
+    /// See [`SocketAddr`].
+    ///
+    /// The scope ID of an IPv6 address is kept in the string representation,
+    /// like `[fe80::1%2]:8080`, while the flow info is not.
+    Inet(SocketAddr),
+
+    #[cfg(unix)]
+    /// See [`SocketAddr`](crate::unix::SocketAddr).
+    ///
+    /// Shared rather than stored inline, as it embeds a whole `sockaddr_un`
+    /// of more than 100 bytes.
+    Unix(Arc<crate::unix::SocketAddr>),
+
+    #[cfg(all(
+        feature = "feat-vsock",
+        any(target_os = "android", target_os = "linux")
+    ))]
+    /// A VM socket (`AF_VSOCK`) address: the context ID (CID) and the port.
+    ///
+    /// See [`VSOCK_URI_PREFIX`].
+    Vsock(u32, u32),
+
+    #[cfg(unix)]
+    /// An inherited file descriptor number, like `fd://3`, handed down by
+    /// container runtimes or supervisors.
+    ///
+    /// With the `feat-systemd` feature on Linux, `systemd::bind_or_inherit`
+    /// turns it into a listener.
+    ///
+    /// See [`FD_URI_PREFIX`].
+    Fd(std::os::fd::RawFd),
+
+    #[cfg(windows)]
+    /// A Windows named pipe path, like `\\.\pipe\my-service`.
+    ///
+    /// See [`PIPE_URI_PREFIX`].
+    Pipe(Arc<str>),
+
+    /// A host name with port.
+    ///
+    /// Also a host name or IP address without port, only if parsed with
+    /// [`UniAddr::new_optional_port`].
+    ///
+    /// Please refer to [`ToSocketAddrs`], and
+    /// [`UniAddr::blocking_resolve_socket_addrs`], etc to resolve the
+    /// address when needed.
+    Host(Arc<str>),
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The address family of a [`UniAddr`], see [`UniAddr::family`].
+pub enum AddressFamily {
+    /// IPv4 (`AF_INET`).
+    Inet,
+
+    /// IPv6 (`AF_INET6`).
+    Inet6,
+
+    /// Unix domain sockets (`AF_UNIX`).
+    Unix,
+
+    #[cfg(all(
+        feature = "feat-vsock",
+        any(target_os = "android", target_os = "linux")
+    ))]
+    /// VM sockets (`AF_VSOCK`).
+    Vsock,
+
+    /// Not known until resolved or inspected, e.g., a host name.
+    Unspecified,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The transport requested by a URI prefix, see [`UniAddr::new_uri`].
+pub enum Transport {
+    /// A stream socket, i.e., TCP for `tcp://`.
+    Stream,
+
+    /// A datagram socket, i.e., UDP for `udp://`.
+    Datagram,
+}
+
+#[cfg(feature = "feat-socket2")]
+impl From<Transport> for socket2::Type {
+    fn from(transport: Transport) -> Self {
+        match transport {
+            Transport::Stream => Self::STREAM,
+            Transport::Datagram => Self::DGRAM,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Options controlling what [`UniAddr::parse_with`] accepts, so that
+/// applications can restrict what their configuration takes.
+pub struct ParseOptions {
+    unix: UnixPolicy,
+    allow_host: bool,
+    default_port: Option<u16>,
+    max_len: Option<usize>,
+    wildcard: WildcardPolicy,
+    trailing_dot: TrailingDotPolicy,
+    lowercase_host: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParseOptions {
+    #[inline]
+    /// Creates a new [`ParseOptions`] behaving like [`UniAddr::new`]: Unix
+    /// domain socket addresses and host names are allowed, the port is
+    /// required, the length is unlimited, and wildcard shorthands and trailing
+    /// dots are rejected.
+    pub const fn new() -> Self {
+        Self {
+            unix: UnixPolicy::Allow,
+            allow_host: true,
+            default_port: None,
+            max_len: None,
+            wildcard: WildcardPolicy::Reject,
+            trailing_dot: TrailingDotPolicy::Reject,
+            lowercase_host: false,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets how to treat `unix://` addresses.
+    pub const fn with_unix(mut self, unix: UnixPolicy) -> Self {
+        self.unix = unix;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets whether host names are allowed, or only IP addresses.
+    pub const fn with_allow_host(mut self, allow_host: bool) -> Self {
+        self.allow_host = allow_host;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Allows a missing port, filling in `default_port`, see
+    /// [`UniAddr::new_with_default_port`].
+    pub const fn with_default_port(mut self, default_port: u16) -> Self {
+        self.default_port = Some(default_port);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets the maximum length of the input in bytes.
+    pub const fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets how to treat the wildcard shorthands `*:PORT` and `:PORT`, meaning
+    /// all interfaces.
+    pub const fn with_wildcard(mut self, wildcard: WildcardPolicy) -> Self {
+        self.wildcard = wildcard;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets how to treat fully qualified host names with a trailing dot, like
+    /// `example.com.:443`.
+    pub const fn with_trailing_dot(mut self, trailing_dot: TrailingDotPolicy) -> Self {
+        self.trailing_dot = trailing_dot;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets whether host names are lowercased (ASCII only), so that addresses
+    /// differing in case only compare and hash equal, see also
+    /// [`UniAddr::eq_ignore_host_case`].
+    pub const fn with_lowercase_host(mut self, lowercase_host: bool) -> Self {
+        self.lowercase_host = lowercase_host;
+        self
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// How to treat `unix://` addresses, see [`ParseOptions::with_unix`].
+pub enum UnixPolicy {
+    #[default]
+    /// Accept them, like [`UniAddr::new`] does.
+    Allow,
+
+    /// Accept them, parsing abstract names in strict mode, rejecting empty
+    /// names and NULL bytes, see
+    /// [`unix::SocketAddr::new_strict`](crate::unix::SocketAddr::new_strict).
+    Strict,
+
+    /// Reject them.
+    Reject,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// How to treat the wildcard shorthands `*:PORT` and `:PORT`, see
+/// [`ParseOptions::with_wildcard`].
+pub enum WildcardPolicy {
+    #[default]
+    /// Reject them, like [`UniAddr::new`] does.
+    Reject,
+
+    /// Accept them as `0.0.0.0:PORT`.
+    Ipv4,
+
+    /// Accept them as `[::]:PORT`, which also accepts IPv4 connections unless
+    /// `IPV6_V6ONLY` is set on the socket.
+    DualStack,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// How to treat a fully qualified host name with a trailing dot, like
+/// `example.com.:443`, see [`ParseOptions::with_trailing_dot`].
+///
+/// The trailing dot is valid DNS, and prevents the resolver from trying the
+/// search domains.
+///
+/// There is no policy keeping the trailing dot, as every [`UniAddr`] must
+/// parse again with [`UniAddr::new`], which rejects it.
+pub enum TrailingDotPolicy {
+    #[default]
+    /// Reject it, like [`UniAddr::new`] does.
+    Reject,
+
+    /// Accept it, stripping the trailing dot.
+    Strip,
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// How to treat the host name `localhost`, see
+/// [`UniAddr::normalize_localhost`].
+///
+/// Services disagreeing on what `localhost` means is a common cause of
+/// connect failures, e.g., a server listening on `127.0.0.1` only while the
+/// client resolves `localhost` to `::1` first.
+pub enum LocalhostPolicy {
+    #[default]
+    /// Keep `localhost` as a host name, leaving it to the resolver.
+    Keep,
+
+    /// Use `127.0.0.1`.
+    Ipv4,
+
+    /// Use `::1`.
+    Ipv6,
+
+    /// Use both `::1` and `127.0.0.1`, in that order.
+    Dual,
+}
+
+#[cfg(feature = "feat-socket2")]
+impl TryFrom<AddressFamily> for socket2::Domain {
+    type Error = io::Error;
+
+    fn try_from(family: AddressFamily) -> Result<Self, Self::Error> {
+        match family {
+            AddressFamily::Inet => Ok(Self::IPV4),
+            AddressFamily::Inet6 => Ok(Self::IPV6),
+            AddressFamily::Unix => Ok(Self::UNIX),
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            AddressFamily::Vsock => Ok(Self::VSOCK),
+            // Not `AF_UNSPEC`, which no socket can be created with.
+            AddressFamily::Unspecified => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "The address has no known family to create a socket with",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for UniAddrInner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = match self {
+            Self::Inet(_) if f.alternate() => TCP_URI_PREFIX,
+            Self::Host(_) if f.alternate() => DNS_URI_PREFIX,
+            _ => "",
+        };
+
+        // Padding needs the whole string, prefix included, up front.
+        if f.width().is_some() || f.precision().is_some() {
+            return if prefix.is_empty() {
+                self.to_str().fmt(f)
+            } else {
+                f.pad(&format!("{prefix}{}", self.to_str()))
+            };
+        }
+
+        f.write_str(prefix)?;
+
+        match self {
+            Self::Inet(addr) => addr.fmt(f),
+            #[cfg(unix)]
+            Self::Unix(addr) => {
+                f.write_str(UNIX_URI_PREFIX)?;
+                addr.fmt_lossy(f)
+            }
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            Self::Vsock(cid, port) => write!(f, "{VSOCK_URI_PREFIX}{cid}:{port}"),
+            #[cfg(unix)]
+            Self::Fd(fd) => write!(f, "{FD_URI_PREFIX}{fd}"),
+            #[cfg(windows)]
+            Self::Pipe(path) => write!(f, "{PIPE_URI_PREFIX}{path}"),
+            Self::Host(host) => f.write_str(host),
+        }
+    }
+}
+
+impl UniAddrInner {
+    #[inline]
+    /// Serializes the address to a string.
+    ///
+    /// Only a host name address is borrowed. Every other type of address,
+    /// including a network socket address, is formatted into a newly
+    /// allocated string, since a [`Cow`] cannot borrow a stack buffer.
+    ///
+    /// On hot paths like logging, use [`Display`](fmt::Display) instead, which
+    /// writes into the formatter directly and never allocates, except for
+    /// padding with a width or precision.
+    pub fn to_str(&self) -> Cow<'_, str> {
+        match self {
+            Self::Host(host) => Cow::Borrowed(host),
+            _ => Cow::Owned(self.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A borrowed counterpart of [`UniAddr`], like `&str` to `String`.
+///
+/// [`UniAddrRef::new`] validates and classifies an address string like
+/// [`UniAddr::new`] does, without allocating, for hot paths like parsing a
+/// `Host` header per request. [`UniAddrRef::to_owned`] turns it into a
+/// [`UniAddr`] when it needs to be kept.
+///
+/// # Examples
+///
+/// ```rust
+/// # use uni_addr::{AddressFamily, UniAddr, UniAddrRef};
+/// let addr = UniAddrRef::new("example.com:443").unwrap();
+///
+/// assert_eq!(addr.family(), AddressFamily::Unspecified);
+/// assert_eq!(addr.to_owned(), UniAddr::new("example.com:443").unwrap());
+/// ```
+pub struct UniAddrRef<'a> {
+    addr: &'a str,
+    kind: UniAddrRefKind<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum UniAddrRefKind<'a> {
+    Inet(SocketAddr),
+
+    #[cfg(unix)]
+    /// The part after [`UNIX_URI_PREFIX`].
+    Unix(&'a str),
+
+    #[cfg(all(
+        feature = "feat-vsock",
+        any(target_os = "android", target_os = "linux")
+    ))]
+    Vsock(u32, u32),
+
+    #[cfg(unix)]
+    Fd(std::os::fd::RawFd),
+
+    #[cfg(windows)]
+    /// The part after [`PIPE_URI_PREFIX`].
+    Pipe(&'a str),
+
+    /// The host name with port, as is, and the length of the host name.
+    Host {
+        addr: &'a str,
+        hostname_len: usize,
+    },
+}
+
+impl<'a> UniAddrRef<'a> {
+    /// Validates and classifies an address string, see [`UniAddr::new`].
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string.
+    pub fn new(addr: &'a str) -> Result<Self, ParseError> {
+        if addr.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        Self::classify(addr).map(|kind| Self { addr, kind })
+    }
+
+    /// Validates a host name with port, like [`UniAddrRef::new`] does,
+    /// rejecting any other type of address or a scheme prefix, for decoding
+    /// untrusted host name addresses.
+    pub(crate) fn new_host(addr: &'a str) -> Result<Self, ParseError> {
+        match Self::new(addr)? {
+            this @ Self {
+                kind: UniAddrRefKind::Host { addr: host, .. },
+                ..
+            } if host == addr => Ok(this),
+            _ => Err(ParseError::InvalidHost),
+        }
+    }
+
+    fn classify(addr: &'a str) -> Result<UniAddrRefKind<'a>, ParseError> {
+        #[cfg(unix)]
+        if let Some(path) = addr.strip_prefix(UNIX_URI_PREFIX) {
+            return unix::SocketAddr::new_keep_file(OsStr::new(path))
+                .map(|_| UniAddrRefKind::Unix(path))
+                .map_err(ParseError::InvalidUDSAddress);
+        }
+
+        #[cfg(not(unix))]
+        if let Some(_addr) = addr.strip_prefix(UNIX_URI_PREFIX) {
+            return Err(ParseError::Unsupported);
+        }
+
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        if let Some(addr) = addr.strip_prefix(VSOCK_URI_PREFIX) {
+            let Some((cid, port)) = addr.rsplit_once(':') else {
+                return Err(ParseError::InvalidPort);
+            };
+
+            let Ok(cid) = cid.parse::<u32>() else {
+                return Err(ParseError::InvalidHost);
+            };
+
+            let Ok(port) = port.parse::<u32>() else {
+                return Err(ParseError::InvalidPort);
+            };
+
+            return Ok(UniAddrRefKind::Vsock(cid, port));
+        }
+
+        #[cfg(unix)]
+        if let Some(fd) = addr.strip_prefix(FD_URI_PREFIX) {
+            return match fd.parse::<std::os::fd::RawFd>() {
+                Ok(fd) if fd >= 0 => Ok(UniAddrRefKind::Fd(fd)),
+                _ => Err(ParseError::InvalidHost),
+            };
+        }
+
+        #[cfg(not(unix))]
+        if let Some(_addr) = addr.strip_prefix(FD_URI_PREFIX) {
+            return Err(ParseError::Unsupported);
+        }
+
+        #[cfg(windows)]
+        if let Some(path) = addr.strip_prefix(PIPE_URI_PREFIX) {
+            return validate_pipe(path).map(|()| UniAddrRefKind::Pipe(path));
+        }
+
+        #[cfg(not(windows))]
+        if let Some(_addr) = addr.strip_prefix(PIPE_URI_PREFIX) {
+            return Err(ParseError::Unsupported);
+        }
+
+        // Exactly one scheme, never nested ones like `tcp://dns://`.
+        if let Some(addr) = addr.strip_prefix(TCP_URI_PREFIX) {
+            return Self::classify_host(addr);
+        }
+
+        if let Some(addr) = addr.strip_prefix(DNS_URI_PREFIX) {
+            return match Self::classify_host(addr)? {
+                kind @ UniAddrRefKind::Host { .. } => Ok(kind),
+                _ => Err(ParseError::InvalidHost),
+            };
+        }
+
+        Self::classify_host(addr)
+    }
+
+    /// Classifies a network socket address or a host name address, without
+    /// any scheme.
+    fn classify_host(addr: &'a str) -> Result<UniAddrRefKind<'a>, ParseError> {
+        let Some((host, port)) = addr.rsplit_once(':') else {
+            return Err(ParseError::InvalidPort);
+        };
+
+        let Ok(port) = port.parse::<u16>() else {
+            return Err(ParseError::InvalidPort);
+        };
+
+        // Short-circuit: IPv4 address starts with a digit.
+        if host.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            if let Ok(ip) = Ipv4Addr::from_str(host) {
+                return Ok(UniAddrRefKind::Inet(SocketAddr::V4(SocketAddrV4::new(
+                    ip, port,
+                ))));
+            }
+
+            // A host name may also start with a digit, see below.
+        }
+
+        // Short-circuit: if starts with '[' and ends with ']', may be an IPv6 address
+        // and can never be a host.
+        if let Some(ipv6_addr) = host.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            let (ipv6_addr, scope_id) = split_zone(ipv6_addr)?;
+
+            return Ipv6Addr::from_str(ipv6_addr)
+                .map(|ip| {
+                    UniAddrRefKind::Inet(SocketAddr::V6(SocketAddrV6::new(ip, port, 0, scope_id)))
+                })
+                .map_err(|_| ParseError::InvalidHost);
+        }
+
+        // Fallback: check if is a valid host name.
+        validate_host(host).map(|()| UniAddrRefKind::Host {
+            addr,
+            hostname_len: host.len(),
+        })
+    }
+
+    #[inline]
+    /// Returns the address string this was parsed from.
+    pub const fn as_str(&self) -> &'a str {
+        self.addr
+    }
+
+    /// Returns the address family of the socket this address is for, see
+    /// [`UniAddr::family`].
+    pub const fn family(&self) -> AddressFamily {
+        match self.kind {
+            UniAddrRefKind::Inet(SocketAddr::V4(_)) => AddressFamily::Inet,
+            UniAddrRefKind::Inet(SocketAddr::V6(_)) => AddressFamily::Inet6,
+            #[cfg(unix)]
+            UniAddrRefKind::Unix(_) => AddressFamily::Unix,
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrRefKind::Vsock(..) => AddressFamily::Vsock,
+            #[allow(unreachable_patterns)]
+            _ => AddressFamily::Unspecified,
+        }
+    }
+
+    #[inline]
+    /// Returns the network socket address, if this is one.
+    pub const fn as_socket_addr(&self) -> Option<SocketAddr> {
+        match self.kind {
+            UniAddrRefKind::Inet(addr) => Some(addr),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    /// Creates an owned [`UniAddr`] from this address.
+    ///
+    /// Unlike [`UniAddr::new`], this never removes the file of a Unix socket
+    /// pathname address.
+    pub fn to_owned(&self) -> UniAddr {
+        match self.kind {
+            UniAddrRefKind::Inet(addr) => UniAddr::from(addr),
+            #[cfg(unix)]
+            UniAddrRefKind::Unix(path) => match unix::SocketAddr::new_keep_file(OsStr::new(path)) {
+                Ok(addr) => UniAddr::from(addr),
+                // Validated by `new` already.
+                Err(_) => UniAddr::from(unix::SocketAddr::new_unnamed()),
+            },
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrRefKind::Vsock(cid, port) => UniAddr::new_vsock(cid, port),
+            #[cfg(unix)]
+            UniAddrRefKind::Fd(fd) => UniAddr::from_inner(UniAddrInner::Fd(fd)),
+            #[cfg(windows)]
+            UniAddrRefKind::Pipe(path) => UniAddr::from_inner(UniAddrInner::Pipe(Arc::from(path))),
+            UniAddrRefKind::Host { addr, hostname_len } => {
+                // A Unicode host name, converted with the `feat-idna` feature.
+                #[cfg(feature = "feat-idna")]
+                if !addr.is_ascii() {
+                    let (hostname, port) = addr.split_at(hostname_len);
+
+                    if let Some(ascii) = idna::to_ascii(hostname) {
+                        return UniAddr::from_inner(UniAddrInner::Host(Arc::from(format!(
+                            "{ascii}{port}"
+                        ))));
+                    }
+                }
+
+                #[cfg(not(feature = "feat-idna"))]
+                let _ = hostname_len;
+
+                UniAddr::from_inner(UniAddrInner::Host(Arc::from(addr)))
+            }
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a str> for UniAddrRef<'a> {
+    type Error = ParseError;
+
+    fn try_from(addr: &'a str) -> Result<Self, Self::Error> {
+        Self::new(addr)
+    }
+}
+
+impl From<UniAddrRef<'_>> for UniAddr {
+    fn from(addr: UniAddrRef<'_>) -> Self {
+        addr.to_owned()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// See [`UniAddr::display_redacted`].
+pub struct Redacted<'a>(&'a UniAddr);
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.as_inner() {
+            UniAddrInner::Inet(addr) if addr.ip().is_loopback() || addr.ip().is_unspecified() => {
+                addr.fmt(f)
+            }
+            UniAddrInner::Inet(SocketAddr::V4(addr)) => {
+                let [a, b, c, _] = addr.ip().octets();
+
+                write!(f, "{a}.{b}.{c}.xxx:{}", addr.port())
+            }
+            UniAddrInner::Inet(SocketAddr::V6(addr)) => {
+                let [a, b, c, ..] = addr.ip().segments();
+
+                write!(f, "[{a:x}:{b:x}:{c:x}::xxx]:{}", addr.port())
+            }
+            #[cfg(unix)]
+            UniAddrInner::Unix(addr) => match addr
+                .as_pathname()
+                .filter(|pathname| {
+                    pathname
+                        .parent()
+                        .is_some_and(|parent| parent != std::path::Path::new(""))
+                })
+                .and_then(std::path::Path::file_name)
+            {
+                Some(file_name) => {
+                    write!(f, "{UNIX_URI_PREFIX}.../{}", file_name.to_string_lossy())
+                }
+                None => self.0.fmt(f),
+            },
+            // A port-less IP address, see `UniAddr::new_optional_port`.
+            UniAddrInner::Host(host) => match parse_port_less_ip(host) {
+                Some((ip, _)) if ip.is_loopback() || ip.is_unspecified() => f.write_str(host),
+                Some((IpAddr::V4(ip), _)) => {
+                    let [a, b, c, _] = ip.octets();
+
+                    write!(f, "{a}.{b}.{c}.xxx")
+                }
+                Some((IpAddr::V6(ip), _)) => {
+                    let [a, b, c, ..] = ip.segments();
+
+                    write!(f, "[{a:x}:{b:x}:{c:x}::xxx]")
+                }
+                None => self.0.fmt(f),
+            },
+            #[allow(unreachable_patterns)]
+            _ => self.0.fmt(f),
+        }
+    }
+}
+
+/// Checks a host name without port, converting it to ASCII first if it is a
+/// Unicode one and the `feat-idna` feature is enabled.
+fn validate_host(hostname: &str) -> Result<(), ParseError> {
+    #[cfg(feature = "feat-idna")]
+    if !hostname.is_ascii() {
+        let ascii = idna::to_ascii(hostname).ok_or(ParseError::InvalidHost)?;
+
+        return UniAddr::validate_host_name(ascii.as_bytes()).map_err(|()| ParseError::InvalidHost);
+    }
+
+    UniAddr::validate_host_name(hostname.as_bytes()).map_err(|()| ParseError::InvalidHost)
+}
+
+#[cfg(windows)]
+/// Checks a named pipe path, see [`UniAddr::new_pipe`].
+fn validate_pipe(path: &str) -> Result<(), ParseError> {
+    /// "The entire pipe name string can be up to 256 characters long."
+    const MAX_PIPE_NAME_LENGTH: usize = 256;
+
+    if path.encode_utf16().count() > MAX_PIPE_NAME_LENGTH {
+        return Err(ParseError::InvalidHost);
+    }
+
+    let Some((server, name)) = path
+        .strip_prefix(r"\\")
+        .and_then(|path| path.split_once('\\'))
+    else {
+        return Err(ParseError::InvalidHost);
+    };
+
+    let Some(name) = name
+        .get(..5)
+        .filter(|prefix| prefix.eq_ignore_ascii_case(r"pipe\"))
+        .map(|_| &name[5..])
+    else {
+        return Err(ParseError::InvalidHost);
+    };
+
+    // The pipe name part can include any character other than a backslash.
+    if server.is_empty() || name.is_empty() || name.contains('\\') {
+        return Err(ParseError::InvalidHost);
+    }
+
+    Ok(())
+}
+
+/// Strips the trailing dot of the host name in `addr`, with or without port,
+/// returning whether there was one.
+fn strip_trailing_dot(addr: &str) -> (Cow<'_, str>, bool) {
+    if addr.contains("://") {
+        return (Cow::Borrowed(addr), false);
+    }
+
+    if let Some(hostname) = addr.strip_suffix('.') {
+        return (Cow::Borrowed(hostname), true);
+    }
+
+    match addr.rsplit_once(':') {
+        Some((hostname, port)) if hostname.ends_with('.') => (
+            Cow::Owned(format!("{}:{port}", &hostname[..hostname.len() - 1])),
+            true,
+        ),
+        _ => (Cow::Borrowed(addr), false),
+    }
+}
+
+/// Splits the zone off an IPv6 address like `fe80::1%eth0`, returning the
+/// address and the scope ID (0 if there is no zone).
+///
+/// A numeric zone is the scope ID itself, while an interface name is resolved
+/// to its index on Unix-like systems.
+fn split_zone(ipv6_addr: &str) -> Result<(&str, u32), ParseError> {
+    let Some((ipv6_addr, zone)) = ipv6_addr.split_once('%') else {
+        return Ok((ipv6_addr, 0));
+    };
+
+    if zone.is_empty() {
+        return Err(ParseError::InvalidHost);
+    }
+
+    if zone.bytes().all(|b| b.is_ascii_digit()) {
+        return zone
+            .parse()
+            .map(|scope_id| (ipv6_addr, scope_id))
+            .map_err(|_| ParseError::InvalidHost);
+    }
+
+    #[cfg(unix)]
+    return unix::interface_index(zone)
+        .map(|scope_id| (ipv6_addr, scope_id))
+        .map_err(|_| ParseError::InvalidHost);
+
+    #[cfg(not(unix))]
+    return Err(ParseError::InvalidHost);
+}
+
+/// The error resolving a port-less host name address, see
+/// [`UniAddr::new_optional_port`].
+fn missing_port() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "cannot resolve a host name address without port, see `UniAddr::with_port`",
+    )
+}
+
+#[cfg(any(
+    feature = "feat-multiaddr",
+    feature = "feat-otel",
+    feature = "feat-pin",
+    feature = "feat-x509"
+))]
+/// Splits a host name address like `example.com:8080` into the host name and
+/// the port, if any, see [`UniAddr::new_optional_port`].
+pub(crate) fn split_host_port(addr: &str) -> (&str, Option<u16>) {
+    match addr.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => (host, Some(port)),
+            // A port-less IPv6 address, like `[::1]`.
+            Err(_) => (addr, None),
+        },
+        None => (addr, None),
+    }
+}
+
+/// Parses a port-less IP address, like `192.0.2.1`, `[::1]` or `[fe80::1%2]`,
+/// see [`UniAddr::new_optional_port`], returning the IPv6 zone too, if any.
+pub(crate) fn parse_port_less_ip(host: &str) -> Option<(IpAddr, Option<&str>)> {
+    let Some(host) = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+    else {
+        return host.parse::<Ipv4Addr>().ok().map(|ip| (ip.into(), None));
+    };
+
+    match host.split_once('%') {
+        Some((ip, zone)) => Some((ip.parse::<Ipv6Addr>().ok()?.into(), Some(zone))),
+        None => Some((host.parse::<Ipv6Addr>().ok()?.into(), None)),
+    }
+}
+
+#[cfg(any(feature = "feat-pin", feature = "feat-sni", feature = "feat-x509"))]
+/// Checks if the DNS name `pattern` matches `name`, case-insensitively.
+///
+/// A wildcard label `*` is only allowed as the complete leftmost label of the
+/// pattern and matches exactly one label of the name.
+pub(crate) fn matches_dns_name(pattern: &str, name: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => name
+            .split_once('.')
+            .is_some_and(|(label, rest)| !label.is_empty() && rest.eq_ignore_ascii_case(suffix)),
+        None => pattern.eq_ignore_ascii_case(name),
+    }
+}
+
+#[derive(Debug)]
 /// Errors that can occur when parsing a [`UniAddr`] from a string.
 pub enum ParseError {
     /// Empty input string
     Empty,
 
-    /// Invalid or missing hostname, or an invalid Ipv4 / IPv6 address
-    InvalidHost,
+    /// Invalid or missing hostname, or an invalid Ipv4 / IPv6 address
+    InvalidHost,
+
+    /// Invalid address format: missing or invalid port
+    InvalidPort,
+
+    /// Invalid UDS address format
+    InvalidUDSAddress(io::Error),
+
+    /// Unsupported address type on this platform
+    Unsupported,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty address string"),
+            Self::InvalidHost => write!(f, "invalid or missing host address"),
+            Self::InvalidPort => write!(f, "invalid or missing port"),
+            Self::InvalidUDSAddress(err) => write!(f, "invalid UDS address: {err}"),
+            Self::Unsupported => write!(f, "unsupported address type on this platform"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidUDSAddress(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for io::Error {
+    fn from(value: ParseError) -> Self {
+        io::Error::new(io::ErrorKind::Other, value)
+    }
+}
+
+/// A trait for objects which can be converted to one or more [`UniAddr`]s,
+/// like [`ToSocketAddrs`].
+///
+/// Functions binding or connecting sockets accept any type implementing this
+/// trait, and try each address in turn. Host names are not resolved here.
+pub trait ToUniAddrs {
+    /// Returned iterator over the addresses.
+    type Iter: Iterator<Item = UniAddr>;
+
+    /// Converts this object to an iterator of [`UniAddr`]s.
+    ///
+    /// # Errors
+    ///
+    /// Not a valid address string.
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter>;
+}
+
+impl ToUniAddrs for UniAddr {
+    type Iter = std::option::IntoIter<UniAddr>;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(Some(self.clone()).into_iter())
+    }
+}
+
+impl ToUniAddrs for SocketAddr {
+    type Iter = std::option::IntoIter<UniAddr>;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(Some(UniAddr::from(*self)).into_iter())
+    }
+}
+
+#[cfg(unix)]
+impl ToUniAddrs for unix::SocketAddr {
+    type Iter = std::option::IntoIter<UniAddr>;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(Some(UniAddr::from(self.clone())).into_iter())
+    }
+}
+
+impl ToUniAddrs for str {
+    type Iter = std::option::IntoIter<UniAddr>;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(Some(UniAddr::new(self)?).into_iter())
+    }
+}
+
+impl ToUniAddrs for String {
+    type Iter = std::option::IntoIter<UniAddr>;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        self.as_str().to_uni_addrs()
+    }
+}
+
+impl ToUniAddrs for [UniAddr] {
+    type Iter = std::vec::IntoIter<UniAddr>;
+
+    #[allow(clippy::unnecessary_to_owned)]
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        // The iterator cannot borrow from `self`.
+        Ok(self.to_vec().into_iter())
+    }
+}
+
+impl ToUniAddrs for Vec<UniAddr> {
+    type Iter = std::vec::IntoIter<UniAddr>;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        self.as_slice().to_uni_addrs()
+    }
+}
+
+impl<T: ToUniAddrs + ?Sized> ToUniAddrs for &T {
+    type Iter = T::Iter;
+
+    fn to_uni_addrs(&self) -> io::Result<Self::Iter> {
+        (**self).to_uni_addrs()
+    }
+}
+
+/// Calls `f` with each address of `addr` in turn, returning the first success
+/// or the last error.
+pub(crate) fn each_addr<A, F, T>(addr: A, mut f: F) -> io::Result<T>
+where
+    A: ToUniAddrs,
+    F: FnMut(&UniAddr) -> io::Result<T>,
+{
+    let mut last_err = None;
+
+    for addr in addr.to_uni_addrs()? {
+        match f(&addr) {
+            Ok(value) => return Ok(value),
+            Err(err) => last_err = Some(err),
+        }
+    }
+
+    Err(last_err.unwrap_or_else(no_addresses))
+}
+
+/// The error returned when a [`ToUniAddrs`] yields no addresses.
+pub(crate) fn no_addresses() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "could not resolve to any addresses",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("0.0.0.0:0")]
+    #[case("0.0.0.0:8080")]
+    #[case("127.0.0.1:0")]
+    #[case("127.0.0.1:8080")]
+    #[case("[::]:0")]
+    #[case("[::]:8080")]
+    #[case("[::1]:0")]
+    #[case("[::1]:8080")]
+    #[case("example.com:8080")]
+    #[case("1example.com:8080")]
+    #[cfg_attr(unix, case("unix://"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case("unix://@")
+    )]
+    #[cfg_attr(unix, case("unix:///tmp/test_UniAddr_new_Display.socket"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case("unix://@test_UniAddr_new_Display.socket")
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        case("vsock://2:1234")
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        case("vsock://4294967295:4294967295")
+    )]
+    #[cfg_attr(unix, case("fd://0"))]
+    #[cfg_attr(unix, case("fd://3"))]
+    #[cfg_attr(windows, case(r"pipe://\\.\pipe\my-service"))]
+    #[cfg_attr(windows, case(r"pipe://\\remote-host\PIPE\my-service"))]
+    fn test_UniAddr_new_Display(#[case] addr: &str) {
+        let addr_displayed = UniAddr::new(addr).unwrap().to_string();
+
+        assert_eq!(
+            addr_displayed, addr,
+            "addr_displayed {addr_displayed:?} != {addr:?}"
+        );
+    }
+
+    #[rstest]
+    #[case("example.com:8080")]
+    #[case("1example.com:8080")]
+    #[should_panic]
+    #[case::panic("1example.com")]
+    #[should_panic]
+    #[case::panic("1example.com.")]
+    #[should_panic]
+    #[case::panic("1example.com.:14514")]
+    #[should_panic]
+    #[case::panic("1example.com:1919810")]
+    #[should_panic]
+    #[case::panic("this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name:19810")]
+    fn test_UniAddr_new_host(#[case] addr: &str) {
+        #[allow(deprecated)]
+        let addr_displayed = UniAddr::new_host(addr, None).unwrap().to_string();
+
+        assert_eq!(
+            addr_displayed, addr,
+            "addr_displayed {addr_displayed:?} != {addr:?}"
+        );
+    }
+
+    #[rstest]
+    #[case("example.com", 8080)]
+    #[case("1example.com", 0)]
+    #[should_panic]
+    #[case::panic("", 8080)]
+    #[should_panic]
+    #[case::panic("1example.com.", 8080)]
+    #[should_panic]
+    #[case::panic("example.com:8080", 8080)]
+    fn test_UniAddr_new_host_parts(#[case] hostname: &str, #[case] port: u16) {
+        let addr = UniAddr::new_host_parts(hostname, port).unwrap();
+
+        assert_eq!(addr.to_string(), format!("{hostname}:{port}"));
+        assert!(matches!(addr.as_inner(), UniAddrInner::Host(_)));
+    }
+
+    #[rstest]
+    #[should_panic]
+    #[case::panic("")]
+    #[should_panic]
+    #[case::panic("not-an-address")]
+    #[should_panic]
+    #[case::panic("127.0.0.1")]
+    #[should_panic]
+    #[case::panic("127.0.0.1:99999")]
+    #[should_panic]
+    #[case::panic("127.0.0.256:99999")]
+    #[should_panic]
+    #[case::panic("::1")]
+    #[should_panic]
+    #[case::panic("[::1]")]
+    #[should_panic]
+    #[case::panic("[::1]:99999")]
+    #[should_panic]
+    #[case::panic("[::gg]:99999")]
+    #[should_panic]
+    #[case::panic("example.com")]
+    #[should_panic]
+    #[case::panic("example.com:99999")]
+    #[should_panic]
+    #[case::panic("examp😀le.com:99999")]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        should_panic,
+        case::panic("vsock://2")
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        should_panic,
+        case::panic("vsock://host:1234")
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        should_panic,
+        case::panic("vsock://2:port")
+    )]
+    #[cfg_attr(unix, should_panic, case::panic("fd://"))]
+    #[cfg_attr(unix, should_panic, case::panic("fd://-1"))]
+    #[cfg_attr(unix, should_panic, case::panic("fd://stdin"))]
+    #[cfg_attr(windows, should_panic, case::panic("pipe://"))]
+    #[cfg_attr(windows, should_panic, case::panic(r"pipe://my-service"))]
+    #[cfg_attr(windows, should_panic, case::panic(r"pipe://\\.\my-service"))]
+    #[cfg_attr(windows, should_panic, case::panic(r"pipe://\\\pipe\my-service"))]
+    #[cfg_attr(windows, should_panic, case::panic(r"pipe://\\.\pipe\"))]
+    #[cfg_attr(windows, should_panic, case::panic(r"pipe://\\.\pipe\my\service"))]
+    fn test_UniAddr_new_invalid(#[case] addr: &str) {
+        let _ = UniAddr::new(addr).unwrap();
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:8125", "127.0.0.1:8125", None)]
+    #[case("metrics.local:8125/myprefix", "metrics.local:8125", Some("myprefix"))]
+    #[case("[::1]:8125/a/b", "[::1]:8125", Some("a/b"))]
+    #[case("metrics.local:8125/", "metrics.local:8125", Some(""))]
+    #[cfg_attr(
+        unix,
+        case(
+            "unix:///tmp/test_UniAddr_split_authority.socket",
+            "unix:///tmp/test_UniAddr_split_authority.socket",
+            None
+        )
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        case("vsock://2:1234/myprefix", "vsock://2:1234", Some("myprefix"))
+    )]
+    #[cfg_attr(unix, case("fd://3/myprefix", "fd://3", Some("myprefix")))]
+    #[case("tcp://1.2.3.4:80/path", "1.2.3.4:80", Some("path"))]
+    #[case("dns://metrics.local:8125/a/b", "metrics.local:8125", Some("a/b"))]
+    #[should_panic]
+    #[case::panic("metrics.local/myprefix", "", None)]
+    #[case(
+        "tcp://metrics.local:8125/myprefix",
+        "metrics.local:8125",
+        Some("myprefix")
+    )]
+    #[should_panic]
+    #[case::panic("/myprefix", "", None)]
+    fn test_UniAddr_split_authority(
+        #[case] input: &str,
+        #[case] expected_addr: &str,
+        #[case] expected_rest: Option<&str>,
+    ) {
+        let (addr, rest) = UniAddr::split_authority(input).unwrap();
+
+        assert_eq!(addr.to_str(), expected_addr);
+        assert_eq!(rest, expected_rest);
+    }
+
+    #[test]
+    fn test_ToUniAddrs() {
+        let addr = UniAddr::new("127.0.0.1:8080").unwrap();
+
+        let expected = vec![addr.clone()];
+
+        assert_eq!(addr.to_uni_addrs().unwrap().collect::<Vec<_>>(), expected);
+        assert_eq!(
+            "127.0.0.1:8080".to_uni_addrs().unwrap().collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            String::from("127.0.0.1:8080")
+                .to_uni_addrs()
+                .unwrap()
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            SocketAddr::from(([127, 0, 0, 1], 8080))
+                .to_uni_addrs()
+                .unwrap()
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            expected.to_uni_addrs().unwrap().collect::<Vec<_>>(),
+            expected
+        );
+
+        let _ = "not-an-address".to_uni_addrs().unwrap_err();
+    }
+
+    #[test]
+    fn test_each_addr() {
+        let addrs = vec![
+            UniAddr::new("127.0.0.1:1").unwrap(),
+            UniAddr::new("127.0.0.1:2").unwrap(),
+        ];
+
+        let port = each_addr(&addrs, |addr| match addr.as_inner() {
+            UniAddrInner::Inet(addr) if addr.port() == 2 => Ok(addr.port()),
+            _ => Err(io::Error::new(io::ErrorKind::Other, "skipped")),
+        })
+        .unwrap();
+
+        assert_eq!(port, 2);
+
+        let err = each_addr(Vec::new(), |_| Ok(())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn test_UniAddr_new_unsupported() {
+        // Unix sockets should be unsupported on non-Unix platforms
+        let result = UniAddr::new("unix:///tmp/test.sock");
+
+        assert!(matches!(result.unwrap_err(), ParseError::Unsupported));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_UniAddr_new_pipe_unsupported() {
+        // Named pipes should be unsupported on non-Windows platforms
+        let result = UniAddr::new(r"pipe://\\.\pipe\my-service");
+
+        assert!(matches!(result.unwrap_err(), ParseError::Unsupported));
+    }
+
+    #[rstest]
+    #[case("0.0.0.0:0")]
+    #[case("0.0.0.0:8080")]
+    #[case("127.0.0.1:0")]
+    #[case("127.0.0.1:8080")]
+    #[case("[::]:0")]
+    #[case("[::]:8080")]
+    #[case("[::1]:0")]
+    #[case("[::1]:8080")]
+    #[cfg_attr(unix, case("unix:///tmp/test_socket2_sock_addr_conversion.socket"))]
+    #[cfg_attr(unix, case("unix://"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case("unix://@test_socket2_sock_addr_conversion.socket")
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        case("vsock://3:1234")
+    )]
+    fn test_socket2_SockAddr_conversion(#[case] addr: &str) {
+        let uni_addr = UniAddr::new(addr).unwrap();
+        let sock_addr = socket2::SockAddr::try_from(&uni_addr).unwrap();
+        let uni_addr_converted = UniAddr::try_from(sock_addr).unwrap();
+
+        assert_eq!(
+            uni_addr, uni_addr_converted,
+            "{uni_addr} != {uni_addr_converted}"
+        );
+    }
+
+    #[rstest]
+    #[case("192.0.2.1:443", &[0x01, 192, 0, 2, 1, 0x01, 0xBB])]
+    #[case(
+        "[2001:db8::1]:443",
+        &[0x04, 0x20, 0x01, 0x0D, 0xB8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01, 0x01, 0xBB]
+    )]
+    #[case("example.com:443", b"\x03\x0Bexample.com\x01\xBB")]
+    fn test_UniAddr_socks5_bytes(#[case] addr: &str, #[case] bytes: &[u8]) {
+        let addr = UniAddr::new(addr).unwrap();
+
+        assert_eq!(addr.to_socks5_bytes().unwrap(), bytes);
+
+        let mut stream = bytes.to_vec();
+        stream.extend_from_slice(b"payload");
+
+        assert_eq!(
+            UniAddr::from_socks5_bytes(&stream).unwrap(),
+            (addr, bytes.len())
+        );
+    }
+
+    #[rstest]
+    #[case(b"", io::ErrorKind::UnexpectedEof)]
+    #[case(b"\x01\xC0\x00\x02\x01\x01", io::ErrorKind::UnexpectedEof)]
+    #[case(b"\x03\x0Bexample", io::ErrorKind::UnexpectedEof)]
+    #[case(b"\x02\xC0\x00\x02\x01\x01\xBB", io::ErrorKind::InvalidData)]
+    #[case(b"\x03\x04a b.\x01\xBB", io::ErrorKind::InvalidData)]
+    fn test_UniAddr_from_socks5_bytes_invalid(#[case] bytes: &[u8], #[case] kind: io::ErrorKind) {
+        assert_eq!(UniAddr::from_socks5_bytes(bytes).unwrap_err().kind(), kind);
+    }
+
+    #[test]
+    fn test_UniAddr_to_socks5_bytes_too_long() {
+        let addr = UniAddr::from_inner(UniAddrInner::Host(Arc::from(format!(
+            "{}:80",
+            "a".repeat(256)
+        ))));
+
+        assert_eq!(
+            addr.to_socks5_bytes().unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_UniAddr_from_socks5_bytes_ip_domain() {
+        assert_eq!(
+            UniAddr::from_socks5_bytes(b"\x03\x09192.0.2.1\x01\xBB")
+                .unwrap()
+                .0,
+            UniAddr::new("192.0.2.1:443").unwrap()
+        );
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:8080", AddressFamily::Inet)]
+    #[case("[::1]:8080", AddressFamily::Inet6)]
+    #[case("example.com:8080", AddressFamily::Unspecified)]
+    #[cfg_attr(
+        unix,
+        case("unix:///tmp/test_UniAddr_family.socket", AddressFamily::Unix)
+    )]
+    #[cfg_attr(unix, case("fd://3", AddressFamily::Unspecified))]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        case("vsock://3:1234", AddressFamily::Vsock)
+    )]
+    fn test_UniAddr_family(#[case] addr: &str, #[case] expected: AddressFamily) {
+        let addr = UniAddr::new(addr).unwrap();
+
+        assert_eq!(addr.family(), expected);
+
+        #[cfg(feature = "feat-socket2")]
+        if let Ok(sock_addr) = socket2::SockAddr::try_from(&addr) {
+            assert_eq!(
+                socket2::Domain::try_from(expected).unwrap(),
+                sock_addr.domain()
+            );
+        }
+
+        #[cfg(feature = "feat-socket2")]
+        if expected == AddressFamily::Unspecified {
+            assert_eq!(
+                socket2::Domain::try_from(expected).unwrap_err().kind(),
+                io::ErrorKind::Unsupported
+            );
+        }
+    }
+
+    #[cfg(feature = "feat-testing")]
+    #[rstest]
+    #[case(0, "192.0.2.0:1024")]
+    #[case(257, "192.0.2.1:1025")]
+    #[case(256 * 64512 - 1, "192.0.2.255:65535")]
+    #[case(256 * 64512, "[2001:db8::fc:0]:8080")]
+    fn test_UniAddr_fake_inet(#[case] n: u32, #[case] expected: &str) {
+        assert_eq!(UniAddr::fake_inet(n).to_str(), expected);
+    }
+
+    #[cfg(feature = "feat-testing")]
+    #[test]
+    fn test_UniAddr_fake_unique() {
+        let mut seen = std::collections::HashSet::new();
+
+        for n in (0..1000).chain(256 * 64512 - 500..256 * 64512 + 500) {
+            assert!(seen.insert(UniAddr::fake_inet(n)));
+            assert!(seen.insert(UniAddr::fake_inet6(n + u32::MAX / 2)));
+            assert!(seen.insert(UniAddr::fake_host(n)));
+            #[cfg(unix)]
+            assert!(seen.insert(UniAddr::fake_unix(n)));
+        }
+
+        assert_eq!(UniAddr::fake_host(7).to_str(), "host-7.example.com:443");
+    }
+
+    #[rstest]
+    #[case("localhost:80", LocalhostPolicy::Keep, &["localhost:80"])]
+    #[case("localhost:80", LocalhostPolicy::Ipv4, &["127.0.0.1:80"])]
+    #[case("LocalHost:80", LocalhostPolicy::Ipv6, &["[::1]:80"])]
+    #[case("localhost:80", LocalhostPolicy::Dual, &["[::1]:80", "127.0.0.1:80"])]
+    #[case("localhost.example.com:80", LocalhostPolicy::Ipv4, &["localhost.example.com:80"])]
+    #[case("127.0.0.2:80", LocalhostPolicy::Ipv6, &["127.0.0.2:80"])]
+    #[cfg_attr(unix, case("unix:///tmp/localhost", LocalhostPolicy::Dual, &["unix:///tmp/localhost"]))]
+    fn test_UniAddr_normalize_localhost(
+        #[case] addr: &str,
+        #[case] policy: LocalhostPolicy,
+        #[case] expected: &[&str],
+    ) {
+        let normalized = UniAddr::new(addr).unwrap().normalize_localhost(policy);
+
+        assert_eq!(
+            normalized.iter().map(UniAddr::to_str).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:80", "tcp://127.0.0.1:80")]
+    #[case("[fe80::1%2]:80", "tcp://[fe80::1%2]:80")]
+    #[case("example.com:80", "dns://example.com:80")]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket", "unix:///tmp/test.socket"))]
+    #[cfg_attr(unix, case("fd://3", "fd://3"))]
+    fn test_UniAddr_display_alternate(#[case] input: &str, #[case] expected: &str) {
+        let addr = UniAddr::new(input).unwrap();
+
+        assert_eq!(format!("{addr}"), input);
+        assert_eq!(format!("{addr:#}"), expected);
+        assert_eq!(format!("{addr:>#30}"), format!("{expected:>30}"));
+        assert_eq!(format!("{addr:#.6}"), format!("{expected:.6}"));
+        assert_eq!(UniAddr::new(expected).unwrap(), addr);
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:80")]
+    #[case("[fe80::1%2]:80")]
+    #[case("example.com:80")]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket"))]
+    #[cfg_attr(unix, case("fd://3"))]
+    fn test_UniAddr_display_padding(#[case] input: &str) {
+        let addr = UniAddr::new(input).unwrap();
+
+        assert_eq!(format!("{addr:>32}"), format!("{input:>32}"));
+        assert_eq!(format!("{addr:.4}"), format!("{input:.4}"));
+    }
+
+    #[rstest]
+    #[case("dns://127.0.0.1:80")]
+    #[case("dns://example.com")]
+    #[case("tcp://")]
+    #[case("tcp://tcp://1.2.3.4:80")]
+    #[case("tcp://dns://example.com:80")]
+    #[case("dns://dns://example.com:80")]
+    #[case("dns://tcp://example.com:80")]
+    #[cfg_attr(unix, case("dns://unix:///tmp/test.socket"))]
+    #[cfg_attr(unix, case("tcp://fd://3"))]
+    fn test_UniAddr_new_scheme_qualified_invalid(#[case] input: &str) {
+        let _ = UniAddr::new(input).unwrap_err();
+    }
+
+    #[rstest]
+    #[case("tcp://127.0.0.1:4222", "127.0.0.1:4222", Some(Transport::Stream))]
+    #[case("udp://[::1]:514", "[::1]:514", Some(Transport::Datagram))]
+    #[case("tcp://example.com:4222", "example.com:4222", Some(Transport::Stream))]
+    #[case("example.com:4222", "example.com:4222", None)]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket", "unix:///tmp/test.socket", None))]
+    fn test_UniAddr_new_uri(
+        #[case] input: &str,
+        #[case] expected: &str,
+        #[case] transport: Option<Transport>,
+    ) {
+        let (addr, parsed) = UniAddr::new_uri(input).unwrap();
+
+        assert_eq!(addr.to_str(), expected);
+        assert_eq!(parsed, transport);
+    }
+
+    #[rstest]
+    #[case("tcp://")]
+    #[case("tcp://127.0.0.1")]
+    #[case("udp://-invalid:514")]
+    #[case("TCP://127.0.0.1:4222")]
+    #[cfg_attr(unix, case("tcp://unix:///tmp/test.socket"))]
+    #[cfg_attr(unix, case("udp://fd://3"))]
+    fn test_UniAddr_new_uri_invalid(#[case] input: &str) {
+        let _ = UniAddr::new_uri(input).unwrap_err();
+    }
+
+    #[rstest]
+    #[case("example.com", "example.com:443")]
+    #[case("example.com:8443", "example.com:8443")]
+    #[case("192.0.2.1", "192.0.2.1:443")]
+    #[case("192.0.2.1:8443", "192.0.2.1:8443")]
+    #[case("::1", "[::1]:443")]
+    #[case("[::1]", "[::1]:443")]
+    #[case("[::1]:8443", "[::1]:8443")]
+    #[case("1password.com", "1password.com:443")]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket", "unix:///tmp/test.socket"))]
+    fn test_UniAddr_new_with_default_port(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            UniAddr::new_with_default_port(input, 443).unwrap().to_str(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("-invalid")]
+    #[case("[example.com]")]
+    #[case("example.com:port")]
+    #[case("192.0.2.1:")]
+    fn test_UniAddr_new_with_default_port_invalid(#[case] input: &str) {
+        let _ = UniAddr::new_with_default_port(input, 443).unwrap_err();
+    }
+
+    #[rstest]
+    #[case("example.com", "example.com", None)]
+    #[case("example.com:8443", "example.com:8443", Some(8443))]
+    #[case("192.0.2.1", "192.0.2.1", None)]
+    #[case("192.0.2.1:8443", "192.0.2.1:8443", Some(8443))]
+    #[case("::1", "[::1]", None)]
+    #[case("[::1]", "[::1]", None)]
+    #[case("[::1]:8443", "[::1]:8443", Some(8443))]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket", "unix:///tmp/test.socket", None))]
+    fn test_UniAddr_new_optional_port(
+        #[case] input: &str,
+        #[case] expected: &str,
+        #[case] port: Option<u16>,
+    ) {
+        let addr = UniAddr::new_optional_port(input).unwrap();
+
+        assert_eq!(addr.to_str(), expected);
+        assert_eq!(addr.port(), port);
+        assert_eq!(addr.require_port().ok(), port);
+    }
+
+    #[rstest]
+    #[case("example.com", "example.com:80")]
+    #[case("example.com:8443", "example.com:80")]
+    #[case("192.0.2.1", "192.0.2.1:80")]
+    #[case("[::1]", "[::1]:80")]
+    #[case("[::1]:8443", "[::1]:80")]
+    fn test_UniAddr_with_port(#[case] input: &str, #[case] expected: &str) {
+        let addr = UniAddr::new_optional_port(input)
+            .unwrap()
+            .with_port(80)
+            .unwrap();
+
+        assert_eq!(addr, UniAddr::new(expected).unwrap());
+        assert_eq!(addr.port(), Some(80));
+    }
+
+    #[test]
+    fn test_UniAddr_resolve_optional_port() {
+        let mut addr = UniAddr::new_optional_port("localhost").unwrap();
+
+        let err = addr
+            .blocking_resolve_socket_addrs_with(|_| Ok(std::iter::empty()))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        // Permanent, so not retried until the deadline.
+        let err = addr
+            .blocking_resolve_socket_addrs_with_retry(&retry::RetryPolicy::new(
+                std::time::Duration::from_secs(30),
+            ))
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(addr, UniAddr::new_optional_port("localhost").unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_UniAddr_with_port_unsupported() {
+        let addr = UniAddr::new("unix:///tmp/test.socket").unwrap();
+
+        assert!(addr.with_port(80).is_none());
+        let _ = addr.require_port().unwrap_err();
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:8080", ParseOptions::new(), Some("127.0.0.1:8080"))]
+    #[case("example.com:8080", ParseOptions::new(), Some("example.com:8080"))]
+    #[case("example.com:8080", ParseOptions::new().with_allow_host(false), None)]
+    #[case("127.0.0.1:8080", ParseOptions::new().with_allow_host(false), Some("127.0.0.1:8080"))]
+    #[case("example.com", ParseOptions::new(), None)]
+    #[case("example.com", ParseOptions::new().with_default_port(443), Some("example.com:443"))]
+    #[case("example.com:8080", ParseOptions::new().with_max_len(16), Some("example.com:8080"))]
+    #[case("example.com:8080", ParseOptions::new().with_max_len(15), None)]
+    #[case(":8080", ParseOptions::new(), None)]
+    #[case("*:8080", ParseOptions::new(), None)]
+    #[case(":8080", ParseOptions::new().with_wildcard(WildcardPolicy::Ipv4), Some("0.0.0.0:8080"))]
+    #[case("*:8080", ParseOptions::new().with_wildcard(WildcardPolicy::Ipv4), Some("0.0.0.0:8080"))]
+    #[case("*:8080", ParseOptions::new().with_wildcard(WildcardPolicy::DualStack), Some("[::]:8080"))]
+    #[case("*:port", ParseOptions::new().with_wildcard(WildcardPolicy::Ipv4), None)]
+    #[case(
+        "::1",
+        ParseOptions::new().with_wildcard(WildcardPolicy::Ipv4).with_default_port(443),
+        Some("[::1]:443")
+    )]
+    #[case("ExAmPlE.com:443", ParseOptions::new(), Some("ExAmPlE.com:443"))]
+    #[case("ExAmPlE.com:443", ParseOptions::new().with_lowercase_host(true), Some("example.com:443"))]
+    #[case("example.com.:443", ParseOptions::new(), None)]
+    #[case(
+        "example.com.:443",
+        ParseOptions::new().with_trailing_dot(TrailingDotPolicy::Strip),
+        Some("example.com:443")
+    )]
+    #[case(
+        "example.com.",
+        ParseOptions::new()
+            .with_trailing_dot(TrailingDotPolicy::Strip)
+            .with_default_port(443),
+        Some("example.com:443")
+    )]
+    #[case(
+        "example.com:443",
+        ParseOptions::new().with_trailing_dot(TrailingDotPolicy::Strip),
+        Some("example.com:443")
+    )]
+    #[case(
+        "example.com..:443",
+        ParseOptions::new().with_trailing_dot(TrailingDotPolicy::Strip),
+        None
+    )]
+    #[case(
+        "192.0.2.1.:443",
+        ParseOptions::new().with_trailing_dot(TrailingDotPolicy::Strip),
+        None
+    )]
+    #[cfg_attr(
+        unix,
+        case(
+            "unix:///tmp/test.socket",
+            ParseOptions::new(),
+            Some("unix:///tmp/test.socket")
+        )
+    )]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket", ParseOptions::new().with_unix(UnixPolicy::Reject), None))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux"),
+        case("unix://@", ParseOptions::new(), Some("unix://@"))
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux"),
+        case("unix://@", ParseOptions::new().with_unix(UnixPolicy::Strict), None)
+    )]
+    fn test_UniAddr_parse_with(
+        #[case] input: &str,
+        #[case] options: ParseOptions,
+        #[case] expected: Option<&str>,
+    ) {
+        let parsed = UniAddr::parse_with(input, &options);
+
+        assert_eq!(
+            parsed.as_ref().ok().map(UniAddr::to_str).as_deref(),
+            expected,
+            "{parsed:?}"
+        );
+    }
+
+    #[rstest]
+    #[case("[fe80::1%2]:8080", 2)]
+    #[case("[fe80::1]:8080", 0)]
+    #[cfg_attr(unix, case("[fe80::1%lo]:8080", unix::interface_index("lo").unwrap()))]
+    fn test_UniAddr_new_ipv6_zone(#[case] input: &str, #[case] scope_id: u32) {
+        let expected = SocketAddrV6::new("fe80::1".parse().unwrap(), 8080, 0, scope_id);
+
+        assert_eq!(
+            UniAddr::new(input).unwrap(),
+            UniAddr::from(SocketAddr::V6(expected))
+        );
+    }
+
+    #[rstest]
+    #[case("[fe80::1%]:8080")]
+    #[case("[fe80::1%4294967296]:8080")]
+    #[case("[fe80::1%no-such-interface0]:8080")]
+    #[case("[fe80::1%2%3]:8080")]
+    fn test_UniAddr_new_ipv6_zone_invalid(#[case] input: &str) {
+        let _ = UniAddr::new(input).unwrap_err();
+    }
+
+    #[test]
+    fn test_UniAddr_new_optional_port_ipv6_zone() {
+        let addr = UniAddr::new_optional_port("[fe80::1%2]").unwrap();
+        assert_eq!(addr.port(), None);
+
+        let addr = addr.with_port(8080).unwrap();
+        assert_eq!(addr, UniAddr::new("[fe80::1%2]:8080").unwrap());
+    }
+
+    #[rstest]
+    #[case(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0), "[::1]:8080")]
+    #[case(SocketAddrV6::new("fe80::1".parse().unwrap(), 8080, 0, 2), "[fe80::1%2]:8080")]
+    #[case(SocketAddrV6::new("fe80::1".parse().unwrap(), 8080, 0, u32::MAX), "[fe80::1%4294967295]:8080")]
+    fn test_UniAddr_ipv6_scope_id_roundtrip(#[case] addr: SocketAddrV6, #[case] expected: &str) {
+        let addr = UniAddr::from(SocketAddr::V6(addr));
+
+        assert_eq!(addr.to_str(), expected);
+        assert_eq!(UniAddr::new(&addr.to_str()).unwrap(), addr);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_UniAddr_new_interface() {
+        let loopback = ["lo", "lo0"]
+            .into_iter()
+            .find(|name| unix::interface_index(name).is_ok())
+            .unwrap();
+
+        let addrs = UniAddr::new_interface(&format!("{loopback}:8080")).unwrap();
+        assert!(addrs.contains(&UniAddr::new("127.0.0.1:8080").unwrap()));
+
+        let _ = UniAddr::new_interface(loopback).unwrap_err();
+        let _ = UniAddr::new_interface("no-such-interface0:8080").unwrap_err();
+    }
+
+    #[cfg(feature = "feat-idna")]
+    #[rstest]
+    #[case("bücher.de:443", "xn--bcher-kva.de:443")]
+    #[case("例え.テスト:80", "xn--r8jz45g.xn--zckzah:80")]
+    fn test_UniAddr_new_idna(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(UniAddr::new(input).unwrap().to_str(), expected);
+        assert_eq!(
+            UniAddr::new_host_parts(input.rsplit_once(':').unwrap().0, 8080)
+                .unwrap()
+                .port(),
+            Some(8080)
+        );
+        assert_eq!(
+            UniAddr::new_optional_port(input.rsplit_once(':').unwrap().0)
+                .unwrap()
+                .to_str(),
+            expected.rsplit_once(':').unwrap().0
+        );
+    }
+
+    #[rstest]
+    #[case("ExAmPlE.com:80", "example.com:80", true)]
+    #[case("ExAmPlE.com:80", "example.com:8080", false)]
+    #[case("example.com:80", "example.org:80", false)]
+    #[case("127.0.0.1:80", "127.0.0.1:80", true)]
+    #[case("127.0.0.1:80", "localhost:80", false)]
+    fn test_UniAddr_eq_ignore_host_case(#[case] a: &str, #[case] b: &str, #[case] expected: bool) {
+        let (a, b) = (UniAddr::new(a).unwrap(), UniAddr::new(b).unwrap());
+
+        assert_eq!(a.eq_ignore_host_case(&b), expected);
+        assert_eq!(b.eq_ignore_host_case(&a), expected);
+    }
+
+    #[test]
+    fn test_UniAddr_eq_cross_type() {
+        let socket_addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+        let addr = UniAddr::from(socket_addr);
+
+        assert_eq!(addr, socket_addr);
+        assert_eq!(socket_addr, addr);
+        assert_ne!(addr, SocketAddr::from(([127, 0, 0, 1], 8081)));
+
+        assert_eq!(addr, (IpAddr::from([127, 0, 0, 1]), 8080));
+        assert_eq!((IpAddr::from([127, 0, 0, 1]), 8080), addr);
+
+        assert_eq!(addr, "127.0.0.1:8080");
+        assert_eq!("127.0.0.1:8080", addr);
+        assert_eq!(addr, *"127.0.0.1:8080");
+        assert_eq!(*"127.0.0.1:8080", addr);
+        assert_ne!(addr, "localhost:8080");
+
+        let addr = UniAddr::new("localhost:8080").unwrap();
+
+        assert_eq!(addr, "localhost:8080");
+        assert_ne!(addr, socket_addr);
+    }
+
+    #[test]
+    fn test_UniAddr_from_components() {
+        let v4 = UniAddr::new("127.0.0.1:8080").unwrap();
+        let v6 = UniAddr::new("[::1]:8080").unwrap();
+
+        assert_eq!(
+            UniAddr::from(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080)),
+            v4
+        );
+        assert_eq!(UniAddr::from((Ipv4Addr::LOCALHOST, 8080)), v4);
+        assert_eq!(UniAddr::from((IpAddr::from(Ipv4Addr::LOCALHOST), 8080)), v4);
+
+        assert_eq!(
+            UniAddr::from(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 0, 0)),
+            v6
+        );
+        assert_eq!(UniAddr::from((Ipv6Addr::LOCALHOST, 8080)), v6);
+        assert_eq!(UniAddr::from((IpAddr::from(Ipv6Addr::LOCALHOST), 8080)), v6);
+    }
+
+    #[rstest]
+    #[case("192.0.2.1:443", "192.0.2.xxx:443")]
+    #[case("[2001:db8:1:2::1]:443", "[2001:db8:1::xxx]:443")]
+    #[case("[fe80::1%2]:443", "[fe80:0:0::xxx]:443")]
+    #[case("127.0.0.1:8080", "127.0.0.1:8080")]
+    #[case("[::]:8080", "[::]:8080")]
+    #[case("example.com:443", "example.com:443")]
+    #[cfg_attr(unix, case("unix:///run/app/app.socket", "unix://.../app.socket"))]
+    #[cfg_attr(unix, case("unix://app.socket", "unix://app.socket"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux"),
+        case("unix://@app.socket", "unix://@app.socket")
+    )]
+    fn test_UniAddr_display_redacted(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            UniAddr::new(input).unwrap().display_redacted().to_string(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("192.0.2.1", "192.0.2.xxx")]
+    #[case("2001:db8:1:2::1", "[2001:db8:1::xxx]")]
+    #[case("[2001:db8:1:2::1]", "[2001:db8:1::xxx]")]
+    #[case("[fe80::1%2]", "[fe80:0:0::xxx]")]
+    #[case("127.0.0.1", "127.0.0.1")]
+    #[case("::1", "[::1]")]
+    #[case("example.com", "example.com")]
+    fn test_UniAddr_display_redacted_optional_port(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            UniAddr::new_optional_port(input)
+                .unwrap()
+                .display_redacted()
+                .to_string(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("127.0.0.1:8080", AddressFamily::Inet)]
+    #[case("[::1%2]:8080", AddressFamily::Inet6)]
+    #[case("1example.com:8080", AddressFamily::Unspecified)]
+    #[case("example.com:8080", AddressFamily::Unspecified)]
+    #[case("bücher.de:443", AddressFamily::Unspecified)]
+    #[case("tcp://127.0.0.1:8080", AddressFamily::Inet)]
+    #[case("dns://example.com:8080", AddressFamily::Unspecified)]
+    #[cfg_attr(unix, case("unix:///tmp/test-ref.socket", AddressFamily::Unix))]
+    #[cfg_attr(unix, case("fd://3", AddressFamily::Unspecified))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux"),
+        case("unix://@test-ref.socket", AddressFamily::Unix)
+    )]
+    fn test_UniAddrRef(#[case] input: &str, #[case] family: AddressFamily) {
+        let addr = UniAddrRef::new(input).unwrap();
+
+        assert_eq!(addr.as_str(), input);
+        assert_eq!(addr.family(), family);
+        assert_eq!(addr.to_owned(), UniAddr::new(input).unwrap());
+        assert_eq!(
+            addr.as_socket_addr().map(UniAddr::from),
+            matches!(addr.to_owned().as_inner(), UniAddrInner::Inet(_)).then(|| addr.to_owned())
+        );
+    }
+
+    #[rstest]
+    #[case("", ParseError::Empty)]
+    #[case("127.0.0.1", ParseError::InvalidPort)]
+    #[case("127.0.0.1:65536", ParseError::InvalidPort)]
+    #[case("-example.com:8080", ParseError::InvalidHost)]
+    #[case("[::1:8080", ParseError::InvalidHost)]
+    #[case("dns://127.0.0.1:8080", ParseError::InvalidHost)]
+    #[cfg_attr(unix, case("fd://-1", ParseError::InvalidHost))]
+    fn test_UniAddrRef_invalid(#[case] input: &str, #[case] expected: ParseError) {
+        assert_eq!(
+            UniAddrRef::new(input).unwrap_err().to_string(),
+            expected.to_string()
+        );
+        assert_eq!(
+            UniAddr::new(input).unwrap_err().to_string(),
+            expected.to_string()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_UniAddrRef_keeps_file() {
+        let path = "/tmp/test_UniAddrRef_keeps_file.socket";
+        std::fs::write(path, b"").unwrap();
+
+        let addr = UniAddrRef::new("unix:///tmp/test_UniAddrRef_keeps_file.socket")
+            .unwrap()
+            .to_owned();
+        assert_eq!(
+            addr.to_str(),
+            "unix:///tmp/test_UniAddrRef_keeps_file.socket"
+        );
+        assert!(std::path::Path::new(path).exists());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_UniAddr_validate_host_name_differential() {
+        const ALPHABET: &[u8] = b"aZ09-._ \xc3:";
+
+        let mut inputs: Vec<Vec<u8>> = vec![Vec::new()];
+
+        // Every input of up to 4 bytes over the alphabet.
+        let mut last = vec![Vec::new()];
+        for _ in 0..4 {
+            last = last
+                .iter()
+                .flat_map(|prefix| {
+                    ALPHABET.iter().map(move |byte| {
+                        let mut input = prefix.clone();
+                        input.push(*byte);
+                        input
+                    })
+                })
+                .collect();
+            inputs.extend(last.iter().cloned());
+        }
+
+        // And pseudo-random long ones, mostly of letters and digits to cover
+        // the fast path and the label length limits.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for _ in 0..20_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+
+            #[allow(clippy::cast_possible_truncation)]
+            let len = (state % 300) as usize;
+            let input = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+
+                    match state % 64 {
+                        0 => b'.',
+                        1 => b'-',
+                        2 => ALPHABET[(state >> 8) as usize % ALPHABET.len()],
+                        n if n < 20 => b'0' + (n % 10) as u8,
+                        n => b'a' + (n % 26) as u8,
+                    }
+                })
+                .collect();
+            inputs.push(input);
+        }
+
+        for len in [1, 7, 8, 9, 62, 63, 64, 65, 252, 253, 254] {
+            inputs.push(vec![b'a'; len]);
+            inputs.push(vec![b'1'; len]);
+            inputs.push([vec![b'a'; len], b".com".to_vec()].concat());
+        }
+
+        for input in inputs {
+            assert_eq!(
+                UniAddr::validate_host_name(&input),
+                validate_host_name_reference(&input),
+                "{:?}",
+                String::from_utf8_lossy(&input)
+            );
+        }
+    }
+
+    /// The per-byte state machine `UniAddr::validate_host_name` was, see
+    /// <https://github.com/rustls/pki-types/blob/b8c04aa6b7a34875e2c4a33edc9b78d31da49523/src/server_name.rs>.
+    const fn validate_host_name_reference(input: &[u8]) -> Result<(), ()> {
+        enum State {
+            Start,
+            Next,
+            NumericOnly { len: usize },
+            NextAfterNumericOnly,
+            Subsequent { len: usize },
+            Hyphen { len: usize },
+        }
+
+        use State::{Hyphen, Next, NextAfterNumericOnly, NumericOnly, Start, Subsequent};
 
-    /// Invalid address format: missing or invalid port
-    InvalidPort,
+        /// "Labels must be 63 characters or less."
+        const MAX_LABEL_LENGTH: usize = 63;
 
-    /// Invalid UDS address format
-    InvalidUDSAddress(io::Error),
+        /// <https://devblogs.microsoft.com/oldnewthing/20120412-00/?p=7873>
+        const MAX_NAME_LENGTH: usize = 253;
 
-    /// Unsupported address type on this platform
-    Unsupported,
-}
+        let mut state = Start;
 
-impl fmt::Display for ParseError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Empty => write!(f, "empty address string"),
-            Self::InvalidHost => write!(f, "invalid or missing host address"),
-            Self::InvalidPort => write!(f, "invalid or missing port"),
-            Self::InvalidUDSAddress(err) => write!(f, "invalid UDS address: {err}"),
-            Self::Unsupported => write!(f, "unsupported address type on this platform"),
+        if input.len() > MAX_NAME_LENGTH {
+            return Err(());
         }
-    }
-}
 
-impl std::error::Error for ParseError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            Self::InvalidUDSAddress(err) => Some(err),
-            _ => None,
+        let mut idx = 0;
+        while idx < input.len() {
+            let ch = input[idx];
+            state = match (state, ch) {
+                (Start | Next | NextAfterNumericOnly | Hyphen { .. }, b'.') => {
+                    return Err(());
+                }
+                (Subsequent { .. }, b'.') => Next,
+                (NumericOnly { .. }, b'.') => NextAfterNumericOnly,
+                (Subsequent { len } | NumericOnly { len } | Hyphen { len }, _)
+                    if len >= MAX_LABEL_LENGTH =>
+                {
+                    return Err(());
+                }
+                (Start | Next | NextAfterNumericOnly, b'0'..=b'9') => NumericOnly { len: 1 },
+                (NumericOnly { len }, b'0'..=b'9') => NumericOnly { len: len + 1 },
+                (Start | Next | NextAfterNumericOnly, b'a'..=b'z' | b'A'..=b'Z' | b'_') => {
+                    Subsequent { len: 1 }
+                }
+                (Subsequent { len } | NumericOnly { len } | Hyphen { len }, b'-') => {
+                    Hyphen { len: len + 1 }
+                }
+                (
+                    Subsequent { len } | NumericOnly { len } | Hyphen { len },
+                    b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'0'..=b'9',
+                ) => Subsequent { len: len + 1 },
+                _ => return Err(()),
+            };
+            idx += 1;
         }
-    }
-}
 
-impl From<ParseError> for io::Error {
-    fn from(value: ParseError) -> Self {
-        io::Error::new(io::ErrorKind::Other, value)
+        if matches!(
+            state,
+            Start | Hyphen { .. } | NumericOnly { .. } | NextAfterNumericOnly | Next
+        ) {
+            return Err(());
+        }
+
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    #![allow(non_snake_case)]
+    #[test]
+    fn test_CacheAlignedUniAddr() {
+        let addr = UniAddr::new("127.0.0.1:8080").unwrap();
+        let aligned = CacheAlignedUniAddr::from(addr.clone());
 
-    use rstest::rstest;
+        // The cache line sizes `wrapper_lite` aligns to.
+        let cache_line = if cfg!(any(
+            target_arch = "x86_64",
+            target_arch = "aarch64",
+            target_arch = "arm64ec",
+            target_arch = "powerpc64"
+        )) {
+            128
+        } else if cfg!(any(
+            target_arch = "arm",
+            target_arch = "mips",
+            target_arch = "mips32r6",
+            target_arch = "mips64",
+            target_arch = "mips64r6",
+            target_arch = "sparc",
+            target_arch = "hexagon"
+        )) {
+            32
+        } else if cfg!(target_arch = "m68k") {
+            16
+        } else if cfg!(target_arch = "s390x") {
+            256
+        } else {
+            64
+        };
 
-    use super::*;
+        assert_eq!(std::mem::align_of::<CacheAlignedUniAddr>(), cache_line);
+
+        // Adjacent elements never share a cache line.
+        let pair = [aligned.clone(), aligned.clone()];
+        let (first, second) = (
+            std::ptr::addr_of!(pair[0]) as usize,
+            std::ptr::addr_of!(pair[1]) as usize,
+        );
+
+        assert_eq!(first % cache_line, 0);
+        assert_eq!(second - first, cache_line);
+
+        assert_eq!(aligned.to_str(), "127.0.0.1:8080");
+        assert_eq!(UniAddr::from(aligned), addr);
+    }
+
+    #[test]
+    fn test_UniAddr_const_constructors() {
+        const LOCALHOST: UniAddr = UniAddr::localhost(8080);
+
+        assert_eq!(LOCALHOST, UniAddr::new("127.0.0.1:8080").unwrap());
+        assert_eq!(
+            UniAddr::unspecified_v4(0),
+            UniAddr::new("0.0.0.0:0").unwrap()
+        );
+        assert_eq!(
+            UniAddr::unspecified_v6(443),
+            UniAddr::new("[::]:443").unwrap()
+        );
+    }
 
     #[rstest]
-    #[case("0.0.0.0:0")]
-    #[case("0.0.0.0:8080")]
-    #[case("127.0.0.1:0")]
     #[case("127.0.0.1:8080")]
-    #[case("[::]:0")]
-    #[case("[::]:8080")]
-    #[case("[::1]:0")]
-    #[case("[::1]:8080")]
     #[case("example.com:8080")]
-    #[case("1example.com:8080")]
-    #[cfg_attr(unix, case("unix://"))]
+    #[cfg_attr(unix, case("unix:///tmp/test.socket"))]
+    fn test_UniAddr_new_os(#[case] input: &str) {
+        assert_eq!(
+            UniAddr::new_os(OsStr::new(input)).unwrap(),
+            UniAddr::new(input).unwrap()
+        );
+        assert_eq!(
+            UniAddr::try_from(OsString::from(input)).unwrap(),
+            UniAddr::new(input).unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_UniAddr_new_os_non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let addr = UniAddr::new_os(OsStr::from_bytes(b"unix:///tmp/test-\xff.socket")).unwrap();
+
+        let expected = std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(
+            b"/tmp/test-\xff.socket",
+        ))
+        .unwrap();
+        assert_eq!(addr, UniAddr::from(expected));
+
+        let _ = UniAddr::new_os(OsStr::from_bytes(b"example-\xff.com:80")).unwrap_err();
+    }
+
+    #[rstest]
+    #[case(b"127.0.0.1:8080", Some("127.0.0.1:8080"))]
+    #[case(b"example.com:8080", Some("example.com:8080"))]
+    #[case(b"", None)]
+    #[case(b"example-\xff.com:80", None)]
     #[cfg_attr(
-        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
-        case("unix://@")
+        unix,
+        case(b"unix:///tmp/test.socket", Some("unix:///tmp/test.socket"))
     )]
-    #[cfg_attr(unix, case("unix:///tmp/test_UniAddr_new_Display.socket"))]
     #[cfg_attr(
-        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
-        case("unix://@test_UniAddr_new_Display.socket")
+        any(target_os = "android", target_os = "linux"),
+        case(b"unix://@test-\xff", Some("unix://@test-\u{fffd}"))
     )]
-    fn test_UniAddr_new_Display(#[case] addr: &str) {
-        let addr_displayed = UniAddr::new(addr).unwrap().to_string();
-
+    fn test_UniAddr_from_bytes(#[case] input: &[u8], #[case] expected: Option<&str>) {
         assert_eq!(
-            addr_displayed, addr,
-            "addr_displayed {addr_displayed:?} != {addr:?}"
+            UniAddr::from_bytes(input)
+                .ok()
+                .as_ref()
+                .map(UniAddr::to_str)
+                .as_deref(),
+            expected
         );
     }
 
+    #[cfg(feature = "feat-serde")]
     #[rstest]
-    #[case("example.com:8080")]
-    #[case("1example.com:8080")]
-    #[should_panic]
-    #[case::panic("1example.com")]
-    #[should_panic]
-    #[case::panic("1example.com.")]
-    #[should_panic]
-    #[case::panic("1example.com.:14514")]
-    #[should_panic]
-    #[case::panic("1example.com:1919810")]
-    #[should_panic]
-    #[case::panic("this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name-this-is-a-long-host-name:19810")]
-    fn test_UniAddr_new_host(#[case] addr: &str) {
-        let addr_displayed = UniAddr::new_host(addr, None).unwrap().to_string();
+    #[case(&[("host", "example.com"), ("port", "8080")], Some("example.com:8080"))]
+    #[case(&[("port", "8080"), ("host", "192.0.2.1")], Some("192.0.2.1:8080"))]
+    #[case(&[("host", "::1"), ("port", "8080")], Some("[::1]:8080"))]
+    #[case(&[("host", "[::1]"), ("port", "8080")], Some("[::1]:8080"))]
+    #[case(&[("host", "example.com")], Some("example.com"))]
+    #[case(&[("host", "::1")], Some("[::1]"))]
+    #[case(&[("host", "example.com"), ("port", "65536")], None)]
+    #[case(&[("host", "example.com:80"), ("port", "8080")], None)]
+    #[case(&[("host", "example.com"), ("port", "8080"), ("scheme", "https")], None)]
+    #[case(&[("host", "example.com"), ("port", "8080"), ("port", "8081")], None)]
+    #[cfg_attr(unix, case(&[("host", "unix:///tmp/test.socket"), ("port", "8080")], None))]
+    fn test_UniAddr_deserialize_map(
+        #[case] entries: &[(&'static str, &'static str)],
+        #[case] expected: Option<&str>,
+    ) {
+        use serde::de::value::{Error, MapDeserializer};
+        use serde::Deserialize;
+
+        let deserializer = MapDeserializer::<_, Error>::new(entries.iter().copied());
 
         assert_eq!(
-            addr_displayed, addr,
-            "addr_displayed {addr_displayed:?} != {addr:?}"
+            UniAddr::deserialize(deserializer)
+                .ok()
+                .as_ref()
+                .map(UniAddr::to_str)
+                .as_deref(),
+            expected
         );
     }
 
+    #[cfg(feature = "feat-serde")]
     #[rstest]
-    #[should_panic]
-    #[case::panic("")]
-    #[should_panic]
-    #[case::panic("not-an-address")]
-    #[should_panic]
-    #[case::panic("127.0.0.1")]
-    #[should_panic]
-    #[case::panic("127.0.0.1:99999")]
-    #[should_panic]
-    #[case::panic("127.0.0.256:99999")]
-    #[should_panic]
-    #[case::panic("::1")]
-    #[should_panic]
-    #[case::panic("[::1]")]
-    #[should_panic]
-    #[case::panic("[::1]:99999")]
-    #[should_panic]
-    #[case::panic("[::gg]:99999")]
-    #[should_panic]
-    #[case::panic("example.com")]
-    #[should_panic]
-    #[case::panic("example.com:99999")]
-    #[should_panic]
-    #[case::panic("examp😀le.com:99999")]
-    fn test_UniAddr_new_invalid(#[case] addr: &str) {
-        let _ = UniAddr::new(addr).unwrap();
+    #[case(UniAddr::new("example.com:8080").unwrap())]
+    #[case(UniAddr::new_optional_port("example.com").unwrap())]
+    #[case(UniAddr::new_optional_port("192.0.2.1").unwrap())]
+    #[case(UniAddr::new_optional_port("::1").unwrap())]
+    #[case(UniAddr::new_optional_port("[fe80::1%2]").unwrap())]
+    fn test_UniAddr_serde_roundtrip(#[case] addr: UniAddr) {
+        use serde::de::value::{Error, MapDeserializer, StrDeserializer};
+        use serde::ser::{Impossible, SerializeMap};
+        use serde::{Deserialize, Serialize};
+
+        /// Records a string, or a map of strings.
+        struct Recorder;
+
+        enum Recorded {
+            Str(String),
+            Map(Vec<(String, String)>),
+        }
+
+        struct MapRecorder(Vec<(String, String)>);
+
+        macro_rules! unsupported {
+            ($($method:ident($($ty:ty),*) -> $ret:ty;)*) => {$(
+                fn $method(self, $(_: $ty),*) -> Result<$ret, Error> {
+                    Err(serde::ser::Error::custom("unsupported"))
+                }
+            )*};
+        }
+
+        impl serde::Serializer for Recorder {
+            type Ok = Recorded;
+            type Error = Error;
+            type SerializeSeq = Impossible<Recorded, Error>;
+            type SerializeTuple = Impossible<Recorded, Error>;
+            type SerializeTupleStruct = Impossible<Recorded, Error>;
+            type SerializeTupleVariant = Impossible<Recorded, Error>;
+            type SerializeMap = MapRecorder;
+            type SerializeStruct = Impossible<Recorded, Error>;
+            type SerializeStructVariant = Impossible<Recorded, Error>;
+
+            fn serialize_str(self, v: &str) -> Result<Recorded, Error> {
+                Ok(Recorded::Str(v.to_owned()))
+            }
+
+            fn serialize_map(self, _: Option<usize>) -> Result<MapRecorder, Error> {
+                Ok(MapRecorder(Vec::new()))
+            }
+
+            fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Recorded, Error> {
+                Err(serde::ser::Error::custom("unsupported"))
+            }
+
+            fn serialize_newtype_struct<T: ?Sized + Serialize>(
+                self,
+                _: &'static str,
+                _: &T,
+            ) -> Result<Recorded, Error> {
+                Err(serde::ser::Error::custom("unsupported"))
+            }
+
+            fn serialize_newtype_variant<T: ?Sized + Serialize>(
+                self,
+                _: &'static str,
+                _: u32,
+                _: &'static str,
+                _: &T,
+            ) -> Result<Recorded, Error> {
+                Err(serde::ser::Error::custom("unsupported"))
+            }
+
+            unsupported! {
+                serialize_bool(bool) -> Recorded;
+                serialize_i8(i8) -> Recorded;
+                serialize_i16(i16) -> Recorded;
+                serialize_i32(i32) -> Recorded;
+                serialize_i64(i64) -> Recorded;
+                serialize_u8(u8) -> Recorded;
+                serialize_u16(u16) -> Recorded;
+                serialize_u32(u32) -> Recorded;
+                serialize_u64(u64) -> Recorded;
+                serialize_f32(f32) -> Recorded;
+                serialize_f64(f64) -> Recorded;
+                serialize_char(char) -> Recorded;
+                serialize_bytes(&[u8]) -> Recorded;
+                serialize_none() -> Recorded;
+                serialize_unit() -> Recorded;
+                serialize_unit_struct(&'static str) -> Recorded;
+                serialize_unit_variant(&'static str, u32, &'static str) -> Recorded;
+                serialize_seq(Option<usize>) -> Self::SerializeSeq;
+                serialize_tuple(usize) -> Self::SerializeTuple;
+                serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+                serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+                serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+                serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+            }
+        }
+
+        impl SerializeMap for MapRecorder {
+            type Ok = Recorded;
+            type Error = Error;
+
+            fn serialize_key<T: ?Sized + Serialize>(&mut self, _: &T) -> Result<(), Error> {
+                Err(serde::ser::Error::custom("unsupported"))
+            }
+
+            fn serialize_value<T: ?Sized + Serialize>(&mut self, _: &T) -> Result<(), Error> {
+                Err(serde::ser::Error::custom("unsupported"))
+            }
+
+            fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Error>
+            where
+                K: ?Sized + Serialize,
+                V: ?Sized + Serialize,
+            {
+                match (key.serialize(Recorder)?, value.serialize(Recorder)?) {
+                    (Recorded::Str(key), Recorded::Str(value)) => {
+                        self.0.push((key, value));
+                        Ok(())
+                    }
+                    _ => Err(serde::ser::Error::custom("unsupported")),
+                }
+            }
+
+            fn end(self) -> Result<Recorded, Error> {
+                Ok(Recorded::Map(self.0))
+            }
+        }
+
+        let deserialized = match addr.serialize(Recorder).unwrap() {
+            Recorded::Str(addr) => UniAddr::deserialize(StrDeserializer::<Error>::new(&addr)),
+            Recorded::Map(entries) => UniAddr::deserialize(MapDeserializer::<_, Error>::new(
+                entries.iter().map(|(key, value)| (&**key, &**value)),
+            )),
+        };
+
+        assert_eq!(deserialized.unwrap(), addr);
     }
 
-    #[cfg(not(unix))]
+    #[cfg(all(unix, feature = "feat-serde"))]
     #[test]
-    fn test_UniAddr_new_unsupported() {
-        // Unix sockets should be unsupported on non-Unix platforms
-        let result = UniAddr::new("unix:///tmp/test.sock");
+    fn test_UniAddr_deserialize_map_keeps_unix_file() {
+        use serde::de::value::{Error, MapDeserializer};
+        use serde::Deserialize;
 
-        assert!(matches!(result.unwrap_err(), ParseError::Unsupported));
+        let path = std::env::temp_dir().join(format!("uni-addr-map-{}", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+
+        let host = format!("unix://{}", path.display());
+        let deserializer =
+            MapDeserializer::<_, Error>::new([("host", &*host), ("port", "8080")].into_iter());
+
+        let result = UniAddr::deserialize(deserializer);
+        let exists = path.exists();
+        let _ = std::fs::remove_file(&path);
+
+        let _ = result.unwrap_err();
+        assert!(exists);
     }
 
-    #[rstest]
-    #[case("0.0.0.0:0")]
-    #[case("0.0.0.0:8080")]
-    #[case("127.0.0.1:0")]
-    #[case("127.0.0.1:8080")]
-    #[case("[::]:0")]
-    #[case("[::]:8080")]
-    #[case("[::1]:0")]
-    #[case("[::1]:8080")]
-    #[cfg_attr(unix, case("unix:///tmp/test_socket2_sock_addr_conversion.socket"))]
-    #[cfg_attr(unix, case("unix://"))]
-    #[cfg_attr(
-        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
-        case("unix://@test_socket2_sock_addr_conversion.socket")
-    )]
-    fn test_socket2_SockAddr_conversion(#[case] addr: &str) {
-        let uni_addr = UniAddr::new(addr).unwrap();
-        let sock_addr = socket2::SockAddr::try_from(&uni_addr).unwrap();
-        let uni_addr_converted = UniAddr::try_from(sock_addr).unwrap();
+    #[cfg(feature = "feat-serde")]
+    #[test]
+    fn test_UniAddr_deserialize_str() {
+        use serde::de::value::{Error, StrDeserializer};
+        use serde::Deserialize;
+
+        let deserializer = StrDeserializer::<Error>::new("example.com:8080");
 
         assert_eq!(
-            uni_addr, uni_addr_converted,
-            "{uni_addr} != {uni_addr_converted}"
+            UniAddr::deserialize(deserializer).unwrap(),
+            "example.com:8080"
+        );
+    }
+
+    #[cfg(feature = "feat-serde")]
+    #[test]
+    fn test_UniAddr_deserialize_binary() {
+        use serde::de::value::{Error, MapAccessDeserializer, MapDeserializer};
+        use serde::Deserialize;
+
+        /// Pretends to be a binary format.
+        struct Binary<D>(D);
+
+        impl<'de, D: serde::Deserializer<'de>> serde::Deserializer<'de> for Binary<D> {
+            type Error = D::Error;
+
+            fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                self.0.deserialize_any(visitor)
+            }
+
+            fn deserialize_enum<V>(
+                self,
+                name: &'static str,
+                variants: &'static [&'static str],
+                visitor: V,
+            ) -> Result<V::Value, Self::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                self.0.deserialize_enum(name, variants, visitor)
+            }
+
+            fn is_human_readable(&self) -> bool {
+                false
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                tuple_struct map struct identifier ignored_any
+            }
+        }
+
+        let tagged = |tag: &'static str, value: &'static str| {
+            Binary(MapAccessDeserializer::new(
+                MapDeserializer::<_, Error>::new([(tag, value)].into_iter()),
+            ))
+        };
+
+        assert_eq!(
+            UniAddr::deserialize(tagged("host", "example.com:8080")).unwrap(),
+            "example.com:8080"
         );
+
+        let _ = UniAddr::deserialize(tagged("socks", "example.com:8080")).unwrap_err();
+
+        // The legacy string representation, before 0.4.0.
+        let legacy =
+            |addr: &'static str| Binary(serde::de::value::StrDeserializer::<Error>::new(addr));
+
+        assert_eq!(
+            UniAddr::deserialize(legacy("example.com:8080")).unwrap(),
+            "example.com:8080"
+        );
+        assert_eq!(
+            UniAddr::deserialize(legacy("192.0.2.1:80")).unwrap(),
+            "192.0.2.1:80"
+        );
+
+        let _ = UniAddr::deserialize(legacy("example.com")).unwrap_err();
+        let _ = UniAddr::deserialize(legacy("host")).unwrap_err();
     }
 }
@@ -0,0 +1,568 @@
+//! Support for the [`uni_addr!`](crate::uni_addr) macro.
+
+use crate::{UniAddr, UniAddrRef};
+
+#[macro_export]
+/// Creates a [`UniAddr`](crate::UniAddr) from an address literal, which is
+/// checked at compile time, so that hard-coded defaults can't be typos.
+///
+/// The literal is in any form [`UniAddr::new`](crate::UniAddr::new) accepts,
+/// except that:
+///
+/// - An IPv6 zone must be numeric, e.g. `[fe80::1%2]:8080`, as interface names
+///   can only be resolved at run time.
+/// - A host name must be ASCII, i.e. already converted to punycode.
+/// - The file of a `unix://` pathname address is never removed, unlike
+///   [`UniAddr::new`](crate::UniAddr::new) does.
+///
+/// # Examples
+///
+/// ```rust
+/// # use uni_addr::{uni_addr, UniAddr};
+/// let addr = uni_addr!("127.0.0.1:8080");
+///
+/// assert_eq!(addr, UniAddr::new("127.0.0.1:8080").unwrap());
+/// ```
+///
+/// An invalid literal fails the build:
+///
+/// ```rust,compile_fail
+/// # use uni_addr::uni_addr;
+/// let addr = uni_addr!("127.0.0.1:80800");
+/// ```
+macro_rules! uni_addr {
+    ($addr:expr $(,)?) => {{
+        const ADDR: &str = $addr;
+        const _: () = if let Err(err) = $crate::macros::validate(ADDR) {
+            panic!("{}", err)
+        };
+
+        $crate::macros::from_validated(ADDR)
+    }};
+}
+
+#[doc(hidden)]
+/// Checks `addr` like [`UniAddr::new`] does, in a const context, returning
+/// why it is invalid.
+pub const fn validate(addr: &str) -> Result<(), &'static str> {
+    let addr = addr.as_bytes();
+
+    if addr.is_empty() {
+        return Err("empty address");
+    }
+
+    if let Some(path) = strip_prefix(addr, crate::UNIX_URI_PREFIX.as_bytes()) {
+        return validate_unix(path);
+    }
+
+    #[cfg(all(
+        feature = "feat-vsock",
+        any(target_os = "android", target_os = "linux")
+    ))]
+    if let Some(addr) = strip_prefix(addr, crate::VSOCK_URI_PREFIX.as_bytes()) {
+        let Some((cid, port)) = rsplit_once(addr, b':') else {
+            return Err("missing VM socket port");
+        };
+
+        if parse_uint(cid, u32::MAX as u64).is_none() {
+            return Err("invalid VM socket CID");
+        }
+
+        if parse_uint(port, u32::MAX as u64).is_none() {
+            return Err("invalid VM socket port");
+        }
+
+        return Ok(());
+    }
+
+    if let Some(fd) = strip_prefix(addr, crate::FD_URI_PREFIX.as_bytes()) {
+        if cfg!(not(unix)) {
+            return Err("file descriptor addresses are unsupported on this platform");
+        }
+
+        if parse_uint(fd, i32::MAX as u64).is_none() {
+            return Err("invalid file descriptor number");
+        }
+
+        return Ok(());
+    }
+
+    if let Some(path) = strip_prefix(addr, crate::PIPE_URI_PREFIX.as_bytes()) {
+        if cfg!(not(windows)) {
+            return Err("named pipe addresses are unsupported on this platform");
+        }
+
+        return validate_pipe(path);
+    }
+
+    if let Some(addr) = strip_prefix(addr, crate::TCP_URI_PREFIX.as_bytes()) {
+        // Only a network socket address or a host name, never another scheme.
+        if contains_scheme(addr) {
+            return Err("invalid address after `tcp://`");
+        }
+
+        return validate_inet_or_host(addr, false);
+    }
+
+    if let Some(addr) = strip_prefix(addr, crate::DNS_URI_PREFIX.as_bytes()) {
+        if contains_scheme(addr) {
+            return Err("invalid host name after `dns://`");
+        }
+
+        return validate_inet_or_host(addr, true);
+    }
+
+    validate_inet_or_host(addr, false)
+}
+
+/// Checks a network socket address, or a host name with port, or only the
+/// latter if `host_only`.
+const fn validate_inet_or_host(addr: &[u8], host_only: bool) -> Result<(), &'static str> {
+    let Some((host, port)) = rsplit_once(addr, b':') else {
+        return Err("missing port");
+    };
+
+    if parse_uint(port, u16::MAX as u64).is_none() {
+        return Err("invalid port");
+    }
+
+    if let [b'[', .., b']'] = host {
+        if host_only {
+            return Err("expected a host name, found an IPv6 address");
+        }
+
+        let (_, host) = host.split_at(1);
+        let (host, _) = host.split_at(host.len() - 1);
+
+        let ip = match split_once(host, b'%') {
+            Some((ip, zone)) => {
+                if zone.is_empty() {
+                    return Err("empty IPv6 zone");
+                }
+
+                if parse_uint(zone, u32::MAX as u64).is_none() {
+                    return Err("IPv6 zone must be numeric in an address literal");
+                }
+
+                ip
+            }
+            None => host,
+        };
+
+        if !is_ipv6(ip) {
+            return Err("invalid IPv6 address");
+        }
+
+        return Ok(());
+    }
+
+    // Like `UniAddr::new`, a host starting with a digit may be an IPv4 address
+    // or a host name.
+    if is_ipv4(host) {
+        if host_only {
+            return Err("expected a host name, found an IPv4 address");
+        }
+
+        return Ok(());
+    }
+
+    if UniAddr::validate_host_name(host).is_ok() {
+        return Ok(());
+    }
+
+    Err("invalid host")
+}
+
+#[doc(hidden)]
+/// Creates a [`UniAddr`] from `addr` checked by [`validate`].
+///
+/// # Panics
+///
+/// If `addr` is invalid, which [`validate`] has ruled out.
+pub fn from_validated(addr: &str) -> UniAddr {
+    match UniAddrRef::new(addr) {
+        // Not `UniAddr::new`, which removes the file of a Unix socket.
+        Ok(addr) => addr.to_owned(),
+        #[allow(clippy::panic)]
+        Err(err) => panic!("invalid address literal {addr:?}: {err}"),
+    }
+}
+
+const fn validate_unix(path: &[u8]) -> Result<(), &'static str> {
+    if cfg!(not(unix)) {
+        return Err("unix socket addresses are unsupported on this platform");
+    }
+
+    let is_linux_like = cfg!(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "cygwin"
+    ));
+
+    // The size of `sun_path`.
+    let max_len = if is_linux_like { 108 } else { 104 };
+
+    if let [b'@' | b'\0', ..] = path {
+        if !is_linux_like {
+            return Err("abstract unix socket addresses are unsupported on this platform");
+        }
+
+        // Without the leading nul.
+        if path.len() > max_len {
+            return Err("abstract unix socket name is too long");
+        }
+
+        return Ok(());
+    }
+
+    // With the trailing nul.
+    if path.len() >= max_len {
+        return Err("unix socket path is too long");
+    }
+
+    let mut idx = 0;
+    while idx < path.len() {
+        if path[idx] == b'\0' {
+            return Err("unix socket path contains a nul byte");
+        }
+        idx += 1;
+    }
+
+    Ok(())
+}
+
+/// Checks a pipe path like [`UniAddr::new_pipe`] does.
+const fn validate_pipe(path: &[u8]) -> Result<(), &'static str> {
+    /// "The entire pipe name string can be up to 256 characters long."
+    const MAX_PIPE_NAME_LENGTH: usize = 256;
+
+    // In UTF-16 code units: one for each sequence of up to 3 bytes, or two
+    // for a sequence of 4 bytes, which starts with `0b11110xxx`.
+    let mut len = 0;
+    let mut idx = 0;
+    while idx < path.len() {
+        match path[idx] {
+            0xF0..=0xF7 => len += 2,
+            0x80..=0xBF => {}
+            _ => len += 1,
+        }
+        idx += 1;
+    }
+
+    if len > MAX_PIPE_NAME_LENGTH {
+        return Err("named pipe path is too long");
+    }
+
+    let Some(path) = strip_prefix(path, br"\\") else {
+        return Err(r"named pipe path must start with `\\`");
+    };
+
+    let Some((server, name)) = split_once(path, b'\\') else {
+        return Err("invalid named pipe path");
+    };
+
+    let Some(name) = strip_prefix_ignore_ascii_case(name, br"pipe\") else {
+        return Err(r"named pipe path must be in the form of `\\<server>\pipe\<name>`");
+    };
+
+    if server.is_empty() || name.is_empty() || split_once(name, b'\\').is_some() {
+        return Err(r"named pipe path must be in the form of `\\<server>\pipe\<name>`");
+    }
+
+    Ok(())
+}
+
+/// Checks an IPv4 address like [`Ipv4Addr::from_str`] does.
+///
+/// [`Ipv4Addr::from_str`]: std::net::Ipv4Addr
+const fn is_ipv4(mut input: &[u8]) -> bool {
+    let mut octets = 0;
+
+    loop {
+        let (octet, is_last) = match split_once(input, b'.') {
+            Some((octet, rest)) => {
+                input = rest;
+                (octet, false)
+            }
+            None => (input, true),
+        };
+
+        // No sign, or leading zeros.
+        if matches!(octet, [b'+', ..] | [b'0', _, ..]) || parse_uint(octet, 255).is_none() {
+            return false;
+        }
+
+        octets += 1;
+
+        if is_last || octets == 4 {
+            return is_last && octets == 4;
+        }
+    }
+}
+
+/// Checks an IPv6 address like [`Ipv6Addr::from_str`] does.
+///
+/// [`Ipv6Addr::from_str`]: std::net::Ipv6Addr
+const fn is_ipv6(input: &[u8]) -> bool {
+    const fn count_groups(mut input: &[u8], limit: usize) -> Option<usize> {
+        if input.is_empty() {
+            return Some(0);
+        }
+
+        let mut groups = 0;
+
+        loop {
+            let group = if let Some((group, rest)) = split_once(input, b':') {
+                input = rest;
+                group
+            } else {
+                let group = input;
+                input = &[];
+                group
+            };
+
+            // An embedded IPv4 address, taking two groups, must be the last.
+            if input.is_empty() && groups + 2 <= limit && is_ipv4(group) {
+                return Some(groups + 2);
+            }
+
+            if group.is_empty() || group.len() > 4 || !is_hex(group) {
+                return None;
+            }
+
+            groups += 1;
+
+            if input.is_empty() {
+                return if groups <= limit { Some(groups) } else { None };
+            }
+        }
+    }
+
+    match split_double_colon(input) {
+        // `::` stands for at least one group.
+        Some((head, tail)) => match count_groups(head, 7) {
+            Some(head) => count_groups(tail, 7 - head).is_some(),
+            None => false,
+        },
+        None => matches!(count_groups(input, 8), Some(8)),
+    }
+}
+
+/// Parses a decimal number of at most `max`, optionally prefixed with `+`,
+/// like [`u64::from_str`] does.
+const fn parse_uint(input: &[u8], max: u64) -> Option<u64> {
+    let input = match input {
+        [b'+', rest @ ..] => rest,
+        _ => input,
+    };
+
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut value: u64 = 0;
+    let mut idx = 0;
+    while idx < input.len() {
+        let digit = input[idx];
+        if !digit.is_ascii_digit() {
+            return None;
+        }
+
+        value = value * 10 + (digit - b'0') as u64;
+        if value > max {
+            return None;
+        }
+
+        idx += 1;
+    }
+
+    Some(value)
+}
+
+const fn is_hex(input: &[u8]) -> bool {
+    let mut idx = 0;
+    while idx < input.len() {
+        if !input[idx].is_ascii_hexdigit() {
+            return false;
+        }
+        idx += 1;
+    }
+
+    true
+}
+
+const fn strip_prefix<'a>(input: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    strip_prefix_with(input, prefix, false)
+}
+
+const fn strip_prefix_ignore_ascii_case<'a>(input: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    strip_prefix_with(input, prefix, true)
+}
+
+const fn strip_prefix_with<'a>(
+    input: &'a [u8],
+    prefix: &[u8],
+    ignore_ascii_case: bool,
+) -> Option<&'a [u8]> {
+    if input.len() < prefix.len() {
+        return None;
+    }
+
+    let mut idx = 0;
+    while idx < prefix.len() {
+        let (a, b) = (input[idx], prefix[idx]);
+        if a != b && !(ignore_ascii_case && a.eq_ignore_ascii_case(&b)) {
+            return None;
+        }
+        idx += 1;
+    }
+
+    Some(input.split_at(prefix.len()).1)
+}
+
+/// Splits `input` at the first `delimiter`.
+const fn split_once(input: &[u8], delimiter: u8) -> Option<(&[u8], &[u8])> {
+    let mut idx = 0;
+    while idx < input.len() {
+        if input[idx] == delimiter {
+            let (head, tail) = input.split_at(idx);
+            return Some((head, tail.split_at(1).1));
+        }
+        idx += 1;
+    }
+
+    None
+}
+
+/// Splits `input` at the last `delimiter`.
+const fn rsplit_once(input: &[u8], delimiter: u8) -> Option<(&[u8], &[u8])> {
+    let mut idx = input.len();
+    while idx > 0 {
+        idx -= 1;
+        if input[idx] == delimiter {
+            let (head, tail) = input.split_at(idx);
+            return Some((head, tail.split_at(1).1));
+        }
+    }
+
+    None
+}
+
+/// Checks if `input` contains `://`.
+const fn contains_scheme(input: &[u8]) -> bool {
+    let mut idx = 0;
+    while idx + 2 < input.len() {
+        if input[idx] == b':' && input[idx + 1] == b'/' && input[idx + 2] == b'/' {
+            return true;
+        }
+        idx += 1;
+    }
+
+    false
+}
+
+/// Splits `input` at the first `::`.
+const fn split_double_colon(input: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut idx = 0;
+    while idx + 1 < input.len() {
+        if input[idx] == b':' && input[idx + 1] == b':' {
+            let (head, tail) = input.split_at(idx);
+            return Some((head, tail.split_at(2).1));
+        }
+        idx += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("127.0.0.1:8080")]
+    #[case("0.0.0.0:+80")]
+    #[case("1.example.com:443")]
+    #[case("example.com:0")]
+    #[case("[::1]:8080")]
+    #[case("[::]:8080")]
+    #[case("[1:2:3:4:5:6:7:8]:8080")]
+    #[case("[1::8]:8080")]
+    #[case("[1:2:3:4:5:6::]:8080")]
+    #[case("[::ffff:192.0.2.1]:8080")]
+    #[case("[1:2:3:4:5:6:192.0.2.1]:8080")]
+    #[case("[fe80::1%2]:8080")]
+    #[case("tcp://127.0.0.1:8080")]
+    #[case("tcp://example.com:8080")]
+    #[case("dns://example.com:8080")]
+    #[cfg_attr(unix, case("unix:///run/uni-addr-macro.socket"))]
+    #[cfg_attr(unix, case("unix://"))]
+    #[cfg_attr(unix, case("fd://3"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux"),
+        case("unix://@uni-addr-macro.socket")
+    )]
+    #[cfg_attr(
+        all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ),
+        case("vsock://2:1234")
+    )]
+    #[cfg_attr(windows, case(r"pipe://\\.\pipe\uni-addr-macro"))]
+    fn test_validate(#[case] addr: &str) {
+        assert_eq!(validate(addr), Ok(()));
+        assert_eq!(from_validated(addr), UniAddr::new(addr).unwrap());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("127.0.0.1")]
+    #[case("127.0.0.1:65536")]
+    #[case("127.0.0.1:-1")]
+    #[case("127.0.0.1:")]
+    #[case("1.2.3:8080")]
+    #[case("1.2.3.4.5:8080")]
+    #[case("01.2.3.4:8080")]
+    #[case("256.0.0.1:8080")]
+    #[case("-example.com:443")]
+    #[case("example.com.:443")]
+    #[case("[::1:8080")]
+    #[case("[1:2:3:4:5:6:7:8:9]:8080")]
+    #[case("[1:2:3:4:5:6:7::8]:8080")]
+    #[case("[1::2::3]:8080")]
+    #[case("[12345::]:8080")]
+    #[case("[::192.0.2.1:1]:8080")]
+    #[case("[fe80::1%]:8080")]
+    #[case("fd://-1")]
+    #[case("dns://127.0.0.1:8080")]
+    #[case("dns://[::1]:8080")]
+    #[case("tcp://fd://3")]
+    #[case("fd://2147483648")]
+    #[case("pipe://pipe")]
+    #[cfg_attr(unix, case("unix:///a\0b"))]
+    #[cfg_attr(
+        unix,
+        case(concat!("unix:///", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"))
+    )]
+    fn test_validate_invalid(#[case] addr: &str) {
+        let _ = validate(addr).unwrap_err();
+        let _ = UniAddr::new(addr).unwrap_err();
+    }
+
+    #[test]
+    fn test_macro() {
+        assert_eq!(
+            crate::uni_addr!("127.0.0.1:8080"),
+            UniAddr::new("127.0.0.1:8080").unwrap()
+        );
+
+        const DEFAULT_ADDR: &str = "localhost:8080";
+        assert_eq!(
+            crate::uni_addr!(DEFAULT_ADDR),
+            UniAddr::new(DEFAULT_ADDR).unwrap()
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Conversions between [`UniAddr`] and the text form of libp2p-style
+//! multiaddrs, for the subset this crate models.
+//!
+//! See <https://github.com/multiformats/multiaddr>.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV6};
+
+use crate::{parse_port_less_ip, split_host_port, split_zone, ParseError, UniAddr, UniAddrInner};
+
+/// See [`UniAddr::from_multiaddr`].
+pub(crate) fn parse(addr: &str) -> Result<UniAddr, ParseError> {
+    let Some(addr) = addr.strip_prefix('/') else {
+        return Err(ParseError::InvalidHost);
+    };
+
+    let mut parts = addr.splitn(4, '/');
+
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some("ip4"), Some(ip), Some("tcp"), Some(port)) => {
+            let ip = ip
+                .parse::<Ipv4Addr>()
+                .map_err(|_| ParseError::InvalidHost)?;
+
+            Ok(SocketAddr::new(ip.into(), parse_port(port)?).into())
+        }
+        (Some("ip6"), Some(ip), Some("tcp"), Some(port)) => {
+            let ip = ip
+                .parse::<Ipv6Addr>()
+                .map_err(|_| ParseError::InvalidHost)?;
+
+            Ok(SocketAddr::new(ip.into(), parse_port(port)?).into())
+        }
+        (Some("dns" | "dns4" | "dns6"), Some(hostname), Some("tcp"), Some(port)) => {
+            UniAddr::new_host_parts(hostname, parse_port(port)?)
+        }
+        // A port-less address, see `UniAddr::new_optional_port`.
+        (Some("ip4"), Some(ip), None, None) => {
+            let _ = ip
+                .parse::<Ipv4Addr>()
+                .map_err(|_| ParseError::InvalidHost)?;
+
+            UniAddr::new_optional_port(ip)
+        }
+        (Some("ip6"), Some(ip), None, None) => {
+            let _ = ip
+                .parse::<Ipv6Addr>()
+                .map_err(|_| ParseError::InvalidHost)?;
+
+            UniAddr::new_optional_port(ip)
+        }
+        // Not `new_optional_port` for anything with a port or a scheme.
+        (Some("dns" | "dns4" | "dns6"), Some(hostname), None, None) if !hostname.contains(':') => {
+            UniAddr::new_optional_port(hostname)
+        }
+        (Some("ip6zone"), Some(zone), Some("ip6"), Some(rest)) => parse_ip6zone(zone, rest),
+        (Some("unix"), Some(_), ..) => parse_unix(&addr["unix/".len()..]),
+        (Some("ip4" | "ip6" | "dns" | "dns4" | "dns6"), _, Some("tcp") | None, _) => {
+            Err(ParseError::InvalidPort)
+        }
+        _ => Err(ParseError::Unsupported),
+    }
+}
+
+fn parse_port(port: &str) -> Result<u16, ParseError> {
+    port.parse().map_err(|_| ParseError::InvalidPort)
+}
+
+/// Parses the `/ip6/<ip>`, optionally followed by `/tcp/<port>`, after an
+/// `/ip6zone/<zone>` component.
+fn parse_ip6zone(zone: &str, rest: &str) -> Result<UniAddr, ParseError> {
+    let (ip, port) = match rest.split_once('/') {
+        Some((ip, rest)) => (
+            ip,
+            Some(rest.strip_prefix("tcp/").ok_or(ParseError::Unsupported)?),
+        ),
+        None => (rest, None),
+    };
+
+    let ip = ip
+        .parse::<Ipv6Addr>()
+        .map_err(|_| ParseError::InvalidHost)?;
+
+    match port {
+        Some(port) => {
+            let (_, scope_id) = split_zone(&format!("{ip}%{zone}"))?;
+
+            Ok(SocketAddrV6::new(ip, parse_port(port)?, 0, scope_id).into())
+        }
+        // A port-less address, see `UniAddr::new_optional_port`.
+        None => UniAddr::new_optional_port(&format!("[{ip}%{zone}]")),
+    }
+}
+
+#[cfg(unix)]
+/// Parses the path of a `/unix` component, either percent-encoded as a single
+/// segment, or spanning the rest of the multiaddr, like older libp2p versions.
+fn parse_unix(path: &str) -> Result<UniAddr, ParseError> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut decoded = percent_decode(path).ok_or(ParseError::InvalidHost)?;
+
+    if decoded.first() != Some(&b'/') {
+        decoded.insert(0, b'/');
+    }
+
+    // Not `new_pathname`, which removes the socket file.
+    std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(&decoded))
+        .map(UniAddr::from)
+        .map_err(ParseError::InvalidUDSAddress)
+}
+
+#[cfg(not(unix))]
+fn parse_unix(_path: &str) -> Result<UniAddr, ParseError> {
+    Err(ParseError::Unsupported)
+}
+
+/// See [`UniAddr::to_multiaddr`].
+pub(crate) fn format(addr: &UniAddr) -> Option<String> {
+    match addr.as_inner() {
+        UniAddrInner::Inet(SocketAddr::V4(addr)) => {
+            Some(format!("/ip4/{}/tcp/{}", addr.ip(), addr.port()))
+        }
+        UniAddrInner::Inet(SocketAddr::V6(addr)) if addr.scope_id() != 0 => Some(format!(
+            "/ip6zone/{}/ip6/{}/tcp/{}",
+            addr.scope_id(),
+            addr.ip(),
+            addr.port()
+        )),
+        UniAddrInner::Inet(SocketAddr::V6(addr)) => {
+            Some(format!("/ip6/{}/tcp/{}", addr.ip(), addr.port()))
+        }
+        #[cfg(unix)]
+        UniAddrInner::Unix(addr) => {
+            use std::os::unix::ffi::OsStrExt;
+
+            addr.as_pathname().map(|pathname| {
+                format!("/unix/{}", percent_encode(pathname.as_os_str().as_bytes()))
+            })
+        }
+        UniAddrInner::Host(addr) => match split_host_port(addr) {
+            (hostname, Some(port)) => Some(format!("/dns/{hostname}/tcp/{port}")),
+            // A port-less address, see `UniAddr::new_optional_port`.
+            (host, None) => match parse_port_less_ip(host) {
+                Some((IpAddr::V4(ip), _)) => Some(format!("/ip4/{ip}")),
+                Some((IpAddr::V6(ip), None)) => Some(format!("/ip6/{ip}")),
+                Some((IpAddr::V6(ip), Some(zone))) => Some(format!("/ip6zone/{zone}/ip6/{ip}")),
+                None => Some(format!("/dns/{host}")),
+            },
+        },
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+fn percent_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut encoded = String::with_capacity(bytes.len());
+
+    for byte in bytes {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(*byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+
+    encoded
+}
+
+#[cfg(unix)]
+fn percent_decode(input: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(input.len());
+    let mut bytes = input.bytes();
+
+    while let Some(byte) = bytes.next() {
+        if byte == b'%' {
+            let hex = [bytes.next()?, bytes.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            decoded.push(byte);
+        }
+    }
+
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("/ip4/127.0.0.1/tcp/8080", "127.0.0.1:8080")]
+    #[case("/ip6/::1/tcp/8080", "[::1]:8080")]
+    #[case("/ip6zone/2/ip6/fe80::1/tcp/8080", "[fe80::1%2]:8080")]
+    #[case("/dns/example.com/tcp/443", "example.com:443")]
+    #[cfg_attr(unix, case("/unix/%2Ftmp%2Fp2p.socket", "unix:///tmp/p2p.socket"))]
+    fn test_roundtrip(#[case] multiaddr: &str, #[case] addr: &str) {
+        let addr = UniAddr::new(addr).unwrap();
+
+        assert_eq!(parse(multiaddr).unwrap(), addr);
+        assert_eq!(format(&addr).unwrap(), multiaddr);
+    }
+
+    #[rstest]
+    #[case("/ip4/192.0.2.1", "192.0.2.1")]
+    #[case("/ip6/::1", "::1")]
+    #[case("/dns/example.com", "example.com")]
+    #[case("/ip6zone/2/ip6/fe80::1", "[fe80::1%2]")]
+    fn test_roundtrip_optional_port(#[case] multiaddr: &str, #[case] addr: &str) {
+        let addr = UniAddr::new_optional_port(addr).unwrap();
+
+        assert_eq!(parse(multiaddr).unwrap(), addr);
+        assert_eq!(format(&addr).unwrap(), multiaddr);
+    }
+
+    #[rstest]
+    #[case("/dns4/example.com/tcp/443", "example.com:443")]
+    #[cfg_attr(unix, case("/unix/tmp/p2p.socket", "unix:///tmp/p2p.socket"))]
+    fn test_parse(#[case] multiaddr: &str, #[case] addr: &str) {
+        assert_eq!(parse(multiaddr).unwrap(), UniAddr::new(addr).unwrap());
+    }
+
+    #[rstest]
+    #[case("ip4/127.0.0.1/tcp/8080")]
+    #[case("/ip4/::1/tcp/8080")]
+    #[case("/ip4/127.0.0.1/tcp/65536")]
+    #[case("/ip4/127.0.0.1/udp/8080")]
+    #[case("/ip4/127.0.0.1/tcp/8080/p2p/QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN")]
+    #[case("/dns/-invalid/tcp/443")]
+    #[case("/ip4/::1")]
+    #[case("/ip6/192.0.2.1")]
+    #[case("/dns/example.com:443")]
+    #[case("/ip6zone//ip6/fe80::1/tcp/8080")]
+    #[case("/ip6zone/2/ip4/127.0.0.1/tcp/8080")]
+    #[case("/ip6zone/2/ip6/fe80::1/udp/8080")]
+    #[cfg_attr(unix, case("/dns/unix:///tmp/p2p.socket"))]
+    #[case("/onion3/vww6ybal4bd7szmgncyruucpgfkqahzddi37ktceo3ah7ngmcopnpyyd:1234")]
+    #[cfg_attr(unix, case("/unix/%2"))]
+    fn test_parse_invalid(#[case] multiaddr: &str) {
+        let _ = parse(multiaddr).unwrap_err();
+    }
+}
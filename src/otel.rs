@@ -0,0 +1,155 @@
+//! OpenTelemetry semantic-convention attributes.
+//!
+//! See [`UniAddr::otel_attributes`].
+
+use std::borrow::Cow;
+use std::net::SocketAddr;
+
+use crate::{split_host_port, UniAddr, UniAddrInner};
+
+/// The `server.address` attribute key.
+pub const SERVER_ADDRESS: &str = "server.address";
+
+/// The `server.port` attribute key.
+pub const SERVER_PORT: &str = "server.port";
+
+/// The `network.transport` attribute key.
+pub const NETWORK_TRANSPORT: &str = "network.transport";
+
+/// The `network.type` attribute key.
+pub const NETWORK_TYPE: &str = "network.type";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// The value of an attribute returned by [`UniAddr::otel_attributes`].
+pub enum AttributeValue<'a> {
+    /// A string value.
+    String(Cow<'a, str>),
+
+    /// An integer value.
+    Int(i64),
+}
+
+pub(crate) fn attributes(addr: &UniAddr) -> Vec<(&'static str, AttributeValue<'_>)> {
+    match addr.as_inner() {
+        UniAddrInner::Inet(addr) => vec![
+            (
+                SERVER_ADDRESS,
+                AttributeValue::String(addr.ip().to_string().into()),
+            ),
+            (SERVER_PORT, AttributeValue::Int(addr.port().into())),
+            (NETWORK_TRANSPORT, AttributeValue::String("tcp".into())),
+            (
+                NETWORK_TYPE,
+                AttributeValue::String(
+                    match addr {
+                        SocketAddr::V4(_) => "ipv4",
+                        SocketAddr::V6(_) => "ipv6",
+                    }
+                    .into(),
+                ),
+            ),
+        ],
+        #[cfg(unix)]
+        UniAddrInner::Unix(addr) => vec![
+            (
+                SERVER_ADDRESS,
+                AttributeValue::String(addr.to_string_lossy().into()),
+            ),
+            (NETWORK_TRANSPORT, AttributeValue::String("unix".into())),
+        ],
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        UniAddrInner::Vsock(cid, port) => vec![
+            (
+                SERVER_ADDRESS,
+                AttributeValue::String(cid.to_string().into()),
+            ),
+            (SERVER_PORT, AttributeValue::Int((*port).into())),
+            (NETWORK_TRANSPORT, AttributeValue::String("vsock".into())),
+        ],
+        // Nothing is known until the file descriptor is inspected.
+        #[cfg(unix)]
+        UniAddrInner::Fd(_) => Vec::new(),
+        #[cfg(windows)]
+        UniAddrInner::Pipe(path) => vec![
+            (SERVER_ADDRESS, AttributeValue::String(path.as_ref().into())),
+            (NETWORK_TRANSPORT, AttributeValue::String("pipe".into())),
+        ],
+        UniAddrInner::Host(addr) => {
+            let (host, port) = split_host_port(addr);
+
+            let mut attributes = vec![(SERVER_ADDRESS, AttributeValue::String(host.into()))];
+
+            if let Some(port) = port {
+                attributes.push((SERVER_PORT, AttributeValue::Int(port.into())));
+            }
+
+            attributes.push((NETWORK_TRANSPORT, AttributeValue::String("tcp".into())));
+
+            attributes
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("127.0.0.1:8080", "127.0.0.1", Some(8080), "tcp", Some("ipv4"))]
+    #[case("[::1]:443", "::1", Some(443), "tcp", Some("ipv6"))]
+    #[case("example.com:80", "example.com", Some(80), "tcp", None)]
+    #[cfg_attr(
+        unix,
+        case(
+            "unix:///tmp/test_otel_attributes.socket",
+            "/tmp/test_otel_attributes.socket",
+            None,
+            "unix",
+            None
+        )
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case(
+            "unix://@test_otel_attributes.socket",
+            "@test_otel_attributes.socket",
+            None,
+            "unix",
+            None
+        )
+    )]
+    fn test_otel_attributes(
+        #[case] addr: &str,
+        #[case] server_address: &str,
+        #[case] server_port: Option<i64>,
+        #[case] network_transport: &str,
+        #[case] network_type: Option<&str>,
+    ) {
+        let addr = UniAddr::new(addr).unwrap();
+
+        let mut expected = vec![(
+            SERVER_ADDRESS,
+            AttributeValue::String(server_address.into()),
+        )];
+
+        if let Some(port) = server_port {
+            expected.push((SERVER_PORT, AttributeValue::Int(port)));
+        }
+
+        expected.push((
+            NETWORK_TRANSPORT,
+            AttributeValue::String(network_transport.into()),
+        ));
+
+        if let Some(network_type) = network_type {
+            expected.push((NETWORK_TYPE, AttributeValue::String(network_type.into())));
+        }
+
+        assert_eq!(addr.otel_attributes(), expected);
+    }
+}
@@ -0,0 +1,261 @@
+//! Pinning host names to known IP addresses and certificate public keys.
+//!
+//! High-security clients must not follow unexpected DNS or certificate
+//! changes. A [`PinStore`] maps host name patterns to the IP addresses they
+//! must resolve to, and to the SHA-256 hashes of the `SubjectPublicKeyInfo`
+//! (SPKI) their certificates must carry.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::net::{IpAddr, Ipv4Addr};
+//!
+//! use uni_addr::pin::PinStore;
+//! use uni_addr::UniAddr;
+//!
+//! let mut store = PinStore::new();
+//! store.pin_ips("*.internal.example.com", [IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))]);
+//!
+//! let mut addr = UniAddr::new("db.internal.example.com:5432").unwrap();
+//! addr.blocking_resolve_socket_addrs_with(|host| store.to_socket_addrs(host))
+//!     .unwrap();
+//!
+//! assert_eq!(addr.to_str(), "10.0.0.1:5432");
+//! ```
+
+use std::borrow::Cow;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::{io, vec};
+
+use crate::{matches_dns_name, split_host_port, UniAddr, UniAddrInner};
+
+#[derive(Debug, Clone, Default)]
+/// A store of pinned IP addresses and SPKI hashes, keyed by host name
+/// patterns.
+///
+/// A pattern is a DNS name, an IP address, or a DNS name with a leading
+/// wildcard label like `*.example.com`, matching exactly one label. Exact
+/// patterns take precedence over wildcard ones. Ports are not part of the
+/// pattern.
+pub struct PinStore {
+    pins: Vec<Pin>,
+}
+
+#[derive(Debug, Clone)]
+struct Pin {
+    pattern: String,
+    ips: Vec<IpAddr>,
+    spki_sha256: Vec<[u8; 32]>,
+}
+
+impl PinStore {
+    #[inline]
+    /// Creates an empty [`PinStore`].
+    pub const fn new() -> Self {
+        Self { pins: Vec::new() }
+    }
+
+    /// Pins the host names matching `pattern` to the given IP addresses.
+    pub fn pin_ips<I>(&mut self, pattern: &str, ips: I) -> &mut Self
+    where
+        I: IntoIterator<Item = IpAddr>,
+    {
+        self.pin_mut(pattern).ips.extend(ips);
+        self
+    }
+
+    /// Pins the certificates of the host names matching `pattern` to the given
+    /// SHA-256 hash of the `SubjectPublicKeyInfo`.
+    pub fn pin_spki_sha256(&mut self, pattern: &str, spki_sha256: [u8; 32]) -> &mut Self {
+        self.pin_mut(pattern).spki_sha256.push(spki_sha256);
+        self
+    }
+
+    /// Returns the IP addresses pinned for the address, if any.
+    pub fn pinned_ips(&self, addr: &UniAddr) -> Option<&[IpAddr]> {
+        let host = host_of(addr)?;
+
+        self.find(&host, |pin| !pin.ips.is_empty())
+            .map(|pin| &*pin.ips)
+    }
+
+    /// Returns the SPKI hashes pinned for the address, if any.
+    pub fn pinned_spki_sha256(&self, addr: &UniAddr) -> Option<&[[u8; 32]]> {
+        let host = host_of(addr)?;
+
+        self.find(&host, |pin| !pin.spki_sha256.is_empty())
+            .map(|pin| &*pin.spki_sha256)
+    }
+
+    /// Checks if `ip` is acceptable for the address, i.e., no IP addresses are
+    /// pinned for it, or `ip` is one of them.
+    ///
+    /// This is for callers resolving host names with their own resolver.
+    pub fn verify_ip(&self, addr: &UniAddr, ip: IpAddr) -> bool {
+        self.pinned_ips(addr).map_or(true, |ips| ips.contains(&ip))
+    }
+
+    /// Checks if the certificate public key is acceptable for the address,
+    /// i.e., no SPKI hashes are pinned for it, or `spki_sha256` is one of them.
+    ///
+    /// TLS layers should call this after the regular certificate verification.
+    pub fn verify_spki_sha256(&self, addr: &UniAddr, spki_sha256: &[u8; 32]) -> bool {
+        self.pinned_spki_sha256(addr)
+            .map_or(true, |hashes| hashes.contains(spki_sha256))
+    }
+
+    /// Resolves a host name with port like `example.com:443`, returning the
+    /// pinned IP addresses if any, or falling back to
+    /// [`ToSocketAddrs::to_socket_addrs`].
+    ///
+    /// Suitable for [`UniAddr::blocking_resolve_socket_addrs_with`].
+    ///
+    /// # Errors
+    ///
+    /// Invalid or missing port, or the resolution failed.
+    pub fn to_socket_addrs(&self, addr: &str) -> io::Result<vec::IntoIter<SocketAddr>> {
+        let (host, port) = split_host_port(addr);
+
+        let pinned = self
+            .find(host, |pin| !pin.ips.is_empty())
+            .map(|pin| &pin.ips);
+
+        match (pinned, port) {
+            (Some(ips), Some(port)) => Ok(ips
+                .iter()
+                .map(|ip| SocketAddr::new(*ip, port))
+                .collect::<Vec<_>>()
+                .into_iter()),
+            (Some(_), None) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid or missing port",
+            )),
+            (None, _) => addr.to_socket_addrs(),
+        }
+    }
+
+    fn pin_mut(&mut self, pattern: &str) -> &mut Pin {
+        let position = self
+            .pins
+            .iter()
+            .position(|pin| pin.pattern.eq_ignore_ascii_case(pattern));
+
+        let idx = if let Some(idx) = position {
+            idx
+        } else {
+            self.pins.push(Pin {
+                pattern: pattern.to_owned(),
+                ips: Vec::new(),
+                spki_sha256: Vec::new(),
+            });
+
+            self.pins.len() - 1
+        };
+
+        &mut self.pins[idx]
+    }
+
+    fn find<F>(&self, host: &str, f: F) -> Option<&Pin>
+    where
+        F: Fn(&Pin) -> bool,
+    {
+        self.pins
+            .iter()
+            .filter(|pin| f(pin))
+            .find(|pin| pin.pattern.eq_ignore_ascii_case(host))
+            .or_else(|| {
+                self.pins
+                    .iter()
+                    .filter(|pin| f(pin))
+                    .find(|pin| matches_dns_name(&pin.pattern, host))
+            })
+    }
+}
+
+/// Returns the host name or IP address the pins of the address are keyed by.
+fn host_of(addr: &UniAddr) -> Option<Cow<'_, str>> {
+    match addr.as_inner() {
+        UniAddrInner::Inet(addr) => Some(addr.ip().to_string().into()),
+        UniAddrInner::Host(addr) => Some(split_host_port(addr).0.into()),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use rstest::rstest;
+
+    use super::*;
+
+    const IP_1: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    const IP_2: IpAddr = IpAddr::V6(Ipv6Addr::LOCALHOST);
+
+    fn store() -> PinStore {
+        let mut store = PinStore::new();
+
+        store
+            .pin_ips("*.example.com", [IP_1])
+            .pin_ips("www.example.com", [IP_2])
+            .pin_spki_sha256("*.example.com", [1; 32])
+            .pin_spki_sha256("10.0.0.1", [2; 32]);
+
+        store
+    }
+
+    #[rstest]
+    #[case("www.example.com:443", Some(&[IP_2][..]))]
+    #[case("WWW.EXAMPLE.COM:443", Some(&[IP_2][..]))]
+    #[case("api.example.com:443", Some(&[IP_1][..]))]
+    #[case("example.com:443", None)]
+    #[case("a.b.example.com:443", None)]
+    #[case("10.0.0.1:443", None)]
+    fn test_pinned_ips(#[case] addr: &str, #[case] expected: Option<&[IpAddr]>) {
+        assert_eq!(store().pinned_ips(&UniAddr::new(addr).unwrap()), expected);
+    }
+
+    #[rstest]
+    #[case("www.example.com:443", &[1; 32], true)]
+    #[case("www.example.com:443", &[2; 32], false)]
+    #[case("10.0.0.1:443", &[2; 32], true)]
+    #[case("10.0.0.1:443", &[1; 32], false)]
+    #[case("example.org:443", &[1; 32], true)]
+    fn test_verify_spki_sha256(
+        #[case] addr: &str,
+        #[case] spki_sha256: &[u8; 32],
+        #[case] expected: bool,
+    ) {
+        assert_eq!(
+            store().verify_spki_sha256(&UniAddr::new(addr).unwrap(), spki_sha256),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_verify_ip() {
+        let store = store();
+
+        assert!(store.verify_ip(&UniAddr::new("api.example.com:443").unwrap(), IP_1));
+        assert!(!store.verify_ip(&UniAddr::new("api.example.com:443").unwrap(), IP_2));
+        assert!(store.verify_ip(&UniAddr::new("example.org:443").unwrap(), IP_2));
+    }
+
+    #[test]
+    fn test_to_socket_addrs() {
+        let store = store();
+
+        let mut addr = UniAddr::new("api.example.com:443").unwrap();
+        addr.blocking_resolve_socket_addrs_with(|host| store.to_socket_addrs(host))
+            .unwrap();
+
+        assert_eq!(addr.to_str(), "10.0.0.1:443");
+
+        let mut addr = UniAddr::new("localhost:443").unwrap();
+        addr.blocking_resolve_socket_addrs_with(|host| store.to_socket_addrs(host))
+            .unwrap();
+
+        assert!(matches!(addr.as_inner(), UniAddrInner::Inet(_)));
+    }
+}
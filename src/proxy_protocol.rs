@@ -0,0 +1,708 @@
+//! The PROXY protocol, versions 1 and 2, for services behind load
+//! balancers to learn the real address of their clients.
+//!
+//! See <https://www.haproxy.org/download/2.9/doc/proxy-protocol.txt>.
+//!
+//! Servers read the header with [`read_header`], and L4 proxies forwarding
+//! the identity of their clients write it with [`write_header`].
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::net::TcpListener;
+//!
+//! use uni_addr::proxy_protocol;
+//! use uni_addr::UniAddr;
+//!
+//! let listener = TcpListener::bind("0.0.0.0:8080").unwrap();
+//!
+//! for mut stream in listener.incoming().flatten() {
+//!     let header = proxy_protocol::read_header(&mut stream).unwrap();
+//!
+//!     let client_addr = match header.source() {
+//!         Some(source) => source.clone(),
+//!         None => UniAddr::from(stream.peer_addr().unwrap()),
+//!     };
+//!
+//!     println!("connection from {client_addr}");
+//! }
+//! ```
+
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use crate::UniAddr;
+
+/// The signature starting a version 2 header.
+const V2_SIGNATURE: [u8; 12] = *b"\r\n\r\n\0\r\nQUIT\n";
+
+/// The maximum length of a version 1 header, including the CRLF.
+const V1_MAX_LEN: usize = 107;
+
+/// The length of the `sun_path` fields of a version 2 header.
+const V2_UNIX_PATH_LEN: usize = 108;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A PROXY protocol header, carrying the addresses of the original connection.
+///
+/// Both addresses are `None` for `UNKNOWN` (version 1) and `LOCAL` (version 2)
+/// headers, e.g., health checks from the load balancer itself, and for address
+/// families not supported on this platform. The addresses of the connection
+/// itself should be used then.
+pub struct ProxyHeader {
+    source: Option<UniAddr>,
+    destination: Option<UniAddr>,
+}
+
+impl ProxyHeader {
+    #[inline]
+    /// Creates a new [`ProxyHeader`] carrying the addresses of the original
+    /// connection.
+    pub const fn new(source: UniAddr, destination: UniAddr) -> Self {
+        Self {
+            source: Some(source),
+            destination: Some(destination),
+        }
+    }
+
+    #[inline]
+    /// Creates a [`ProxyHeader`] without addresses, i.e., `UNKNOWN` (version
+    /// 1) or `LOCAL` (version 2).
+    pub const fn local() -> Self {
+        Self {
+            source: None,
+            destination: None,
+        }
+    }
+
+    #[inline]
+    /// Returns the address of the original client, if any.
+    pub const fn source(&self) -> Option<&UniAddr> {
+        self.source.as_ref()
+    }
+
+    #[inline]
+    /// Returns the original destination address, if any.
+    pub const fn destination(&self) -> Option<&UniAddr> {
+        self.destination.as_ref()
+    }
+
+    /// Encodes the header in the given version of the protocol.
+    ///
+    /// A version 1 header can only carry TCP addresses, others are encoded as
+    /// `UNKNOWN`. Mixed IPv4 and IPv6 addresses are encoded as IPv6, with the
+    /// IPv4 one mapped.
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::InvalidInput`] if only one of the addresses is present,
+    /// the addresses are of different types, or one is an unresolved host
+    /// name, or a Unix socket path is too long.
+    pub fn encode(&self, version: Version) -> io::Result<Vec<u8>> {
+        let addrs = match (&self.source, &self.destination) {
+            (Some(source), Some(destination)) => Some(Addrs::new(source, destination)?),
+            (None, None) => None,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "both or none of the addresses must be present",
+                ))
+            }
+        };
+
+        match version {
+            Version::V1 => Ok(encode_v1(addrs.as_ref()).into_bytes()),
+            Version::V2 => Ok(encode_v2(addrs.as_ref())),
+        }
+    }
+}
+
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A version of the PROXY protocol.
+pub enum Version {
+    /// The human-readable version 1.
+    V1,
+
+    /// The binary version 2.
+    V2,
+}
+
+/// Writes the header right after connecting to the upstream server, so that
+/// it learns the address of the original client.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::net::TcpStream;
+///
+/// use uni_addr::proxy_protocol::{self, ProxyHeader, Version};
+/// use uni_addr::UniAddr;
+///
+/// # let client_addr = UniAddr::new("192.0.2.1:56324").unwrap();
+/// # let local_addr = UniAddr::new("198.51.100.1:443").unwrap();
+/// let mut upstream = TcpStream::connect("127.0.0.1:8080").unwrap();
+///
+/// let header = ProxyHeader::new(client_addr, local_addr);
+/// proxy_protocol::write_header(&mut upstream, &header, Version::V2).unwrap();
+/// ```
+///
+/// # Errors
+///
+/// See [`ProxyHeader::encode`] and [`Write::write_all`].
+pub fn write_header<W: Write>(
+    mut writer: W,
+    header: &ProxyHeader,
+    version: Version,
+) -> io::Result<()> {
+    writer.write_all(&header.encode(version)?)
+}
+
+/// The pair of addresses of a header, of the same type.
+enum Addrs {
+    Inet(SocketAddr, SocketAddr),
+    #[cfg(unix)]
+    Unix(Vec<u8>, Vec<u8>),
+}
+
+impl Addrs {
+    fn new(source: &UniAddr, destination: &UniAddr) -> io::Result<Self> {
+        use crate::UniAddrInner;
+
+        match (source.as_inner(), destination.as_inner()) {
+            (UniAddrInner::Inet(source), UniAddrInner::Inet(destination)) => {
+                Ok(Self::Inet(*source, *destination))
+            }
+            #[cfg(unix)]
+            (UniAddrInner::Unix(source), UniAddrInner::Unix(destination)) => {
+                let (source, destination) = (unix_path(source), unix_path(destination));
+
+                if source.len() > V2_UNIX_PATH_LEN || destination.len() > V2_UNIX_PATH_LEN {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Unix socket path too long for a PROXY protocol header",
+                    ));
+                }
+
+                Ok(Self::Unix(source, destination))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "PROXY protocol headers need a pair of resolved IP or Unix socket addresses",
+            )),
+        }
+    }
+}
+
+fn encode_v1(addrs: Option<&Addrs>) -> String {
+    match addrs {
+        Some(Addrs::Inet(SocketAddr::V4(source), SocketAddr::V4(destination))) => format!(
+            "PROXY TCP4 {} {} {} {}\r\n",
+            source.ip(),
+            destination.ip(),
+            source.port(),
+            destination.port()
+        ),
+        Some(Addrs::Inet(source, destination)) => format!(
+            "PROXY TCP6 {} {} {} {}\r\n",
+            to_ipv6(source),
+            to_ipv6(destination),
+            source.port(),
+            destination.port()
+        ),
+        _ => "PROXY UNKNOWN\r\n".to_owned(),
+    }
+}
+
+fn encode_v2(addrs: Option<&Addrs>) -> Vec<u8> {
+    let mut header = V2_SIGNATURE.to_vec();
+
+    let (ver_cmd, family, payload) = match addrs {
+        // LOCAL, AF_UNSPEC
+        None => (0x20, 0x00, Vec::new()),
+        // PROXY, AF_INET, STREAM
+        Some(Addrs::Inet(SocketAddr::V4(source), SocketAddr::V4(destination))) => (
+            0x21,
+            0x11,
+            [
+                &source.ip().octets()[..],
+                &destination.ip().octets(),
+                &source.port().to_be_bytes(),
+                &destination.port().to_be_bytes(),
+            ]
+            .concat(),
+        ),
+        // PROXY, AF_INET6, STREAM
+        Some(Addrs::Inet(source, destination)) => (
+            0x21,
+            0x21,
+            [
+                &to_ipv6(source).octets()[..],
+                &to_ipv6(destination).octets(),
+                &source.port().to_be_bytes(),
+                &destination.port().to_be_bytes(),
+            ]
+            .concat(),
+        ),
+        // PROXY, AF_UNIX, STREAM
+        #[cfg(unix)]
+        Some(Addrs::Unix(source, destination)) => {
+            let mut payload = vec![0; V2_UNIX_PATH_LEN * 2];
+            payload[..source.len()].copy_from_slice(source);
+            payload[V2_UNIX_PATH_LEN..V2_UNIX_PATH_LEN + destination.len()]
+                .copy_from_slice(destination);
+
+            (0x21, 0x31, payload)
+        }
+    };
+
+    header.extend_from_slice(&[ver_cmd, family]);
+    header.extend_from_slice(
+        &u16::try_from(payload.len())
+            .unwrap_or(u16::MAX)
+            .to_be_bytes(),
+    );
+    header.extend_from_slice(&payload);
+
+    header
+}
+
+/// Returns the IP address of the socket address as IPv6, mapping IPv4 ones.
+fn to_ipv6(addr: &SocketAddr) -> Ipv6Addr {
+    match addr.ip() {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+#[cfg(unix)]
+/// Returns the `sun_path` bytes of the address, with a leading nul for an
+/// abstract address, and none for an unnamed one, padded to all zeros.
+fn unix_path(addr: &crate::unix::SocketAddr) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    if let Some(pathname) = addr.as_pathname() {
+        return pathname.as_os_str().as_bytes().to_vec();
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+    {
+        #[cfg(target_os = "android")]
+        use std::os::android::net::SocketAddrExt;
+        #[cfg(target_os = "cygwin")]
+        use std::os::cygwin::net::SocketAddrExt;
+        #[cfg(target_os = "linux")]
+        use std::os::linux::net::SocketAddrExt;
+
+        if let Some(name) = addr.as_abstract_name() {
+            return [&[0], name].concat();
+        }
+    }
+
+    Vec::new()
+}
+
+/// Reads a PROXY protocol header, of either version, from the start of an
+/// accepted stream.
+///
+/// Nothing past the header is read. Version 2 TLVs are skipped.
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidData`] if the stream does not start with a valid
+///   PROXY protocol header.
+/// - See [`Read::read_exact`].
+pub fn read_header<R: Read>(mut reader: R) -> io::Result<ProxyHeader> {
+    // Both versions of headers are at least this long.
+    let mut prefix = [0; 12];
+    reader.read_exact(&mut prefix)?;
+
+    if prefix == V2_SIGNATURE {
+        read_v2(reader)
+    } else if prefix.starts_with(b"PROXY ") {
+        read_v1(reader, &prefix)
+    } else {
+        Err(invalid_data("missing PROXY protocol header"))
+    }
+}
+
+fn read_v1<R: Read>(mut reader: R, prefix: &[u8]) -> io::Result<ProxyHeader> {
+    let mut line = prefix.to_vec();
+
+    while !line.ends_with(b"\r\n") {
+        if line.len() >= V1_MAX_LEN {
+            return Err(invalid_data("PROXY protocol v1 header too long"));
+        }
+
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        line.push(byte[0]);
+    }
+
+    let line = std::str::from_utf8(&line[..line.len() - 2])
+        .map_err(|_| invalid_data("invalid PROXY protocol v1 header"))?;
+
+    let mut parts = line.split(' ').skip(1);
+
+    match parts.next() {
+        Some("UNKNOWN") => Ok(ProxyHeader::default()),
+        Some("TCP4") => parse_v1_addrs(parts, true),
+        Some("TCP6") => parse_v1_addrs(parts, false),
+        _ => Err(invalid_data("invalid PROXY protocol v1 header")),
+    }
+}
+
+fn parse_v1_addrs<'a, I>(mut parts: I, is_ipv4: bool) -> io::Result<ProxyHeader>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let mut next = || {
+        parts
+            .next()
+            .ok_or_else(|| invalid_data("invalid PROXY protocol v1 header"))
+    };
+
+    let (source_ip, destination_ip) = (next()?, next()?);
+    let (source_port, destination_port) = (next()?, next()?);
+
+    if next().is_ok() {
+        return Err(invalid_data("invalid PROXY protocol v1 header"));
+    }
+
+    let parse = |ip: &str, port: &str| -> io::Result<UniAddr> {
+        let ip = if is_ipv4 {
+            ip.parse::<Ipv4Addr>().map(Into::into)
+        } else {
+            ip.parse::<Ipv6Addr>().map(Into::into)
+        }
+        .map_err(|_| invalid_data("invalid address in PROXY protocol v1 header"))?;
+
+        // Leading zeros are not allowed.
+        let port = port
+            .parse::<u16>()
+            .ok()
+            .filter(|parsed| parsed.to_string() == port)
+            .ok_or_else(|| invalid_data("invalid port in PROXY protocol v1 header"))?;
+
+        Ok(SocketAddr::new(ip, port).into())
+    };
+
+    Ok(ProxyHeader {
+        source: Some(parse(source_ip, source_port)?),
+        destination: Some(parse(destination_ip, destination_port)?),
+    })
+}
+
+fn read_v2<R: Read>(mut reader: R) -> io::Result<ProxyHeader> {
+    let mut header = [0; 4];
+    reader.read_exact(&mut header)?;
+
+    let [ver_cmd, family, len @ ..] = header;
+
+    if ver_cmd >> 4 != 2 {
+        return Err(invalid_data("unsupported PROXY protocol version"));
+    }
+
+    let mut payload = vec![0; usize::from(u16::from_be_bytes(len))];
+    reader.read_exact(&mut payload)?;
+
+    match ver_cmd & 0x0F {
+        // LOCAL
+        0x0 => return Ok(ProxyHeader::default()),
+        // PROXY
+        0x1 => {}
+        _ => return Err(invalid_data("invalid PROXY protocol v2 command")),
+    }
+
+    let too_short = || invalid_data("PROXY protocol v2 header too short");
+
+    match family >> 4 {
+        // AF_INET
+        0x1 => {
+            let addrs = payload.get(..12).ok_or_else(too_short)?;
+
+            let ip = |offset: usize| {
+                Ipv4Addr::from(<[u8; 4]>::try_from(&addrs[offset..offset + 4]).unwrap_or_default())
+            };
+            let port = |offset: usize| u16::from_be_bytes([addrs[offset], addrs[offset + 1]]);
+
+            Ok(ProxyHeader {
+                source: Some(SocketAddr::new(ip(0).into(), port(8)).into()),
+                destination: Some(SocketAddr::new(ip(4).into(), port(10)).into()),
+            })
+        }
+        // AF_INET6
+        0x2 => {
+            let addrs = payload.get(..36).ok_or_else(too_short)?;
+
+            let ip = |offset: usize| {
+                Ipv6Addr::from(
+                    <[u8; 16]>::try_from(&addrs[offset..offset + 16]).unwrap_or_default(),
+                )
+            };
+            let port = |offset: usize| u16::from_be_bytes([addrs[offset], addrs[offset + 1]]);
+
+            Ok(ProxyHeader {
+                source: Some(SocketAddr::new(ip(0).into(), port(32)).into()),
+                destination: Some(SocketAddr::new(ip(16).into(), port(34)).into()),
+            })
+        }
+        // AF_UNIX
+        0x3 => {
+            let addrs = payload.get(..V2_UNIX_PATH_LEN * 2).ok_or_else(too_short)?;
+
+            let (source, destination) = addrs.split_at(V2_UNIX_PATH_LEN);
+
+            Ok(ProxyHeader {
+                source: parse_v2_unix(source)?,
+                destination: parse_v2_unix(destination)?,
+            })
+        }
+        // AF_UNSPEC, or unknown.
+        _ => Ok(ProxyHeader::default()),
+    }
+}
+
+#[cfg(unix)]
+/// Parses a `sun_path` field, where a leading nul denotes an abstract address,
+/// and an all-zero one an unnamed address, like the one of a connecting client.
+fn parse_v2_unix(path: &[u8]) -> io::Result<Option<UniAddr>> {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let trim = |bytes: &[u8]| {
+        let len = bytes
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(bytes.len());
+
+        bytes[..len].to_vec()
+    };
+
+    let addr = match path {
+        [0, name @ ..] if trim(name).is_empty() => {
+            Ok(UniAddr::from(crate::unix::SocketAddr::new_unnamed()))
+        }
+        // Unsupported on platforms without the abstract namespace.
+        [0, name @ ..] => {
+            crate::unix::SocketAddr::new_strict(OsStr::from_bytes(&[b"@", &*trim(name)].concat()))
+                .map(UniAddr::from)
+        }
+        // Not `new_pathname`, which removes the socket file.
+        _ => std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(&trim(path)))
+            .map(UniAddr::from),
+    };
+
+    match addr {
+        Ok(addr) => Ok(Some(addr)),
+        Err(err) if err.kind() == io::ErrorKind::Unsupported => Ok(None),
+        Err(_) => Err(invalid_data(
+            "invalid Unix socket address in PROXY protocol v2 header",
+        )),
+    }
+}
+
+#[cfg(not(unix))]
+/// Unix socket addresses are not supported on this platform.
+fn parse_v2_unix(_path: &[u8]) -> io::Result<Option<UniAddr>> {
+    Ok(None)
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(
+        b"PROXY TCP4 192.0.2.1 198.51.100.1 56324 443\r\n",
+        Some("192.0.2.1:56324"),
+        Some("198.51.100.1:443")
+    )]
+    #[case(
+        b"PROXY TCP6 2001:db8::1 2001:db8::2 56324 443\r\n",
+        Some("[2001:db8::1]:56324"),
+        Some("[2001:db8::2]:443")
+    )]
+    #[case(b"PROXY UNKNOWN\r\n", None, None)]
+    #[case(b"PROXY UNKNOWN 192.0.2.1 198.51.100.1 56324 443\r\n", None, None)]
+    fn test_read_header_v1(
+        #[case] header: &[u8],
+        #[case] source: Option<&str>,
+        #[case] destination: Option<&str>,
+    ) {
+        let mut stream = header.to_vec();
+        stream.extend_from_slice(b"payload");
+
+        let mut reader = &*stream;
+        let header = read_header(&mut reader).unwrap();
+
+        assert_eq!(header.source().map(UniAddr::to_str).as_deref(), source);
+        assert_eq!(
+            header.destination().map(UniAddr::to_str).as_deref(),
+            destination
+        );
+        assert_eq!(reader, b"payload");
+    }
+
+    fn v2(ver_cmd: u8, family: u8, addrs: &[u8]) -> Vec<u8> {
+        let mut header = V2_SIGNATURE.to_vec();
+        header.extend_from_slice(&[ver_cmd, family]);
+        header.extend_from_slice(&u16::try_from(addrs.len()).unwrap().to_be_bytes());
+        header.extend_from_slice(addrs);
+        header
+    }
+
+    fn v2_unix(source: &[u8], destination: &[u8]) -> Vec<u8> {
+        let mut addrs = vec![0; V2_UNIX_PATH_LEN * 2];
+        addrs[..source.len()].copy_from_slice(source);
+        addrs[V2_UNIX_PATH_LEN..V2_UNIX_PATH_LEN + destination.len()].copy_from_slice(destination);
+
+        v2(0x21, 0x31, &addrs)
+    }
+
+    #[rstest]
+    #[case(
+        v2(0x21, 0x11, &[192, 0, 2, 1, 198, 51, 100, 1, 0xDC, 0x04, 0x01, 0xBB, 0x04, 0x00, 0x01, 0x00]),
+        Some("192.0.2.1:56324"),
+        Some("198.51.100.1:443")
+    )]
+    #[case(v2(0x20, 0x11, &[0; 12]), None, None)]
+    #[case(v2(0x21, 0x00, &[]), None, None)]
+    #[cfg_attr(
+        unix,
+        case(
+            v2_unix(b"/run/client.socket", b"/run/server.socket"),
+            Some("unix:///run/client.socket"),
+            Some("unix:///run/server.socket")
+        )
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case(
+            v2_unix(b"\0client.socket", b"\0server.socket"),
+            Some("unix://@client.socket"),
+            Some("unix://@server.socket")
+        )
+    )]
+    #[cfg_attr(
+        unix,
+        case(
+            v2_unix(b"", b"/run/server.socket"),
+            Some("unix://"),
+            Some("unix:///run/server.socket")
+        )
+    )]
+    fn test_read_header_v2(
+        #[case] header: Vec<u8>,
+        #[case] source: Option<&str>,
+        #[case] destination: Option<&str>,
+    ) {
+        let mut stream = header;
+        stream.extend_from_slice(b"payload");
+
+        let mut reader = &*stream;
+        let header = read_header(&mut reader).unwrap();
+
+        assert_eq!(header.source().map(UniAddr::to_str).as_deref(), source);
+        assert_eq!(
+            header.destination().map(UniAddr::to_str).as_deref(),
+            destination
+        );
+        assert_eq!(reader, b"payload");
+    }
+
+    #[rstest]
+    #[case::missing(b"GET / HTTP/1.1\r\n\r\n".to_vec())]
+    #[case::unknown_protocol(b"PROXY UDP4 192.0.2.1 198.51.100.1 1 2\r\n".to_vec())]
+    #[case::invalid_ip(b"PROXY TCP4 2001:db8::1 198.51.100.1 1 2\r\n".to_vec())]
+    #[case::leading_zero(b"PROXY TCP4 192.0.2.1 198.51.100.1 01 2\r\n".to_vec())]
+    #[case::extra_field(b"PROXY TCP4 192.0.2.1 198.51.100.1 1 2 3\r\n".to_vec())]
+    #[case::too_long([b"PROXY UNKNOWN ".as_slice(), &[b'x'; 128]].concat())]
+    #[case::invalid_version(v2(0x11, 0x11, &[0; 12]))]
+    #[case::too_short(v2(0x21, 0x11, &[0; 4]))]
+    fn test_read_header_invalid(#[case] stream: Vec<u8>) {
+        assert_eq!(
+            read_header(&*stream).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[rstest]
+    #[case(Version::V1, "192.0.2.1:56324", "198.51.100.1:443")]
+    #[case(Version::V2, "192.0.2.1:56324", "198.51.100.1:443")]
+    #[case(Version::V1, "[2001:db8::1]:56324", "[2001:db8::2]:443")]
+    #[case(Version::V2, "[2001:db8::1]:56324", "[2001:db8::2]:443")]
+    #[cfg_attr(
+        unix,
+        case(Version::V2, "unix:///run/client.socket", "unix:///run/server.socket")
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case(Version::V2, "unix://@client.socket", "unix://@server.socket")
+    )]
+    fn test_encode_roundtrip(
+        #[case] version: Version,
+        #[case] source: &str,
+        #[case] destination: &str,
+    ) {
+        let header = ProxyHeader::new(
+            UniAddr::new(source).unwrap(),
+            UniAddr::new(destination).unwrap(),
+        );
+
+        let mut stream = Vec::new();
+        write_header(&mut stream, &header, version).unwrap();
+
+        assert_eq!(read_header(&*stream).unwrap(), header);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_encode_roundtrip_unnamed() {
+        let header = ProxyHeader::new(
+            UniAddr::from(crate::unix::SocketAddr::new_unnamed()),
+            UniAddr::new("unix:///run/server.socket").unwrap(),
+        );
+
+        let mut stream = Vec::new();
+        write_header(&mut stream, &header, Version::V2).unwrap();
+
+        assert_eq!(read_header(&*stream).unwrap(), header);
+    }
+
+    #[rstest]
+    #[case(Version::V1, b"PROXY UNKNOWN\r\n".to_vec())]
+    #[case(Version::V2, v2(0x20, 0x00, &[]))]
+    fn test_encode_local(#[case] version: Version, #[case] expected: Vec<u8>) {
+        assert_eq!(ProxyHeader::local().encode(version).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_encode_mixed() {
+        let header = ProxyHeader::new(
+            UniAddr::new("192.0.2.1:56324").unwrap(),
+            UniAddr::new("[2001:db8::2]:443").unwrap(),
+        );
+
+        assert_eq!(
+            header.encode(Version::V1).unwrap(),
+            b"PROXY TCP6 ::ffff:192.0.2.1 2001:db8::2 56324 443\r\n"
+        );
+
+        let header = ProxyHeader::new(
+            UniAddr::new("example.com:56324").unwrap(),
+            UniAddr::new("198.51.100.1:443").unwrap(),
+        );
+
+        assert_eq!(
+            header.encode(Version::V2).unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Retrying host name resolution with backoff.
+//!
+//! A service starting before its peers' DNS records propagate (common in
+//! Kubernetes) sees resolution failures for a while. See
+//! [`UniAddr::blocking_resolve_socket_addrs_with_retry`] and its async
+//! counterpart.
+//!
+//! [`UniAddr::blocking_resolve_socket_addrs_with_retry`]: crate::UniAddr::blocking_resolve_socket_addrs_with_retry
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How to retry a failed resolution: an exponential backoff with jitter,
+/// bounded by a total deadline.
+///
+/// Each retry waits a random duration between half and all of the current
+/// backoff, which starts at [`initial_backoff`](Self::with_initial_backoff)
+/// and doubles after each attempt up to
+/// [`max_backoff`](Self::with_max_backoff).
+pub struct RetryPolicy {
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(30))
+    }
+}
+
+impl RetryPolicy {
+    #[inline]
+    /// Creates a new [`RetryPolicy`] giving up after `deadline` in total.
+    ///
+    /// The backoff starts at 100ms and is capped at 5s by default.
+    pub const fn new(deadline: Duration) -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            deadline,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets the backoff before the first retry.
+    pub const fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    /// Sets the maximum backoff between retries.
+    pub const fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Returns how long to wait before the next attempt, or `None` if the
+    /// deadline would be exceeded.
+    fn next_delay(&self, backoff: &mut Duration, started: Instant) -> Option<Duration> {
+        let delay = jitter(*backoff);
+
+        *backoff = backoff.saturating_mul(2).min(self.max_backoff);
+
+        (started.elapsed() + delay <= self.deadline).then_some(delay)
+    }
+}
+
+/// Checks if `err` may go away by retrying.
+///
+/// Resolution failures like `NXDOMAIN` have no dedicated kind, so only the
+/// kinds caused by the address itself, like a host name without port, are
+/// permanent.
+fn is_transient(err: &io::Error) -> bool {
+    !matches!(
+        err.kind(),
+        io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData | io::ErrorKind::Unsupported
+    )
+}
+
+/// Calls `f` until it succeeds or the deadline of `policy` is reached,
+/// returning the last error in the latter case, or a permanent error at once.
+pub(crate) fn blocking_retry<T, F>(policy: &RetryPolicy, mut f: F) -> io::Result<T>
+where
+    F: FnMut() -> io::Result<T>,
+{
+    let started = Instant::now();
+    let mut backoff = policy.initial_backoff.min(policy.max_backoff);
+
+    loop {
+        let err = match f() {
+            Ok(value) => return Ok(value),
+            Err(err) if is_transient(&err) => err,
+            Err(err) => return Err(err),
+        };
+
+        let Some(delay) = policy.next_delay(&mut backoff, started) else {
+            return Err(err);
+        };
+
+        std::thread::sleep(delay);
+    }
+}
+
+#[cfg(feature = "feat-tokio")]
+/// The async counterpart of [`blocking_retry`].
+pub(crate) async fn retry<T, F, Fut>(policy: &RetryPolicy, mut f: F) -> io::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = io::Result<T>>,
+{
+    let started = Instant::now();
+    let mut backoff = policy.initial_backoff.min(policy.max_backoff);
+
+    loop {
+        let err = match f().await {
+            Ok(value) => return Ok(value),
+            Err(err) if is_transient(&err) => err,
+            Err(err) => return Err(err),
+        };
+
+        let Some(delay) = policy.next_delay(&mut backoff, started) else {
+            return Err(err);
+        };
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Returns a random duration between half and all of `backoff`.
+fn jitter(backoff: Duration) -> Duration {
+    // `RandomState` is randomly seeded, which is good enough for jitter.
+    let random = RandomState::new().build_hasher().finish();
+
+    let half = backoff / 2;
+    let range = u64::try_from(backoff.saturating_sub(half).as_nanos()).unwrap_or(u64::MAX);
+
+    half + Duration::from_nanos(random % range.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jitter() {
+        for _ in 0..100 {
+            let delay = jitter(Duration::from_millis(100));
+
+            assert!(delay >= Duration::from_millis(50));
+            assert!(delay <= Duration::from_millis(100));
+        }
+
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_blocking_retry() {
+        let policy = RetryPolicy::new(Duration::from_secs(1))
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_backoff(Duration::from_millis(2));
+
+        let mut attempts = 0;
+        let value = blocking_retry(&policy, || {
+            attempts += 1;
+
+            if attempts < 3 {
+                Err(io::Error::new(io::ErrorKind::Other, "transient"))
+            } else {
+                Ok(attempts)
+            }
+        })
+        .unwrap();
+
+        assert_eq!(value, 3);
+    }
+
+    #[test]
+    fn test_blocking_retry_deadline() {
+        let policy = RetryPolicy::new(Duration::from_millis(20))
+            .with_initial_backoff(Duration::from_millis(5))
+            .with_max_backoff(Duration::from_millis(5));
+
+        let started = Instant::now();
+        let mut attempts = 0;
+        let err = blocking_retry::<(), _>(&policy, || {
+            attempts += 1;
+
+            Err(io::Error::new(io::ErrorKind::Other, "permanent"))
+        })
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "permanent");
+        assert!(attempts > 1);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_blocking_retry_permanent() {
+        let policy = RetryPolicy::new(Duration::from_secs(30));
+
+        let started = Instant::now();
+        let mut attempts = 0;
+        let err = blocking_retry::<(), _>(&policy, || {
+            attempts += 1;
+
+            Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid socket address",
+            ))
+        })
+        .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(attempts, 1);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "feat-tokio")]
+    #[tokio::test]
+    async fn test_retry() {
+        let policy = RetryPolicy::new(Duration::from_secs(1))
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_backoff(Duration::from_millis(2));
+
+        let mut attempts = 0;
+        let value = retry(&policy, || {
+            attempts += 1;
+
+            let attempts = attempts;
+
+            async move {
+                if attempts < 3 {
+                    Err(io::Error::new(io::ErrorKind::Other, "transient"))
+                } else {
+                    Ok(attempts)
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(value, 3);
+    }
+}
@@ -0,0 +1,491 @@
+//! An opt-in, tagged serde representation of [`UniAddr`], keeping the type of
+//! the address, and Unix domain socket names that are not valid UTF-8.
+//!
+//! In JSON, the addresses look like:
+//!
+//! - `{"inet": "192.0.2.1:80"}`
+//! - `{"unix": {"pathname": "/run/app.socket"}}`, `{"unix": {"abstract":
+//!   "app"}}`, or `{"unix": {}}` for an unnamed one. Names that are not valid
+//!   UTF-8 are serialized as bytes.
+//! - `{"vsock": [2, 1234]}`
+//! - `{"fd": 3}`
+//! - `{"pipe": "\\\\.\\pipe\\app"}`
+//! - `{"host": "example.com:80"}`
+//!
+//! [`UniAddr`] itself uses this representation in binary formats, i.e., when
+//! the format is not human-readable, where the IP addresses are encoded as raw
+//! octets. For data written by older versions, it also accepts the plain string
+//! representation there, as long as the format is self-describing.
+//!
+//! # Examples
+//!
+//! ```rust,ignore
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Config {
+//!     #[serde(with = "uni_addr::serde_tagged")]
+//!     listen: uni_addr::UniAddr,
+//! }
+//! ```
+
+use std::fmt;
+use std::net::SocketAddr;
+
+use serde::de::{self, EnumAccess, VariantAccess};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{UniAddr, UniAddrInner};
+
+const NAME: &str = "UniAddr";
+
+/// The variants, whose indices are stable regardless of the platform.
+const VARIANTS: &[&str] = &["inet", "unix", "vsock", "fd", "pipe", "host"];
+
+/// Serializes `addr` in the tagged representation.
+///
+/// # Errors
+///
+/// See [`Serializer`].
+pub fn serialize<S: Serializer>(addr: &UniAddr, serializer: S) -> Result<S::Ok, S::Error> {
+    match addr.as_inner() {
+        UniAddrInner::Inet(addr) => serializer.serialize_newtype_variant(NAME, 0, "inet", addr),
+        #[cfg(unix)]
+        UniAddrInner::Unix(addr) => {
+            serializer.serialize_newtype_variant(NAME, 1, "unix", &UnixName(addr))
+        }
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        UniAddrInner::Vsock(cid, port) => {
+            serializer.serialize_newtype_variant(NAME, 2, "vsock", &(cid, port))
+        }
+        #[cfg(unix)]
+        UniAddrInner::Fd(fd) => serializer.serialize_newtype_variant(NAME, 3, "fd", fd),
+        #[cfg(windows)]
+        UniAddrInner::Pipe(path) => serializer.serialize_newtype_variant(NAME, 4, "pipe", &**path),
+        UniAddrInner::Host(addr) => serializer.serialize_newtype_variant(NAME, 5, "host", &**addr),
+    }
+}
+
+/// Deserializes an address in the tagged representation.
+///
+/// # Errors
+///
+/// See [`Deserializer`]. Also fails if the address is invalid, or its type is
+/// unsupported on this platform.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<UniAddr, D::Error> {
+    deserializer.deserialize_enum(NAME, VARIANTS, TaggedVisitor { legacy: false })
+}
+
+/// Like [`deserialize`], but also accepts the plain string representation,
+/// which [`UniAddr`] used in binary formats before 0.4.0.
+pub(crate) fn deserialize_or_legacy<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<UniAddr, D::Error> {
+    deserializer.deserialize_enum(NAME, VARIANTS, TaggedVisitor { legacy: true })
+}
+
+struct TaggedVisitor {
+    legacy: bool,
+}
+
+impl<'de> de::Visitor<'de> for TaggedVisitor {
+    type Value = UniAddr;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.legacy {
+            formatter.write_str("a tagged address, or an address string")
+        } else {
+            formatter.write_str("a tagged address")
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        if self.legacy {
+            return de::Visitor::visit_str(crate::UniAddrVisitor, v);
+        }
+
+        Err(de::Error::invalid_type(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        // Self-describing formats present a plain string as a unit variant.
+        let (tag, variant) = match data.variant_seed(TagSeed {
+            legacy: self.legacy,
+        })? {
+            (Ok(tag), variant) => (tag, variant),
+            (Err(legacy), variant) => {
+                variant.unit_variant()?;
+                return de::Visitor::visit_str(crate::UniAddrVisitor, &legacy);
+            }
+        };
+
+        let unsupported = || de::Error::custom("unsupported address type on this platform");
+
+        match tag {
+            Tag::Inet => variant.newtype_variant::<SocketAddr>().map(UniAddr::from),
+            Tag::Unix => {
+                #[cfg(unix)]
+                return variant
+                    .newtype_variant::<UnixNameBuf>()
+                    .and_then(|UnixNameBuf(addr)| addr.map_err(de::Error::custom));
+
+                #[cfg(not(unix))]
+                return Err(unsupported());
+            }
+            Tag::Vsock => {
+                #[cfg(all(
+                    feature = "feat-vsock",
+                    any(target_os = "android", target_os = "linux")
+                ))]
+                return variant
+                    .newtype_variant::<(u32, u32)>()
+                    .map(|(cid, port)| UniAddr::new_vsock(cid, port));
+
+                #[cfg(not(all(
+                    feature = "feat-vsock",
+                    any(target_os = "android", target_os = "linux")
+                )))]
+                return Err(unsupported());
+            }
+            Tag::Fd => {
+                #[cfg(unix)]
+                return match variant.newtype_variant::<std::os::fd::RawFd>()? {
+                    fd if fd >= 0 => Ok(UniAddr::from_inner(UniAddrInner::Fd(fd))),
+                    _ => Err(de::Error::custom("invalid file descriptor number")),
+                };
+
+                #[cfg(not(unix))]
+                return Err(unsupported());
+            }
+            Tag::Pipe => {
+                #[cfg(windows)]
+                return UniAddr::new_pipe(&variant.newtype_variant::<String>()?)
+                    .map_err(de::Error::custom);
+
+                #[cfg(not(windows))]
+                return Err(unsupported());
+            }
+            // Not `UniAddr::new`, which accepts other types of addresses, and
+            // removes the file of a Unix socket.
+            Tag::Host => UniAddr::from_host_str(&variant.newtype_variant::<String>()?)
+                .map_err(de::Error::custom),
+        }
+    }
+}
+
+/// The variant identifier.
+enum Tag {
+    Inet,
+    Unix,
+    Vsock,
+    Fd,
+    Pipe,
+    Host,
+}
+
+/// Deserializes the variant identifier by name or by index, or with `legacy`,
+/// keeps an unknown name as an address string in the legacy representation.
+struct TagSeed {
+    legacy: bool,
+}
+
+impl<'de> de::DeserializeSeed<'de> for TagSeed {
+    type Value = Result<Tag, String>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl de::Visitor<'_> for TagSeed {
+    type Value = Result<Tag, String>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an address type")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        match usize::try_from(v).ok().and_then(|v| VARIANTS.get(v)) {
+            Some(v) => self.visit_str(v),
+            None => Err(de::Error::invalid_value(de::Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v {
+            "inet" => Ok(Ok(Tag::Inet)),
+            "unix" => Ok(Ok(Tag::Unix)),
+            "vsock" => Ok(Ok(Tag::Vsock)),
+            "fd" => Ok(Ok(Tag::Fd)),
+            "pipe" => Ok(Ok(Tag::Pipe)),
+            "host" => Ok(Ok(Tag::Host)),
+            _ if self.legacy => Ok(Err(v.to_owned())),
+            _ => Err(de::Error::unknown_variant(v, VARIANTS)),
+        }
+    }
+}
+
+#[cfg(unix)]
+/// Serializes a Unix domain socket address as a map with at most one entry,
+/// `pathname` or `abstract`.
+struct UnixName<'a>(&'a crate::unix::SocketAddr);
+
+#[cfg(unix)]
+impl Serialize for UnixName<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use std::os::unix::ffi::OsStrExt;
+
+        if let Some(pathname) = self.0.as_pathname() {
+            let mut map = serializer.serialize_map(Some(1))?;
+            map.serialize_entry("pathname", &Name(pathname.as_os_str().as_bytes()))?;
+            return map.end();
+        }
+
+        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+        {
+            #[cfg(target_os = "android")]
+            use std::os::android::net::SocketAddrExt;
+            #[cfg(target_os = "cygwin")]
+            use std::os::cygwin::net::SocketAddrExt;
+            #[cfg(target_os = "linux")]
+            use std::os::linux::net::SocketAddrExt;
+
+            if let Some(abstract_name) = self.0.as_abstract_name() {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("abstract", &Name(abstract_name))?;
+                return map.end();
+            }
+        }
+
+        // An unnamed one...
+        serializer.serialize_map(Some(0))?.end()
+    }
+}
+
+#[cfg(unix)]
+/// See [`UnixName`].
+struct UnixNameBuf(Result<UniAddr, std::io::Error>);
+
+#[cfg(unix)]
+impl<'de> Deserialize<'de> for UnixNameBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct UnixNameVisitor;
+
+        impl<'de> de::Visitor<'de> for UnixNameVisitor {
+            type Value = UnixNameBuf;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map with a `pathname` or `abstract` entry, or an empty map")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                use std::ffi::OsStr;
+                use std::os::unix::ffi::OsStrExt;
+
+                let Some(key) = map.next_key::<String>()? else {
+                    return Ok(UnixNameBuf(Ok(
+                        crate::unix::SocketAddr::new_unnamed().into()
+                    )));
+                };
+
+                let NameBuf(name) = map.next_value()?;
+
+                let addr = match &*key {
+                    // Not `new_pathname`, which removes the socket file.
+                    "pathname" => {
+                        std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(&name))
+                            .map(UniAddr::from)
+                    }
+                    #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+                    "abstract" => crate::unix::SocketAddr::new_abstract(&name).map(UniAddr::from),
+                    #[cfg(not(any(
+                        target_os = "android",
+                        target_os = "linux",
+                        target_os = "cygwin"
+                    )))]
+                    "abstract" => Err(std::io::Error::new(
+                        std::io::ErrorKind::Unsupported,
+                        "abstract unix socket address is not supported",
+                    )),
+                    _ => return Err(de::Error::unknown_field(&key, &["pathname", "abstract"])),
+                };
+
+                if map.next_key::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(2, &self));
+                }
+
+                Ok(UnixNameBuf(addr))
+            }
+        }
+
+        deserializer.deserialize_map(UnixNameVisitor)
+    }
+}
+
+/// Serializes a name as a string if it is valid UTF-8, or as bytes otherwise.
+struct Name<'a>(&'a [u8]);
+
+impl Serialize for Name<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(self.0) {
+            Ok(name) => serializer.serialize_str(name),
+            Err(_) => serializer.serialize_bytes(self.0),
+        }
+    }
+}
+
+/// Deserializes a name from a string, bytes, or a sequence of bytes.
+struct NameBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for NameBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NameVisitor;
+
+        impl<'de> de::Visitor<'de> for NameVisitor {
+            type Value = NameBuf;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a string or bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(NameBuf(v.as_bytes().to_vec()))
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(NameBuf(v.to_vec()))
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default().min(108));
+
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+
+                Ok(NameBuf(bytes))
+            }
+        }
+
+        deserializer.deserialize_bytes(NameVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::value::{BytesDeserializer, Error, MapAccessDeserializer, MapDeserializer};
+
+    use super::*;
+
+    /// Deserializes `{tag: value}` as the tagged representation.
+    fn from_entry<'de, V>(tag: &'static str, value: V) -> Result<UniAddr, Error>
+    where
+        V: de::IntoDeserializer<'de, Error>,
+    {
+        deserialize(MapAccessDeserializer::new(MapDeserializer::new(
+            [(tag, value)].into_iter(),
+        )))
+    }
+
+    #[test]
+    fn test_deserialize() {
+        assert_eq!(
+            from_entry("inet", "192.0.2.1:80").unwrap(),
+            UniAddr::new("192.0.2.1:80").unwrap()
+        );
+        assert_eq!(
+            from_entry("host", "example.com:80").unwrap(),
+            UniAddr::new("example.com:80").unwrap()
+        );
+
+        let _ = from_entry("inet", "example.com:80").unwrap_err();
+        let _ = from_entry("socks", "example.com:80").unwrap_err();
+        assert_eq!(
+            from_entry("host", "example.com").unwrap(),
+            UniAddr::new_optional_port("example.com").unwrap()
+        );
+        assert_eq!(
+            from_entry("host", "[::1]").unwrap(),
+            UniAddr::new_optional_port("::1").unwrap()
+        );
+
+        let _ = from_entry("host", "192.0.2.1:80").unwrap_err();
+        let _ = from_entry("host", "::1").unwrap_err();
+        let _ = from_entry("host", "dns://example.com:80").unwrap_err();
+        #[cfg(unix)]
+        let _ = from_entry("host", "fd://3").unwrap_err();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_deserialize_host_keeps_unix_file() {
+        let path = std::env::temp_dir().join(format!("uni-addr-serde-{}", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+
+        let result = from_entry("host", format!("unix://{}", path.display()));
+        let exists = path.exists();
+        let _ = std::fs::remove_file(&path);
+
+        let _ = result.unwrap_err();
+        assert!(exists);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_deserialize_unix() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let addr = from_entry(
+            "unix",
+            MapDeserializer::new([("pathname", "/tmp/test.socket")].into_iter()),
+        )
+        .unwrap();
+        assert_eq!(addr, "unix:///tmp/test.socket");
+
+        let addr = from_entry(
+            "unix",
+            MapDeserializer::new(
+                [("pathname", BytesDeserializer::new(b"/tmp/test-\xff.socket"))].into_iter(),
+            ),
+        )
+        .unwrap();
+        let expected = std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(
+            b"/tmp/test-\xff.socket",
+        ))
+        .unwrap();
+        assert_eq!(addr, UniAddr::from(expected));
+
+        let addr = from_entry(
+            "unix",
+            MapDeserializer::new(std::iter::empty::<(&str, &str)>()),
+        )
+        .unwrap();
+        assert_eq!(addr, UniAddr::from(crate::unix::SocketAddr::new_unnamed()));
+
+        let _ = from_entry(
+            "unix",
+            MapDeserializer::new([("path", "/tmp/test.socket")].into_iter()),
+        )
+        .unwrap_err();
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_deserialize_unix_abstract() {
+        let addr = from_entry(
+            "unix",
+            MapDeserializer::new(
+                [("abstract", BytesDeserializer::new(b"test-\0\xff"))].into_iter(),
+            ),
+        )
+        .unwrap();
+
+        assert_eq!(
+            addr,
+            UniAddr::from(crate::unix::SocketAddr::new_abstract(b"test-\0\xff").unwrap())
+        );
+    }
+}
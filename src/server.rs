@@ -0,0 +1,298 @@
+//! A minimal thread-per-connection server, shared by [`testing`] and
+//! [`health`].
+//!
+//! [`testing`]: crate::testing
+//! [`health`]: crate::health
+
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::{fmt, io, thread};
+
+use crate::{ToUniAddrs, UniAddr, UniAddrInner};
+
+/// A connection accepted by a server bound to a [`UniAddr`].
+///
+/// Only nameable from outside with `feat-testing`, which re-exports it.
+#[cfg_attr(not(feature = "feat-testing"), allow(unreachable_pub))]
+pub trait Connection: Read + Write + Send + fmt::Debug {}
+
+impl<T: Read + Write + Send + fmt::Debug> Connection for T {}
+
+#[derive(Debug, Clone, Copy, Default)]
+/// Limits on the connections of a server, none by default.
+pub(crate) struct Limits {
+    /// The read and write timeout of each accepted connection.
+    pub(crate) timeout: Option<Duration>,
+
+    /// The maximum number of connections handled at the same time. Further
+    /// connections are closed right after being accepted.
+    pub(crate) max_connections: Option<usize>,
+}
+
+/// Binds `addr` and spawns a thread accepting connections, calling `handler`
+/// on a dedicated thread for each accepted connection.
+pub(crate) fn spawn<A, F>(
+    addr: A,
+    name: &str,
+    limits: Limits,
+    handler: F,
+) -> io::Result<ServerHandle>
+where
+    A: ToUniAddrs,
+    F: Fn(Box<dyn Connection>) + Send + Sync + 'static,
+{
+    let listener = crate::each_addr(addr, Listener::bind)?;
+    let local_addr = listener.local_addr()?;
+
+    let handler = Arc::new(handler);
+    let is_shutdown = Arc::new(AtomicBool::new(false));
+    let active = Arc::new(AtomicUsize::new(0));
+
+    let handle = thread::Builder::new().name(name.to_owned()).spawn({
+        let is_shutdown = is_shutdown.clone();
+
+        move || {
+            while let Ok(conn) = listener.accept(limits.timeout) {
+                if is_shutdown.load(Ordering::Acquire) {
+                    break;
+                }
+
+                if limits
+                    .max_connections
+                    .is_some_and(|max| active.load(Ordering::Acquire) >= max)
+                {
+                    drop(conn);
+                    continue;
+                }
+
+                let handler = handler.clone();
+                let guard = ActiveGuard::new(&active);
+
+                thread::spawn(move || {
+                    let _guard = guard;
+
+                    handler(conn);
+                });
+            }
+        }
+    })?;
+
+    Ok(ServerHandle {
+        local_addr,
+        is_shutdown,
+        handle: Some(handle),
+    })
+}
+
+/// Counts a connection as active until dropped, see [`Limits::max_connections`].
+struct ActiveGuard(Arc<AtomicUsize>);
+
+impl ActiveGuard {
+    fn new(active: &Arc<AtomicUsize>) -> Self {
+        active.fetch_add(1, Ordering::AcqRel);
+
+        Self(active.clone())
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[derive(Debug)]
+/// A running server spawned with [`spawn`], which is shut down on drop.
+pub(crate) struct ServerHandle {
+    local_addr: UniAddr,
+    is_shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    #[inline]
+    pub(crate) const fn local_addr(&self) -> &UniAddr {
+        &self.local_addr
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        let Some(handle) = self.handle.take() else {
+            return;
+        };
+
+        self.is_shutdown.store(true, Ordering::Release);
+
+        // Wake up the accepting thread blocked in `accept`.
+        let waked = match self.local_addr.as_inner() {
+            UniAddrInner::Inet(addr) => {
+                let addr = match addr {
+                    SocketAddr::V4(addr) if addr.ip().is_unspecified() => {
+                        SocketAddr::new(Ipv4Addr::LOCALHOST.into(), addr.port())
+                    }
+                    SocketAddr::V6(addr) if addr.ip().is_unspecified() => {
+                        SocketAddr::new(Ipv6Addr::LOCALHOST.into(), addr.port())
+                    }
+                    addr => *addr,
+                };
+
+                TcpStream::connect(addr).is_ok()
+            }
+            #[cfg(unix)]
+            UniAddrInner::Unix(addr) => {
+                let waked = UnixStream::connect_addr(addr).is_ok();
+
+                if let Some(pathname) = addr.as_pathname() {
+                    let _ = std::fs::remove_file(pathname);
+                }
+
+                waked
+            }
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrInner::Vsock(..) => false,
+            #[cfg(unix)]
+            UniAddrInner::Fd(_) => false,
+            #[cfg(windows)]
+            UniAddrInner::Pipe(_) => false,
+            UniAddrInner::Host(_) => false,
+        };
+
+        if waked {
+            let _ = handle.join();
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    fn bind(addr: &UniAddr) -> io::Result<Self> {
+        match addr.as_inner() {
+            UniAddrInner::Inet(addr) => TcpListener::bind(addr).map(Self::Tcp),
+            #[cfg(unix)]
+            UniAddrInner::Unix(addr) => UnixListener::bind_addr(addr).map(Self::Unix),
+            #[cfg(all(
+                feature = "feat-vsock",
+                any(target_os = "android", target_os = "linux")
+            ))]
+            UniAddrInner::Vsock(..) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "vsock addresses are not supported by the server",
+            )),
+            #[cfg(unix)]
+            UniAddrInner::Fd(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "file descriptor addresses are not supported by the server",
+            )),
+            #[cfg(windows)]
+            UniAddrInner::Pipe(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "named pipe addresses are not supported by the server",
+            )),
+            UniAddrInner::Host(host) => TcpListener::bind(&**host).map(Self::Tcp),
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Tcp(listener) => listener.local_addr().map(UniAddr::from),
+            #[cfg(unix)]
+            Self::Unix(listener) => listener.local_addr().map(UniAddr::from),
+        }
+    }
+
+    fn accept(&self, timeout: Option<Duration>) -> io::Result<Box<dyn Connection>> {
+        match self {
+            Self::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+
+                stream.set_read_timeout(timeout)?;
+                stream.set_write_timeout(timeout)?;
+
+                Ok(Box::new(stream))
+            }
+            #[cfg(unix)]
+            Self::Unix(listener) => {
+                let (stream, _) = listener.accept()?;
+
+                stream.set_read_timeout(timeout)?;
+                stream.set_write_timeout(timeout)?;
+
+                Ok(Box::new(stream))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+/// Connects to a server bound to `addr`.
+pub(crate) fn connect(addr: &UniAddr) -> io::Result<Box<dyn Connection>> {
+    match addr.as_inner() {
+        #[cfg(unix)]
+        UniAddrInner::Unix(addr) => {
+            UnixStream::connect_addr(addr).map(|stream| Box::new(stream) as Box<dyn Connection>)
+        }
+        _ => TcpStream::connect(&*addr.to_str())
+            .map(|stream| Box::new(stream) as Box<dyn Connection>),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn test_spawn_limits() {
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(100)),
+            max_connections: Some(1),
+        };
+
+        let server = spawn("127.0.0.1:0", "uni-addr-test-limits", limits, |mut conn| {
+            let mut buf = [0; 1];
+
+            if conn.read_exact(&mut buf).is_ok() {
+                let _ = conn.write_all(&buf);
+            }
+        })
+        .unwrap();
+
+        // Holds the only slot, until the read times out.
+        let _idle = connect(server.local_addr()).unwrap();
+
+        let start = Instant::now();
+
+        loop {
+            let mut conn = connect(server.local_addr()).unwrap();
+            let mut buf = [0; 1];
+
+            if conn.write_all(b"x").is_ok() && conn.read_exact(&mut buf).is_ok() {
+                assert_eq!(&buf, b"x");
+                break;
+            }
+
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "the idle connection is never released"
+            );
+
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+}
@@ -0,0 +1,389 @@
+//! Routing TLS connections by the server name (SNI) of their `ClientHello`,
+//! without terminating TLS.
+//!
+//! [`read_client_hello`] reads the `ClientHello` off an accepted stream and
+//! extracts the server name, and a [`SniRouter`] maps it to the backend the
+//! connection should be forwarded to.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::io::{self, Write};
+//! use std::net::{TcpListener, TcpStream};
+//! use std::thread;
+//!
+//! use uni_addr::sni::{read_client_hello, SniRouter};
+//! use uni_addr::UniAddr;
+//!
+//! let mut router = SniRouter::new();
+//! router
+//!     .route("*.example.com", UniAddr::new("127.0.0.1:8443").unwrap())
+//!     .fallback(UniAddr::new("127.0.0.1:9443").unwrap());
+//!
+//! let listener = TcpListener::bind("0.0.0.0:443").unwrap();
+//!
+//! for mut client in listener.incoming().flatten() {
+//!     let client_hello = read_client_hello(&mut client).unwrap();
+//!
+//!     let Some(backend) = router.get(client_hello.server_name()) else {
+//!         continue;
+//!     };
+//!
+//!     let mut upstream = TcpStream::connect(&*backend.to_str()).unwrap();
+//!     upstream.write_all(client_hello.as_bytes()).unwrap();
+//!
+//!     let mut client_r = client.try_clone().unwrap();
+//!     let mut upstream_w = upstream.try_clone().unwrap();
+//!     thread::spawn(move || io::copy(&mut client_r, &mut upstream_w));
+//!     thread::spawn(move || io::copy(&mut upstream, &mut client));
+//! }
+//! ```
+
+use std::io::{self, Read};
+
+use crate::{matches_dns_name, UniAddr};
+
+/// The maximum length of the plaintext fragment of a TLS record.
+const MAX_RECORD_LEN: usize = 1 << 14;
+
+/// The maximum length of a `ClientHello` message accepted, spanning several
+/// records.
+const MAX_CLIENT_HELLO_LEN: usize = 1 << 16;
+
+#[derive(Debug, Clone, Default)]
+/// A table mapping server names to backend addresses.
+///
+/// A pattern is a DNS name, or a DNS name with a leading wildcard label like
+/// `*.example.com`, matching exactly one label. Exact patterns take precedence
+/// over wildcard ones.
+pub struct SniRouter {
+    routes: Vec<(String, UniAddr)>,
+    fallback: Option<UniAddr>,
+}
+
+impl SniRouter {
+    #[inline]
+    /// Creates an empty [`SniRouter`].
+    pub const fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback: None,
+        }
+    }
+
+    /// Routes the server names matching `pattern` to `backend`, replacing any
+    /// previous route of the same pattern.
+    pub fn route(&mut self, pattern: &str, backend: UniAddr) -> &mut Self {
+        match self
+            .routes
+            .iter_mut()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(pattern))
+        {
+            Some((_, existing)) => *existing = backend,
+            None => self.routes.push((pattern.to_owned(), backend)),
+        }
+
+        self
+    }
+
+    /// Sets the backend for connections without a server name, or with one no
+    /// route matches.
+    pub fn fallback(&mut self, backend: UniAddr) -> &mut Self {
+        self.fallback = Some(backend);
+        self
+    }
+
+    /// Returns the backend for the server name, see
+    /// [`ClientHello::server_name`].
+    pub fn get(&self, server_name: Option<&str>) -> Option<&UniAddr> {
+        let route = server_name
+            .map(|server_name| server_name.strip_suffix('.').unwrap_or(server_name))
+            .and_then(|server_name| {
+                self.routes
+                    .iter()
+                    .find(|(pattern, _)| pattern.eq_ignore_ascii_case(server_name))
+                    .or_else(|| {
+                        self.routes
+                            .iter()
+                            .find(|(pattern, _)| matches_dns_name(pattern, server_name))
+                    })
+            });
+
+        route.map(|(_, backend)| backend).or(self.fallback.as_ref())
+    }
+}
+
+#[derive(Debug, Clone)]
+/// A TLS `ClientHello` read by [`read_client_hello`].
+pub struct ClientHello {
+    bytes: Vec<u8>,
+    server_name: Option<String>,
+}
+
+impl ClientHello {
+    #[inline]
+    /// Returns the server name of the `server_name` extension, if any.
+    pub fn server_name(&self) -> Option<&str> {
+        self.server_name.as_deref()
+    }
+
+    #[inline]
+    /// Returns the raw TLS records read, which must be forwarded to the backend
+    /// before the rest of the stream.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline]
+    /// Returns the raw TLS records read, see [`as_bytes`](Self::as_bytes).
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads the TLS records carrying the `ClientHello` from the stream.
+///
+/// Nothing past the `ClientHello` is read.
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidData`] if the stream does not start with a
+///   well-formed `ClientHello`.
+/// - See [`Read::read_exact`].
+pub fn read_client_hello<R: Read>(mut reader: R) -> io::Result<ClientHello> {
+    let mut bytes = Vec::new();
+    let mut handshake = Vec::new();
+
+    loop {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+
+        // A handshake record.
+        if header[0] != 0x16 {
+            return Err(invalid_data("not a TLS handshake record"));
+        }
+
+        let len = usize::from(u16::from_be_bytes([header[3], header[4]]));
+
+        if len == 0 || len > MAX_RECORD_LEN {
+            return Err(invalid_data("invalid TLS record length"));
+        }
+
+        let start = bytes.len();
+        bytes.extend_from_slice(&header);
+        bytes.resize(start + header.len() + len, 0);
+        reader.read_exact(&mut bytes[start + header.len()..])?;
+
+        handshake.extend_from_slice(&bytes[start + header.len()..]);
+
+        if let [msg_type, a, b, c, ..] = *handshake {
+            // A `ClientHello` message.
+            if msg_type != 0x01 {
+                return Err(invalid_data("not a TLS ClientHello"));
+            }
+
+            let len = usize::from(a) << 16 | usize::from(b) << 8 | usize::from(c);
+
+            if len > MAX_CLIENT_HELLO_LEN {
+                return Err(invalid_data("TLS ClientHello too long"));
+            }
+
+            if handshake.len() >= 4 + len {
+                let server_name = parse_server_name(&handshake[4..4 + len])?;
+
+                return Ok(ClientHello { bytes, server_name });
+            }
+        }
+    }
+}
+
+/// Parses the body of a `ClientHello` message, returning the server name of
+/// the `server_name` extension, if any.
+fn parse_server_name(body: &[u8]) -> io::Result<Option<String>> {
+    let mut body = Cursor(body);
+
+    // `legacy_version` and `random`.
+    body.take(2 + 32)?;
+
+    // `legacy_session_id`, `cipher_suites` and `legacy_compression_methods`.
+    body.take_u8_prefixed()?;
+    body.take_u16_prefixed()?;
+    body.take_u8_prefixed()?;
+
+    if body.0.is_empty() {
+        return Ok(None);
+    }
+
+    let mut extensions = Cursor(body.take_u16_prefixed()?);
+
+    while !extensions.0.is_empty() {
+        let ty = extensions.take_u16()?;
+        let mut data = Cursor(extensions.take_u16_prefixed()?);
+
+        // The `server_name` extension.
+        if ty != 0x0000 {
+            continue;
+        }
+
+        let mut names = Cursor(data.take_u16_prefixed()?);
+
+        while !names.0.is_empty() {
+            let name_type = names.take(1)?[0];
+            let name = names.take_u16_prefixed()?;
+
+            // A `host_name`.
+            if name_type == 0x00 {
+                return std::str::from_utf8(name)
+                    .ok()
+                    .filter(|name| !name.is_empty() && name.is_ascii())
+                    .map(|name| Some(name.to_owned()))
+                    .ok_or_else(|| invalid_data("invalid TLS server name"));
+            }
+        }
+
+        return Ok(None);
+    }
+
+    Ok(None)
+}
+
+/// A cursor over TLS wire-format data.
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(invalid_data("truncated TLS ClientHello"));
+        }
+
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Ok(taken)
+    }
+
+    fn take_u16(&mut self) -> io::Result<u16> {
+        self.take(2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn take_u8_prefixed(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.take(1)?[0];
+
+        self.take(usize::from(len))
+    }
+
+    fn take_u16_prefixed(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.take_u16()?;
+
+        self.take(usize::from(len))
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(non_snake_case)]
+
+    use rstest::rstest;
+
+    use super::*;
+
+    /// Builds the handshake message of a minimal `ClientHello`.
+    fn handshake(server_name: Option<&str>) -> Vec<u8> {
+        let mut extensions = Vec::new();
+
+        // An unrelated `supported_versions` extension.
+        extensions.extend_from_slice(&[0x00, 0x2b, 0x00, 0x03, 0x02, 0x03, 0x04]);
+
+        if let Some(server_name) = server_name {
+            let len = u16::try_from(server_name.len()).unwrap();
+
+            extensions.extend_from_slice(&0x0000u16.to_be_bytes());
+            extensions.extend_from_slice(&(len + 5).to_be_bytes());
+            extensions.extend_from_slice(&(len + 3).to_be_bytes());
+            extensions.push(0x00);
+            extensions.extend_from_slice(&len.to_be_bytes());
+            extensions.extend_from_slice(server_name.as_bytes());
+        }
+
+        let mut body = vec![0x03, 0x03];
+        body.extend_from_slice(&[0; 32]);
+        body.push(0);
+        body.extend_from_slice(&[0x00, 0x02, 0x13, 0x01]);
+        body.extend_from_slice(&[0x01, 0x00]);
+        body.extend_from_slice(&u16::try_from(extensions.len()).unwrap().to_be_bytes());
+        body.extend_from_slice(&extensions);
+
+        let mut handshake = vec![0x01];
+        handshake.extend_from_slice(&u32::try_from(body.len()).unwrap().to_be_bytes()[1..]);
+        handshake.extend_from_slice(&body);
+
+        handshake
+    }
+
+    /// Wraps the handshake message into TLS records of at most `chunk` bytes.
+    fn records(handshake: &[u8], chunk: usize) -> Vec<u8> {
+        handshake
+            .chunks(chunk)
+            .flat_map(|fragment| {
+                let mut record = vec![0x16, 0x03, 0x01];
+                record.extend_from_slice(&u16::try_from(fragment.len()).unwrap().to_be_bytes());
+                record.extend_from_slice(fragment);
+                record
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(Some("example.com"), MAX_RECORD_LEN)]
+    #[case(Some("example.com"), 7)]
+    #[case(None, MAX_RECORD_LEN)]
+    fn test_read_client_hello(#[case] server_name: Option<&str>, #[case] chunk: usize) {
+        let records = records(&handshake(server_name), chunk);
+
+        let mut stream = records.clone();
+        stream.extend_from_slice(b"application data");
+
+        let mut reader = &*stream;
+        let client_hello = read_client_hello(&mut reader).unwrap();
+
+        assert_eq!(client_hello.server_name(), server_name);
+        assert_eq!(client_hello.as_bytes(), &*records);
+        assert_eq!(reader, b"application data");
+    }
+
+    #[rstest]
+    #[case::not_handshake(b"GET / HTTP/1.1\r\n\r\n")]
+    #[case::empty_record(&[0x16, 0x03, 0x01, 0x00, 0x00])]
+    #[case::not_client_hello(&[0x16, 0x03, 0x01, 0x00, 0x04, 0x02, 0x00, 0x00, 0x00])]
+    #[case::truncated(&[0x16, 0x03, 0x01, 0x00, 0x06, 0x01, 0x00, 0x00, 0x02, 0x03, 0x03])]
+    fn test_read_client_hello_invalid(#[case] stream: &[u8]) {
+        assert_eq!(
+            read_client_hello(stream).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[rstest]
+    #[case(Some("www.example.com"), Some("127.0.0.1:1"))]
+    #[case(Some("WWW.EXAMPLE.COM."), Some("127.0.0.1:1"))]
+    #[case(Some("api.example.com"), Some("127.0.0.1:2"))]
+    #[case(Some("a.b.example.com"), Some("127.0.0.1:3"))]
+    #[case(None, Some("127.0.0.1:3"))]
+    fn test_SniRouter_get(#[case] server_name: Option<&str>, #[case] expected: Option<&str>) {
+        let mut router = SniRouter::new();
+        router
+            .route("*.example.com", UniAddr::new("127.0.0.1:2").unwrap())
+            .route("www.example.com", UniAddr::new("127.0.0.1:1").unwrap())
+            .fallback(UniAddr::new("127.0.0.1:3").unwrap());
+
+        assert_eq!(
+            router.get(server_name).map(UniAddr::to_str).as_deref(),
+            expected
+        );
+    }
+}
@@ -0,0 +1,721 @@
+//! systemd socket activation, see `sd_listen_fds(3)`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! let listeners = uni_addr::systemd::listeners().unwrap();
+//!
+//! for listener in &listeners {
+//!     println!("inherited {}", listener.local_addr().unwrap());
+//! }
+//! ```
+//!
+//! Listening sockets can also be handed down to a new process image, e.g., for
+//! upgrading the binary of a running server, see [`SocketBundle`].
+
+#![allow(unsafe_code)]
+
+use std::ffi::c_int;
+use std::net::TcpListener;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixListener;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command};
+use std::sync::{Mutex, PoisonError};
+use std::{env, io};
+
+use crate::{ToUniAddrs, UniAddr, UniAddrInner};
+
+/// The first file descriptor passed by systemd, `SD_LISTEN_FDS_START`.
+const LISTEN_FDS_START: c_int = 3;
+
+/// The environment variable listing the file descriptors of a [`SocketBundle`].
+const BUNDLE_FDS: &str = "UNI_ADDR_FDS";
+
+/// The file descriptors whose ownership has been taken by this module.
+static OWNED: Mutex<Vec<RawFd>> = Mutex::new(Vec::new());
+
+#[derive(Debug)]
+/// A listening socket inherited from systemd.
+pub enum ActivatedListener {
+    /// A TCP listener, i.e., `ListenStream=` with an IP address or port.
+    Tcp(TcpListener),
+
+    /// A Unix domain socket listener, i.e., `ListenStream=` with a path or an
+    /// abstract name.
+    Unix(UnixListener),
+}
+
+impl AsFd for ActivatedListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Self::Tcp(listener) => listener.as_fd(),
+            Self::Unix(listener) => listener.as_fd(),
+        }
+    }
+}
+
+impl AsRawFd for ActivatedListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+impl IntoRawFd for ActivatedListener {
+    fn into_raw_fd(self) -> RawFd {
+        OwnedFd::from(self).into_raw_fd()
+    }
+}
+
+impl From<ActivatedListener> for OwnedFd {
+    fn from(listener: ActivatedListener) -> Self {
+        match listener {
+            ActivatedListener::Tcp(listener) => listener.into(),
+            ActivatedListener::Unix(listener) => listener.into(),
+        }
+    }
+}
+
+impl TryFrom<OwnedFd> for ActivatedListener {
+    type Error = io::Error;
+
+    /// Wraps a listening stream socket file descriptor, detecting whether it
+    /// is a TCP or a Unix domain socket listener.
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        classify(fd)
+    }
+}
+
+impl ActivatedListener {
+    /// Returns the local address of the listener.
+    ///
+    /// # Errors
+    ///
+    /// See [`TcpListener::local_addr`] and [`UnixListener::local_addr`].
+    pub fn local_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Tcp(listener) => listener.local_addr().map(UniAddr::from),
+            Self::Unix(listener) => listener.local_addr().map(UniAddr::from),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// A set of listening sockets to be handed down to a new process image, for
+/// upgrading a running server without closing its listeners.
+///
+/// The parent process collects its listeners with [`SocketBundle::push`], and
+/// spawns the new binary with [`SocketBundle::spawn`] (or replaces itself with
+/// [`SocketBundle::exec`]). The listeners are inherited by that process only,
+/// and are closed in the parent when the bundle is dropped.
+///
+/// The new process reconstructs the bundle with [`SocketBundle::from_env`],
+/// and takes its listeners back by address with [`SocketBundle::take`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::process::Command;
+///
+/// use uni_addr::systemd::{bind_or_inherit, SocketBundle};
+/// use uni_addr::UniAddr;
+///
+/// let addr = UniAddr::new("0.0.0.0:8080").unwrap();
+///
+/// // In the new process, or in the first one.
+/// let listener = match SocketBundle::from_env().unwrap().take(&addr) {
+///     Some(listener) => listener,
+///     None => bind_or_inherit(&addr).unwrap(),
+/// };
+///
+/// // Upon reload.
+/// let mut bundle = SocketBundle::new();
+/// bundle.push(listener).unwrap();
+/// bundle.spawn(&mut Command::new("/proc/self/exe")).unwrap();
+/// ```
+pub struct SocketBundle {
+    listeners: Vec<(UniAddr, ActivatedListener)>,
+}
+
+impl SocketBundle {
+    /// Creates an empty [`SocketBundle`].
+    pub const fn new() -> Self {
+        Self {
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Adds a listener to the bundle.
+    ///
+    /// # Errors
+    ///
+    /// See [`ActivatedListener::local_addr`].
+    pub fn push(&mut self, listener: ActivatedListener) -> io::Result<&mut Self> {
+        self.listeners.push((listener.local_addr()?, listener));
+
+        Ok(self)
+    }
+
+    /// Reconstructs the bundle handed down by the parent process, taking the
+    /// ownership of the inherited file descriptors listed in `UNI_ADDR_FDS`.
+    ///
+    /// Returns an empty bundle if the variable is not set. The environment
+    /// variable is left untouched.
+    ///
+    /// # Errors
+    ///
+    /// - `UNI_ADDR_FDS` is malformed.
+    /// - An inherited file descriptor is invalid, has already been taken, or
+    ///   is not a listening stream socket of a supported address family.
+    pub fn from_env() -> io::Result<Self> {
+        match env::var(BUNDLE_FDS) {
+            Ok(fds) => Self::from_fds(&fds),
+            Err(_) => Ok(Self::new()),
+        }
+    }
+
+    fn from_fds(fds: &str) -> io::Result<Self> {
+        let fds = parse_bundle_fds(fds)?;
+
+        // Take the ownership of all file descriptors first, so that they are
+        // closed on error.
+        let fds = fds
+            .into_iter()
+            .map(take_raw_fd)
+            .collect::<io::Result<Vec<_>>>()?;
+
+        let mut bundle = Self::new();
+
+        for fd in fds {
+            bundle.push(classify(fd)?)?;
+        }
+
+        Ok(bundle)
+    }
+
+    /// Takes the listener bound to `addr` out of the bundle, comparing it with
+    /// the `getsockname()` of each listener.
+    pub fn take(&mut self, addr: &UniAddr) -> Option<ActivatedListener> {
+        let idx = self
+            .listeners
+            .iter()
+            .position(|(local_addr, _)| local_addr == addr)?;
+
+        Some(self.listeners.remove(idx).1)
+    }
+
+    /// Returns the addresses of the listeners in the bundle.
+    pub fn addrs(&self) -> impl Iterator<Item = &UniAddr> + '_ {
+        self.listeners.iter().map(|(addr, _)| addr)
+    }
+
+    /// Returns the number of listeners in the bundle.
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    /// Returns `true` if the bundle contains no listeners.
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// Consumes the bundle, returning the listeners along with their
+    /// addresses.
+    pub fn into_listeners(self) -> Vec<(UniAddr, ActivatedListener)> {
+        self.listeners
+    }
+
+    /// Spawns `command` with the listeners inherited, see [`Command::spawn`].
+    ///
+    /// # Errors
+    ///
+    /// See [`Command::spawn`].
+    pub fn spawn(&self, command: &mut Command) -> io::Result<Child> {
+        self.prepare(command).spawn()
+    }
+
+    /// Replaces the current process image with `command`, with the listeners
+    /// inherited, see [`CommandExt::exec`].
+    ///
+    /// Returns only on error.
+    pub fn exec(&self, command: &mut Command) -> io::Error {
+        self.prepare(command).exec()
+    }
+
+    fn prepare<'c>(&self, command: &'c mut Command) -> &'c mut Command {
+        let fds = self
+            .listeners
+            .iter()
+            .map(|(_, listener)| listener.as_raw_fd())
+            .collect::<Vec<_>>();
+
+        command.env(
+            BUNDLE_FDS,
+            fds.iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+
+        // SAFETY: `fcntl` is async-signal-safe, and the file descriptors stay
+        // open until the command is spawned, as they are borrowed from `self`.
+        unsafe {
+            command.pre_exec(move || {
+                for &fd in &fds {
+                    // Keep the file descriptors open across `exec` in the
+                    // child only, never leaking them to other processes.
+                    if libc::fcntl(fd, libc::F_SETFD, 0) == -1 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+
+                Ok(())
+            })
+        }
+    }
+}
+
+/// Takes all the remaining listening sockets passed by systemd socket
+/// activation.
+///
+/// Reads `LISTEN_PID` and `LISTEN_FDS`, and returns an empty list if the
+/// process is not socket activated, or the sockets have already been taken.
+/// The environment variables are left untouched.
+///
+/// # Errors
+///
+/// - `LISTEN_PID` or `LISTEN_FDS` is malformed.
+/// - An inherited file descriptor is not a listening stream socket of a
+///   supported address family (`AF_INET`, `AF_INET6` or `AF_UNIX`).
+pub fn listeners() -> io::Result<Vec<ActivatedListener>> {
+    take(|_| true)
+}
+
+/// Takes the remaining listening sockets with the given name, i.e., the
+/// `FileDescriptorName=` of the socket unit, passed in `LISTEN_FDNAMES`.
+///
+/// Sockets without a name are named `unknown`, like `sd_listen_fds_with_names`
+/// does.
+///
+/// # Errors
+///
+/// See [`listeners`].
+pub fn listeners_named(name: &str) -> io::Result<Vec<ActivatedListener>> {
+    take(|inherited| inherited.name == name)
+}
+
+/// Takes the remaining listening socket bound to `addr`, comparing it with the
+/// `getsockname()` of each inherited socket, or binds a new one if the process
+/// is not socket activated for this address.
+///
+/// For an `fd://N` address, takes the ownership of the file descriptor `N`
+/// instead, after validating that it is a listening stream socket.
+///
+/// # Errors
+///
+/// See [`listeners`], [`TcpListener::bind`] and [`UnixListener::bind_addr`].
+/// Each address is tried in turn, returning the error of the last one if none
+/// succeeds. Also returns an error if the file descriptor of an `fd://N` address is
+/// invalid, or has already been taken.
+pub fn bind_or_inherit<A: ToUniAddrs>(addr: A) -> io::Result<ActivatedListener> {
+    crate::each_addr(addr, bind_or_inherit_one)
+}
+
+fn bind_or_inherit_one(addr: &UniAddr) -> io::Result<ActivatedListener> {
+    if let UniAddrInner::Fd(fd) = addr.as_inner() {
+        if let Some(listener) = take(|inherited| inherited.listener.as_raw_fd() == *fd)?.pop() {
+            return Ok(listener);
+        }
+
+        return take_raw_fd(*fd).and_then(classify);
+    }
+
+    if let Some(listener) = take(|inherited| inherited.local_addr.as_ref() == Some(addr))?.pop() {
+        return Ok(listener);
+    }
+
+    match addr.as_inner() {
+        UniAddrInner::Inet(addr) => TcpListener::bind(addr).map(ActivatedListener::Tcp),
+        UniAddrInner::Unix(addr) => UnixListener::bind_addr(addr).map(ActivatedListener::Unix),
+        UniAddrInner::Host(host) => TcpListener::bind(&**host).map(ActivatedListener::Tcp),
+        #[allow(unreachable_patterns)]
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "unsupported address type for a listener",
+        )),
+    }
+}
+
+/// An inherited listener not taken yet.
+struct Inherited {
+    name: String,
+    local_addr: Option<UniAddr>,
+    listener: ActivatedListener,
+}
+
+/// Removes the inherited listeners matching `f` from the pool, taking the
+/// inherited file descriptors into the pool first if not yet.
+fn take<F>(mut f: F) -> io::Result<Vec<ActivatedListener>>
+where
+    F: FnMut(&Inherited) -> bool,
+{
+    static POOL: Mutex<Option<Vec<Inherited>>> = Mutex::new(None);
+
+    let mut pool = POOL.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let pool = if let Some(pool) = &mut *pool {
+        pool
+    } else {
+        // Never retry taking the file descriptors, even on error.
+        let pool = pool.insert(Vec::new());
+
+        *pool = inherit()?
+            .into_iter()
+            .map(|(name, listener)| Inherited {
+                name,
+                local_addr: listener.local_addr().ok(),
+                listener,
+            })
+            .collect();
+
+        pool
+    };
+
+    let mut taken = Vec::new();
+    let mut idx = 0;
+
+    while idx < pool.len() {
+        if f(&pool[idx]) {
+            taken.push(pool.remove(idx).listener);
+        } else {
+            idx += 1;
+        }
+    }
+
+    Ok(taken)
+}
+
+/// Takes the ownership of the inherited file descriptors, along with their
+/// names.
+fn inherit() -> io::Result<Vec<(String, ActivatedListener)>> {
+    let count = listen_fds_count(
+        env::var("LISTEN_PID").ok().as_deref(),
+        env::var("LISTEN_FDS").ok().as_deref(),
+    )?;
+
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let names = listen_fd_names(
+        env::var("LISTEN_FDNAMES").ok().as_deref(),
+        usize::try_from(count).unwrap_or_default(),
+    );
+
+    let mut owned = OWNED.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // Take the ownership of all file descriptors first, so that they are closed
+    // on error. Skip the ones taken as `fd://N` already.
+    let fds = (LISTEN_FDS_START..LISTEN_FDS_START + count)
+        .zip(names)
+        .filter_map(|(fd, name)| {
+            if owned.contains(&fd) {
+                return None;
+            }
+
+            owned.push(fd);
+
+            // SAFETY: systemd passes `count` open file descriptors starting at
+            // `SD_LISTEN_FDS_START`, and `OWNED` ensures they are owned once.
+            Some((name, unsafe { OwnedFd::from_raw_fd(fd) }))
+        })
+        .collect::<Vec<_>>();
+
+    drop(owned);
+
+    fds.into_iter()
+        .map(|(name, fd)| classify(fd).map(|listener| (name, listener)))
+        .collect()
+}
+
+/// Takes the ownership of the file descriptor `fd` handed down by the parent
+/// process.
+fn take_raw_fd(fd: RawFd) -> io::Result<OwnedFd> {
+    let mut owned = OWNED.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if owned.contains(&fd) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the file descriptor has already been taken",
+        ));
+    }
+
+    // SAFETY: `F_GETFD` has no side effects, and fails for an invalid `fd`.
+    if unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    owned.push(fd);
+
+    // SAFETY: `fd` is open, and `OWNED` ensures it is owned once.
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// Parses `LISTEN_FDNAMES`, falling back to `unknown` for each file descriptor
+/// if it is missing or does not match `LISTEN_FDS`.
+fn listen_fd_names(listen_fdnames: Option<&str>, count: usize) -> Vec<String> {
+    match listen_fdnames {
+        Some(names) if names.split(':').count() == count => {
+            names.split(':').map(String::from).collect()
+        }
+        _ => vec![String::from("unknown"); count],
+    }
+}
+
+/// Parses `LISTEN_PID` and `LISTEN_FDS`, returning the number of inherited
+/// file descriptors.
+fn listen_fds_count(listen_pid: Option<&str>, listen_fds: Option<&str>) -> io::Result<c_int> {
+    let (Some(listen_pid), Some(listen_fds)) = (listen_pid, listen_fds) else {
+        return Ok(0);
+    };
+
+    let Ok(listen_pid) = listen_pid.parse::<u32>() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid LISTEN_PID",
+        ));
+    };
+
+    // The file descriptors are passed to another process.
+    if listen_pid != std::process::id() {
+        return Ok(0);
+    }
+
+    match listen_fds.parse::<c_int>() {
+        Ok(count) if (0..=c_int::MAX - LISTEN_FDS_START).contains(&count) => Ok(count),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid LISTEN_FDS",
+        )),
+    }
+}
+
+/// Parses `UNI_ADDR_FDS`, a comma-separated list of file descriptors.
+fn parse_bundle_fds(fds: &str) -> io::Result<Vec<RawFd>> {
+    if fds.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    fds.split(',')
+        .map(|fd| match fd.parse::<RawFd>() {
+            Ok(fd) if fd >= 0 => Ok(fd),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid UNI_ADDR_FDS",
+            )),
+        })
+        .collect()
+}
+
+/// Checks the inherited file descriptor, and wraps it in the right listener
+/// type.
+fn classify(fd: OwnedFd) -> io::Result<ActivatedListener> {
+    // SAFETY: `fd` is a valid, owned file descriptor.
+    if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    if crate::unix::getsockopt_int(fd.as_fd(), libc::SO_TYPE)? != libc::SOCK_STREAM
+        || crate::unix::getsockopt_int(fd.as_fd(), libc::SO_ACCEPTCONN)? == 0
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the inherited file descriptor is not a listening stream socket",
+        ));
+    }
+
+    match crate::unix::getsockopt_int(fd.as_fd(), libc::SO_DOMAIN)? {
+        libc::AF_INET | libc::AF_INET6 => Ok(ActivatedListener::Tcp(TcpListener::from(fd))),
+        libc::AF_UNIX => Ok(ActivatedListener::Unix(UnixListener::from(fd))),
+        _ => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the inherited socket has an unsupported address family",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::UdpSocket;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(None, None, 0)]
+    #[case(None, Some("1"), 0)]
+    #[case(Some("1"), Some("1"), 0)]
+    #[should_panic]
+    #[case::panic(Some("pid"), Some("1"), 0)]
+    fn test_listen_fds_count(
+        #[case] listen_pid: Option<&str>,
+        #[case] listen_fds: Option<&str>,
+        #[case] expected: c_int,
+    ) {
+        assert_eq!(listen_fds_count(listen_pid, listen_fds).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("2", 2)]
+    #[case("0", 0)]
+    #[should_panic]
+    #[case::panic("-1", 0)]
+    #[should_panic]
+    #[case::panic("fds", 0)]
+    fn test_listen_fds_count_current_process(#[case] listen_fds: &str, #[case] expected: c_int) {
+        let listen_pid = std::process::id().to_string();
+
+        assert_eq!(
+            listen_fds_count(Some(&listen_pid), Some(listen_fds)).unwrap(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(None, 2, vec!["unknown", "unknown"])]
+    #[case(Some("http:https"), 2, vec!["http", "https"])]
+    #[case(Some("http::https"), 3, vec!["http", "", "https"])]
+    #[case(Some("http"), 2, vec!["unknown", "unknown"])]
+    fn test_listen_fd_names(
+        #[case] listen_fdnames: Option<&str>,
+        #[case] count: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        assert_eq!(listen_fd_names(listen_fdnames, count), expected);
+    }
+
+    #[test]
+    fn test_bind_or_inherit_fd() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        // Leak the file descriptor, as if handed down by the parent process.
+        let fd = std::os::fd::IntoRawFd::into_raw_fd(listener);
+
+        let addr = UniAddr::new(&format!("fd://{fd}")).unwrap();
+
+        let listener = bind_or_inherit(&addr).unwrap();
+        assert_eq!(listener.local_addr().unwrap(), UniAddr::from(local_addr));
+
+        // Taken already.
+        let _ = bind_or_inherit(&addr).unwrap_err();
+    }
+
+    #[test]
+    fn test_bind_or_inherit_not_activated() {
+        let listener = bind_or_inherit("127.0.0.1:0").unwrap();
+
+        assert!(matches!(listener, ActivatedListener::Tcp(_)));
+    }
+
+    #[test]
+    fn test_classify() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let listener = classify(OwnedFd::from(listener)).unwrap();
+        assert!(matches!(listener, ActivatedListener::Tcp(_)));
+        assert_eq!(listener.local_addr().unwrap(), UniAddr::from(addr));
+
+        let listener =
+            UnixListener::bind_addr(&std::os::unix::net::SocketAddr::from_pathname("").unwrap())
+                .unwrap();
+
+        let listener = classify(OwnedFd::from(listener)).unwrap();
+        assert!(matches!(listener, ActivatedListener::Unix(_)));
+
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let _ = classify(OwnedFd::from(socket)).unwrap_err();
+    }
+
+    #[rstest]
+    #[case("", vec![])]
+    #[case("3", vec![3])]
+    #[case("3,10,4", vec![3, 10, 4])]
+    #[should_panic]
+    #[case::panic("3,", vec![])]
+    #[should_panic]
+    #[case::panic("-1", vec![])]
+    #[should_panic]
+    #[case::panic("fd", vec![])]
+    fn test_parse_bundle_fds(#[case] fds: &str, #[case] expected: Vec<RawFd>) {
+        assert_eq!(parse_bundle_fds(fds).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_socket_bundle_from_fds() {
+        let tcp = TcpListener::bind("127.0.0.1:0").unwrap();
+        let tcp_addr = UniAddr::from(tcp.local_addr().unwrap());
+
+        let unix = UnixListener::bind_addr(
+            &crate::unix::SocketAddr::new("@uni-addr-test-socket-bundle").unwrap(),
+        )
+        .unwrap();
+        let unix_addr = UniAddr::from(unix.local_addr().unwrap());
+
+        // Leak the file descriptors, as if handed down by the parent process.
+        // Moved out of the range of other tests, as `OWNED` keeps the numbers of
+        // file descriptors taken and closed already.
+        let leak = |fd: OwnedFd| {
+            // SAFETY: `fd` is valid, and `F_DUPFD_CLOEXEC` has no other effect.
+            let high = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_DUPFD_CLOEXEC, 512) };
+            assert_ne!(high, -1);
+            high
+        };
+        let fds = format!("{},{}", leak(tcp.into()), leak(unix.into()));
+
+        let mut bundle = SocketBundle::from_fds(&fds).unwrap();
+        assert_eq!(bundle.len(), 2);
+        assert_eq!(bundle.addrs().collect::<Vec<_>>(), [&tcp_addr, &unix_addr]);
+
+        assert!(matches!(
+            bundle.take(&unix_addr),
+            Some(ActivatedListener::Unix(_))
+        ));
+        assert!(bundle.take(&unix_addr).is_none());
+        assert!(matches!(
+            bundle.take(&tcp_addr),
+            Some(ActivatedListener::Tcp(_))
+        ));
+        assert!(bundle.is_empty());
+
+        // Taken already.
+        let _ = SocketBundle::from_fds(&fds).unwrap_err();
+    }
+
+    #[test]
+    fn test_socket_bundle_spawn() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let fd = listener.as_raw_fd();
+
+        let mut bundle = SocketBundle::new();
+        bundle.push(ActivatedListener::Tcp(listener)).unwrap();
+
+        let script = format!(r#"[ "$UNI_ADDR_FDS" = "{fd}" ] && [ -e /proc/self/fd/{fd} ]"#);
+
+        let status = bundle
+            .spawn(Command::new("sh").args(["-c", &script]))
+            .unwrap()
+            .wait()
+            .unwrap();
+        assert!(status.success());
+
+        // Not leaked to other processes.
+        let status = Command::new("sh")
+            .args(["-c", &format!("[ -e /proc/self/fd/{fd} ]")])
+            .status()
+            .unwrap();
+        assert!(!status.success());
+    }
+}
@@ -0,0 +1,132 @@
+//! Utilities for writing transport-agnostic integration tests.
+//!
+//! # Examples
+//!
+//! ```rust
+//! # use std::io::{Read, Write};
+//! # use std::net::TcpStream;
+//! use uni_addr::UniAddr;
+//! use uni_addr::testing::spawn_echo_server;
+//!
+//! let server = spawn_echo_server(&UniAddr::new("127.0.0.1:0").unwrap()).unwrap();
+//!
+//! let mut stream = TcpStream::connect(&*server.local_addr().to_str()).unwrap();
+//! stream.write_all(b"ping").unwrap();
+//!
+//! let mut buf = [0; 4];
+//! stream.read_exact(&mut buf).unwrap();
+//! assert_eq!(&buf, b"ping");
+//!
+//! server.shutdown();
+//! ```
+
+use std::io::{self, Read, Write};
+
+pub use crate::server::Connection;
+use crate::server::ServerHandle;
+use crate::{ToUniAddrs, UniAddr};
+
+/// Spawns a server bound to `addr` which echoes back everything it receives.
+///
+/// See [`spawn_server`] for more details.
+///
+/// # Errors
+///
+/// See [`spawn_server`].
+pub fn spawn_echo_server<A: ToUniAddrs>(addr: A) -> io::Result<TestServer> {
+    spawn_server(addr, |mut conn| {
+        let mut buf = [0; 8192];
+
+        loop {
+            match conn.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if conn.write_all(&buf[..n]).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Spawns a server bound to `addr`, calling `handler` on a dedicated thread
+/// for each accepted connection.
+///
+/// Any [`UniAddr`] variant is accepted, and each address of `addr` is tried in
+/// turn: host names are resolved with
+/// [`TcpListener::bind`](std::net::TcpListener::bind), and port `0` or an
+/// unnamed Unix socket address lets the OS pick the address. Use
+/// [`TestServer::local_addr`] to get the address actually bound.
+///
+/// # Errors
+///
+/// Failed to bind the address, or failed to spawn the accepting thread.
+pub fn spawn_server<A, F>(addr: A, handler: F) -> io::Result<TestServer>
+where
+    A: ToUniAddrs,
+    F: Fn(Box<dyn Connection>) + Send + Sync + 'static,
+{
+    crate::server::spawn(
+        addr,
+        "uni-addr-test-server",
+        crate::server::Limits::default(),
+        handler,
+    )
+    .map(TestServer)
+}
+
+#[derive(Debug)]
+/// A running server spawned with [`spawn_server`] or [`spawn_echo_server`].
+///
+/// The server stops accepting new connections when this is dropped or
+/// [`shutdown`](Self::shutdown) is called. Connections already accepted are
+/// left to their handlers.
+pub struct TestServer(ServerHandle);
+
+impl TestServer {
+    #[inline]
+    /// Returns the address the server is actually bound to.
+    pub const fn local_addr(&self) -> &UniAddr {
+        self.0.local_addr()
+    }
+
+    #[inline]
+    /// Stops the server, waiting for the accepting thread to exit.
+    pub fn shutdown(self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("127.0.0.1:0")]
+    #[case("[::1]:0")]
+    #[case("localhost:0")]
+    #[cfg_attr(unix, case("unix:///tmp/test_spawn_echo_server.socket"))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case("unix://")
+    )]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux", target_os = "cygwin"),
+        case("unix://@test_spawn_echo_server.socket")
+    )]
+    fn test_spawn_echo_server(#[case] addr: &str) {
+        let server = spawn_echo_server(addr).unwrap();
+
+        let mut conn = crate::server::connect(server.local_addr()).unwrap();
+
+        conn.write_all(b"hello, world").unwrap();
+
+        let mut buf = [0; 12];
+        conn.read_exact(&mut buf).unwrap();
+
+        assert_eq!(&buf, b"hello, world");
+
+        server.shutdown();
+    }
+}
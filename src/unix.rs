@@ -1,10 +1,20 @@
 //! Platform-specific code for Unix-like systems
 
-use std::ffi::{CStr, OsStr, OsString};
+use std::ffi::{c_int, CStr, OsStr, OsString};
 use std::hash::{Hash, Hasher};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd};
 use std::os::unix::ffi::OsStrExt;
-use std::path::Path;
-use std::{fmt, fs, io};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io, mem, ptr};
+
+#[cfg(feature = "feat-fd-passing")]
+pub mod fd_passing;
+pub mod inetd;
+#[cfg(all(
+    feature = "feat-socket2",
+    any(target_os = "android", target_os = "linux")
+))]
+pub mod seqpacket;
 
 wrapper_lite::general_wrapper! {
     #[wrapper_impl(Deref)]
@@ -89,6 +99,48 @@ impl SocketAddr {
         }
     }
 
+    /// Likes [`SocketAddr::new`], but never removes the file of a pathname
+    /// address, for addresses which are parsed rather than bound.
+    pub(crate) fn new_keep_file(addr: &OsStr) -> io::Result<Self> {
+        match addr.as_bytes() {
+            #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+            [b'@' | b'\0', rest @ ..] => Self::new_abstract(rest),
+            #[cfg(not(any(target_os = "android", target_os = "linux", target_os = "cygwin")))]
+            [b'@' | b'\0', ..] => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "abstract unix socket address is not supported",
+            )),
+            _ => std::os::unix::net::SocketAddr::from_pathname(addr).map(Self::from_inner),
+        }
+    }
+
+    /// Likes [`SocketAddr::new`], but on platforms without the abstract
+    /// namespace, e.g., macOS, abstract addresses are emulated with a pathname
+    /// in a per-user runtime directory, see [`abstract_fallback_path`].
+    ///
+    /// This lets cross-platform applications keep one address string like
+    /// `@app.socket` in their configuration.
+    ///
+    /// # Errors
+    ///
+    /// See [`SocketAddr::new`] and [`abstract_fallback_path`].
+    pub fn new_portable<S: AsRef<OsStr> + ?Sized>(addr: &S) -> io::Result<Self> {
+        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+        {
+            Self::new(addr)
+        }
+
+        #[cfg(not(any(target_os = "android", target_os = "linux", target_os = "cygwin")))]
+        {
+            let addr = addr.as_ref();
+
+            match addr.as_bytes() {
+                [b'@' | b'\0', rest @ ..] => Self::new_pathname(abstract_fallback_path(rest)?),
+                _ => Self::new_pathname(addr),
+            }
+        }
+    }
+
     #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
     /// Creates a Unix socket address in the abstract namespace.
     ///
@@ -204,6 +256,72 @@ impl SocketAddr {
         }
     }
 
+    #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+    /// Returns the "other" form of this address, for daemons migrating their
+    /// IPC endpoint between the filesystem and the abstract namespace.
+    ///
+    /// The mapping is deterministic and reversible: the pathname
+    /// `/run/foo.socket` maps to the abstract name `@/run/foo.socket`, and vice
+    /// versa.
+    ///
+    /// Unlike [`SocketAddr::new_pathname`], this never removes the mirrored
+    /// pathname, as it may still be served by an old daemon instance.
+    ///
+    /// # Errors
+    ///
+    /// - The address is unnamed, which has no mirror.
+    /// - The abstract name is not a valid pathname, e.g., contains NULL bytes.
+    pub fn mirror(&self) -> io::Result<Self> {
+        #[cfg(target_os = "android")]
+        use std::os::android::net::SocketAddrExt;
+        #[cfg(target_os = "cygwin")]
+        use std::os::cygwin::net::SocketAddrExt;
+        #[cfg(target_os = "linux")]
+        use std::os::linux::net::SocketAddrExt;
+
+        if let Some(pathname) = self.as_pathname() {
+            return Self::new_abstract(pathname.as_os_str().as_bytes());
+        }
+
+        if let Some(abstract_name) = self.as_abstract_name() {
+            return std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(abstract_name))
+                .map(Self::from_inner);
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "unnamed unix socket address has no mirror",
+        ))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+    /// Binds listeners on both this address and its [`mirror`](Self::mirror),
+    /// returning `(listener, mirror_listener)`.
+    ///
+    /// During a migration window, the daemon serves both old and new clients by
+    /// accepting on both listeners.
+    ///
+    /// # Errors
+    ///
+    /// See [`SocketAddr::mirror`] and [`UnixListener::bind_addr`]. Notes that
+    /// binding the mirrored pathname fails with
+    /// [`AddrInUse`](io::ErrorKind::AddrInUse) if the file already exists.
+    ///
+    /// [`UnixListener::bind_addr`]: std::os::unix::net::UnixListener::bind_addr
+    pub fn bind_with_mirror(
+        &self,
+    ) -> io::Result<(
+        std::os::unix::net::UnixListener,
+        std::os::unix::net::UnixListener,
+    )> {
+        let mirror = self.mirror()?;
+
+        let listener = std::os::unix::net::UnixListener::bind_addr(self)?;
+        let mirror_listener = std::os::unix::net::UnixListener::bind_addr(&mirror)?;
+
+        Ok((listener, mirror_listener))
+    }
+
     /// Serializes the [`SocketAddr`] to an `OsString`.
     ///
     /// # Returns
@@ -263,6 +381,35 @@ impl SocketAddr {
         // An unnamed one...
         os_string
     }
+
+    /// Writes what [`to_string_lossy`](Self::to_string_lossy) returns, without
+    /// allocating.
+    pub(crate) fn fmt_lossy(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(pathname) = self.as_pathname() {
+            return write!(f, "{}", pathname.display());
+        }
+
+        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+        {
+            #[cfg(target_os = "android")]
+            use std::os::android::net::SocketAddrExt;
+            #[cfg(target_os = "cygwin")]
+            use std::os::cygwin::net::SocketAddrExt;
+            #[cfg(target_os = "linux")]
+            use std::os::linux::net::SocketAddrExt;
+
+            if let Some(abstract_name) = self.as_abstract_name() {
+                return write!(
+                    f,
+                    "@{}",
+                    Path::new(OsStr::from_bytes(abstract_name)).display()
+                );
+            }
+        }
+
+        // An unnamed one...
+        Ok(())
+    }
 }
 
 impl fmt::Debug for SocketAddr {
@@ -334,6 +481,31 @@ impl Hash for SocketAddr {
     }
 }
 
+#[cfg(feature = "feat-socket2")]
+impl TryFrom<&socket2::SockAddr> for SocketAddr {
+    type Error = io::Error;
+
+    fn try_from(addr: &socket2::SockAddr) -> Result<Self, Self::Error> {
+        if let Some(addr) = addr.as_unix() {
+            return Ok(Self::from_inner(addr));
+        }
+
+        if addr.is_unnamed() {
+            return Ok(Self::new_unnamed());
+        }
+
+        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+        if let Some(addr) = addr.as_abstract_namespace() {
+            return Self::new_abstract(addr);
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "unsupported address type",
+        ))
+    }
+}
+
 #[cfg(feature = "feat-serde")]
 impl serde::Serialize for SocketAddr {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -354,8 +526,637 @@ impl<'de> serde::Deserialize<'de> for SocketAddr {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Credentials of the peer process of a Unix domain socket connection, see
+/// [`peer_cred`].
+pub struct UCred {
+    /// The effective user ID.
+    pub uid: u32,
+
+    /// The effective group ID.
+    pub gid: u32,
+
+    /// The process ID, where the platform reports it.
+    pub pid: Option<u32>,
+}
+
+#[allow(unsafe_code)]
+/// Returns the credentials of the peer process of a connected Unix domain
+/// socket, e.g., an accepted [`UnixStream`](std::os::unix::net::UnixStream),
+/// for identity-based authorization.
+///
+/// The credentials are the ones at the time the peer called `connect` or
+/// `socketpair`. This uses `SO_PEERCRED` on Linux, and `getpeereid` on BSDs and
+/// macOS, plus `LOCAL_PEERPID` for the process ID on the latter.
+///
+/// # Errors
+///
+/// [`io::ErrorKind::Unsupported`] on other platforms, otherwise see
+/// `getsockopt(2)` and `getpeereid(3)`.
+pub fn peer_cred<S: AsFd>(socket: &S) -> io::Result<UCred> {
+    let fd = socket.as_fd().as_raw_fd();
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        // SAFETY: an all-zero `ucred` is valid.
+        let mut ucred: libc::ucred = unsafe { mem::zeroed() };
+        let mut len = socklen_of::<libc::ucred>();
+
+        // SAFETY: `fd` is valid, and `ucred` / `len` describe a valid buffer.
+        let ret = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                ptr::addr_of_mut!(ucred).cast(),
+                &mut len,
+            )
+        };
+
+        if ret == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(UCred {
+            uid: ucred.uid,
+            gid: ucred.gid,
+            pid: u32::try_from(ucred.pid).ok().filter(|pid| *pid != 0),
+        })
+    }
+
+    #[cfg(any(
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        let mut uid: libc::uid_t = 0;
+        let mut gid: libc::gid_t = 0;
+
+        // SAFETY: `fd` is valid, and `uid` / `gid` are valid pointers.
+        if unsafe { libc::getpeereid(fd, &mut uid, &mut gid) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        #[cfg(any(target_os = "ios", target_os = "macos"))]
+        let pid = {
+            let mut pid: libc::pid_t = 0;
+            let mut len = socklen_of::<libc::pid_t>();
+
+            // SAFETY: `fd` is valid, and `pid` / `len` describe a valid buffer.
+            let ret = unsafe {
+                libc::getsockopt(
+                    fd,
+                    libc::SOL_LOCAL,
+                    libc::LOCAL_PEERPID,
+                    ptr::addr_of_mut!(pid).cast(),
+                    &mut len,
+                )
+            };
+
+            (ret == 0)
+                .then(|| u32::try_from(pid).ok())
+                .flatten()
+                .filter(|pid| *pid != 0)
+        };
+
+        #[cfg(not(any(target_os = "ios", target_os = "macos")))]
+        let pid = None;
+
+        Ok(UCred { uid, gid, pid })
+    }
+
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    {
+        let _ = fd;
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "peer credentials are not supported on this platform",
+        ))
+    }
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[allow(unsafe_code)]
+/// Returns the security context (e.g., an `SELinux` label) of the peer process
+/// of a connected Unix domain socket, as raw bytes without the trailing nul,
+/// for MAC-aware authorization.
+///
+/// This reads `SO_PEERSEC`.
+///
+/// # Errors
+///
+/// [`io::ErrorKind::Unsupported`] if no LSM providing security contexts is
+/// enabled, otherwise see `getsockopt(2)`.
+pub fn peer_security_context<S: AsFd>(socket: &S) -> io::Result<Vec<u8>> {
+    let fd = socket.as_fd().as_raw_fd();
+    let mut buf = vec![0u8; 256];
+
+    loop {
+        // Grown to a length the kernel returned only.
+        let mut len = libc::socklen_t::try_from(buf.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+
+        // SAFETY: `fd` is valid, and `buf` / `len` describe a valid buffer.
+        let ret = unsafe {
+            libc::getsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_PEERSEC,
+                buf.as_mut_ptr().cast(),
+                &mut len,
+            )
+        };
+
+        if ret == 0 {
+            buf.truncate(len as usize);
+
+            if buf.last() == Some(&0) {
+                buf.pop();
+            }
+
+            return Ok(buf);
+        }
+
+        let err = io::Error::last_os_error();
+
+        match err.raw_os_error() {
+            // `len` is set to the required length.
+            Some(libc::ERANGE) if len as usize > buf.len() => buf.resize(len as usize, 0),
+            Some(libc::ENOPROTOOPT) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "no LSM providing peer security contexts is enabled",
+                ))
+            }
+            _ => return Err(err),
+        }
+    }
+}
+
+#[allow(unsafe_code)]
+/// Binds the socket to the named network interface, so that only packets
+/// received on, or routed through, that interface are handled, e.g., for
+/// per-NIC listeners on multi-homed servers.
+///
+/// This uses `SO_BINDTODEVICE` on Linux, and `IP_BOUND_IF` / `IPV6_BOUND_IF`
+/// on macOS, depending on the address family of the socket.
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::InvalidInput`] if the interface name is invalid.
+/// - [`io::ErrorKind::PermissionDenied`] on Linux before 5.7 without the
+///   `CAP_NET_RAW` capability.
+/// - [`io::ErrorKind::Unsupported`] on other platforms, or for sockets other
+///   than IPv4 / IPv6 ones on macOS.
+/// - See `setsockopt(2)`.
+pub fn bind_to_device<S: AsFd>(socket: &S, interface: &str) -> io::Result<()> {
+    if interface.is_empty() || interface.len() >= 16 || interface.contains('\0') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid network interface name: {interface:?}"),
+        ));
+    }
+
+    let fd = socket.as_fd().as_raw_fd();
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    {
+        // Shorter than `IFNAMSIZ`, checked above.
+        let len = libc::socklen_t::try_from(interface.len())
+            .map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+
+        // SAFETY: `fd` is valid, and `interface` is a valid buffer.
+        let ret = unsafe {
+            libc::setsockopt(
+                fd,
+                libc::SOL_SOCKET,
+                libc::SO_BINDTODEVICE,
+                interface.as_ptr().cast(),
+                len,
+            )
+        };
+
+        if ret == -1 {
+            let err = io::Error::last_os_error();
+
+            if err.kind() == io::ErrorKind::PermissionDenied {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!(
+                        "binding to network interface {interface:?} requires the CAP_NET_RAW \
+                         capability before Linux 5.7: {err}"
+                    ),
+                ));
+            }
+
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "ios", target_os = "macos"))]
+    {
+        let index = interface_index(interface)?;
+
+        // SAFETY: an all-zero `sockaddr_storage` is valid.
+        let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+        let mut len = socklen_of::<libc::sockaddr_storage>();
+
+        // SAFETY: `fd` is valid, and `storage` / `len` describe a valid buffer.
+        if unsafe { libc::getsockname(fd, ptr::addr_of_mut!(storage).cast(), &mut len) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let (level, name) = match c_int::from(storage.ss_family) {
+            libc::AF_INET => (libc::IPPROTO_IP, libc::IP_BOUND_IF),
+            libc::AF_INET6 => (libc::IPPROTO_IPV6, libc::IPV6_BOUND_IF),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "binding to a network interface requires an IPv4 or IPv6 socket",
+                ))
+            }
+        };
+
+        // SAFETY: `fd` is valid, and `index` is a valid buffer.
+        let ret = unsafe {
+            libc::setsockopt(
+                fd,
+                level,
+                name,
+                ptr::addr_of!(index).cast(),
+                socklen_of::<libc::c_uint>(),
+            )
+        };
+
+        if ret == -1 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(any(
+        target_os = "android",
+        target_os = "linux",
+        target_os = "ios",
+        target_os = "macos"
+    )))]
+    {
+        let _ = fd;
+
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "binding to a network interface is not supported on this platform",
+        ))
+    }
+}
+
+/// Returns the pathname emulating the abstract name on platforms without the
+/// abstract namespace, see [`SocketAddr::new_portable`].
+///
+/// The pathname is deterministic: the name, with bytes other than ASCII
+/// alphanumerics, `-`, `.` and `_` percent-encoded, in the first of
+/// `$XDG_RUNTIME_DIR`, `$TMPDIR` (per-user on macOS) and `/tmp` set to an
+/// absolute path. For example, `@app.socket` maps to
+/// `$XDG_RUNTIME_DIR/app.socket`.
+///
+/// # Errors
+///
+/// [`io::ErrorKind::InvalidInput`] if the name is empty.
+pub fn abstract_fallback_path(name: &[u8]) -> io::Result<PathBuf> {
+    use std::fmt::Write;
+
+    if name.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "empty abstract socket name",
+        ));
+    }
+
+    let dir = ["XDG_RUNTIME_DIR", "TMPDIR"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .find(|dir| dir.is_absolute())
+        .unwrap_or_else(|| PathBuf::from("/tmp"));
+
+    let mut file_name = String::with_capacity(name.len());
+
+    for byte in name {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_') {
+            file_name.push(char::from(*byte));
+        } else {
+            let _ = write!(file_name, "%{byte:02X}");
+        }
+    }
+
+    // Never `.` or `..`.
+    if file_name.bytes().all(|byte| byte == b'.') {
+        file_name = file_name.replace('.', "%2E");
+    }
+
+    Ok(dir.join(file_name))
+}
+
+#[allow(unsafe_code)]
+/// Returns the value of the integer socket option `name` at `SOL_SOCKET`
+/// level, like `SO_TYPE`.
+pub(crate) fn getsockopt_int(fd: BorrowedFd<'_>, name: c_int) -> io::Result<c_int> {
+    let mut value: c_int = 0;
+    let mut len = socklen_of::<c_int>();
+
+    // SAFETY: `fd` is valid, and `value` / `len` describe a valid buffer.
+    let ret = unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            name,
+            ptr::addr_of_mut!(value).cast(),
+            &mut len,
+        )
+    };
+
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(value)
+}
+
+/// Checks if the current process may bind `port`, see
+/// [`UniAddr::check_privileged_port`](crate::UniAddr::check_privileged_port).
+pub(crate) fn check_privileged_port(port: u16) -> io::Result<()> {
+    let unprivileged_port_start = unprivileged_port_start();
+
+    if port == 0 || port >= unprivileged_port_start || can_bind_privileged_ports() {
+        return Ok(());
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::PermissionDenied,
+        format!(
+            "binding to port {port} requires root or the CAP_NET_BIND_SERVICE capability; \
+             run as root, grant it with `setcap cap_net_bind_service=+ep <executable>`, \
+             or use a port >= {unprivileged_port_start}"
+        ),
+    ))
+}
+
+/// Returns the first port unprivileged processes may bind.
+fn unprivileged_port_start() -> u16 {
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    if let Ok(start) = fs::read_to_string("/proc/sys/net/ipv4/ip_unprivileged_port_start") {
+        if let Ok(start) = start.trim().parse() {
+            return start;
+        }
+    }
+
+    1024
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+/// Checks if `CAP_NET_BIND_SERVICE` is in the effective capability set.
+fn can_bind_privileged_ports() -> bool {
+    const CAP_NET_BIND_SERVICE: u32 = 10;
+
+    fs::read_to_string("/proc/self/status").is_ok_and(|status| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("CapEff:"))
+            .and_then(|cap_eff| u64::from_str_radix(cap_eff.trim(), 16).ok())
+            .is_some_and(|cap_eff| cap_eff & (1 << CAP_NET_BIND_SERVICE) != 0)
+    })
+}
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+#[allow(unsafe_code)]
+/// Checks if the effective user is root.
+fn can_bind_privileged_ports() -> bool {
+    // SAFETY: `geteuid` is always successful.
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[allow(unsafe_code)]
+/// Returns the index of the network interface named `name`, see
+/// `if_nametoindex(3)`.
+pub(crate) fn interface_index(name: &str) -> io::Result<u32> {
+    let name = std::ffi::CString::new(name)?;
+
+    // SAFETY: `name` is a valid, nul-terminated string.
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+
+    if index == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(index)
+}
+
+#[allow(unsafe_code)]
+/// Returns the current IP addresses of the network interface named `name`
+/// with the port `port`, see `getifaddrs(3)`.
+///
+/// IPv6 link-local addresses are scoped to the interface.
+pub(crate) fn interface_addrs(name: &str, port: u16) -> io::Result<Vec<std::net::SocketAddr>> {
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+    let index = interface_index(name)?;
+
+    let mut ifaddrs = std::ptr::null_mut();
+
+    // SAFETY: `ifaddrs` is a valid pointer to be filled in.
+    if unsafe { libc::getifaddrs(&mut ifaddrs) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut addrs = Vec::new();
+    let mut cursor = ifaddrs;
+
+    while !cursor.is_null() {
+        // SAFETY: `cursor` is a valid entry of the list returned by
+        // `getifaddrs`, which is freed only after the loop.
+        let ifaddr = unsafe { &*cursor };
+
+        cursor = ifaddr.ifa_next;
+
+        // SAFETY: `ifa_name` is a valid, nul-terminated string.
+        if ifaddr.ifa_addr.is_null()
+            || unsafe { CStr::from_ptr(ifaddr.ifa_name) }.to_bytes() != name.as_bytes()
+        {
+            continue;
+        }
+
+        // SAFETY: `ifa_addr` is non-null, and points to a socket address of
+        // the type indicated by its family.
+        match c_int::from(unsafe { (*ifaddr.ifa_addr).sa_family }) {
+            libc::AF_INET => {
+                // SAFETY: see above. It may be less aligned than `sockaddr_in`.
+                let addr =
+                    unsafe { ptr::read_unaligned(ifaddr.ifa_addr.cast::<libc::sockaddr_in>()) };
+                let ip = Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr));
+
+                addrs.push(SocketAddr::V4(SocketAddrV4::new(ip, port)));
+            }
+            libc::AF_INET6 => {
+                // SAFETY: see above.
+                let addr =
+                    unsafe { ptr::read_unaligned(ifaddr.ifa_addr.cast::<libc::sockaddr_in6>()) };
+                let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+
+                // Only link-local addresses need the zone.
+                let scope_id = if ip.segments()[0] & 0xffc0 == 0xfe80 {
+                    index
+                } else {
+                    0
+                };
+
+                addrs.push(SocketAddr::V6(SocketAddrV6::new(ip, port, 0, scope_id)));
+            }
+            _ => {}
+        }
+    }
+
+    // SAFETY: `ifaddrs` is the list returned by `getifaddrs`.
+    unsafe { libc::freeifaddrs(ifaddrs) };
+
+    Ok(addrs)
+}
+
+/// Returns the size of `T` as a `socklen_t`, for socket addresses and option
+/// values.
+pub(crate) const fn socklen_of<T>() -> libc::socklen_t {
+    // Far smaller than `socklen_t::MAX`, and `TryFrom` is not const.
+    #[allow(clippy::cast_possible_truncation)]
+    let len = mem::size_of::<T>() as libc::socklen_t;
+
+    len
+}
+
+#[allow(unsafe_code)]
+/// Sets the integer socket option `name` at `SOL_SOCKET` level, like
+/// `SO_PASSCRED`.
+pub(crate) fn setsockopt_int(fd: BorrowedFd<'_>, name: c_int, value: c_int) -> io::Result<()> {
+    // SAFETY: `fd` is valid, and `value` is a valid buffer.
+    let ret = unsafe {
+        libc::setsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            name,
+            ptr::addr_of!(value).cast(),
+            socklen_of::<c_int>(),
+        )
+    };
+
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+#[allow(unsafe_code)]
+/// Receives a single datagram on a Unix datagram socket, along with the
+/// credentials of the sender passed as `SCM_CREDENTIALS`, if any.
+pub(crate) fn recv_from_with_creds(
+    fd: BorrowedFd<'_>,
+    buf: &mut [u8],
+) -> io::Result<(usize, SocketAddr, Option<UCred>)> {
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr().cast(),
+        iov_len: buf.len(),
+    };
+
+    // Large enough and suitably aligned for a control message carrying a
+    // `ucred`.
+    let mut control = [0u64; 8];
+
+    // SAFETY: an all-zero `sockaddr_un` is valid.
+    let mut name: libc::sockaddr_un = unsafe { mem::zeroed() };
+
+    // SAFETY: an all-zero `msghdr` is valid, and the buffers outlive the call.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_name = ptr::addr_of_mut!(name).cast();
+    msg.msg_namelen = socklen_of::<libc::sockaddr_un>();
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = mem::size_of_val(&control) as _;
+
+    // SAFETY: see above.
+    let len = unsafe { libc::recvmsg(fd.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+
+    // Negative on error only.
+    let Ok(len) = usize::try_from(len) else {
+        return Err(io::Error::last_os_error());
+    };
+
+    let mut ucred = None;
+
+    // SAFETY: the kernel filled in the control messages.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET
+                && (*cmsg).cmsg_type == libc::SCM_CREDENTIALS
+                && (*cmsg).cmsg_len as usize >= libc::CMSG_LEN(socklen_of::<libc::ucred>()) as usize
+            {
+                let raw = std::ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<libc::ucred>());
+
+                ucred = Some(UCred {
+                    uid: raw.uid,
+                    gid: raw.gid,
+                    pid: u32::try_from(raw.pid).ok().filter(|pid| *pid != 0),
+                });
+            }
+
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    let path_len = (msg.msg_namelen as usize)
+        .saturating_sub(mem::size_of::<libc::sa_family_t>())
+        .min(name.sun_path.len());
+
+    // SAFETY: `c_char` and `u8` have the same layout, and `path_len` is in
+    // bounds.
+    let path: &[u8] =
+        unsafe { std::slice::from_raw_parts(name.sun_path.as_ptr().cast::<u8>(), path_len) };
+
+    let addr = match path {
+        [0, name @ ..] => SocketAddr::new_abstract(name)?,
+        // Without the trailing nul, if any. Not `new_pathname`, which removes
+        // the socket file of the sender.
+        _ => std::os::unix::net::SocketAddr::from_pathname(OsStr::from_bytes(
+            path.split(|byte| *byte == 0).next().unwrap_or_default(),
+        ))
+        .map(SocketAddr::from_inner)?,
+    };
+
+    Ok((len, addr, ucred))
+}
+
 #[cfg(test)]
 mod tests {
+    use rstest::rstest;
+
     use super::*;
 
     #[test]
@@ -426,6 +1227,52 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+    fn test_mirror() {
+        #[cfg(target_os = "android")]
+        use std::os::android::net::SocketAddrExt;
+        #[cfg(target_os = "cygwin")]
+        use std::os::cygwin::net::SocketAddrExt;
+        #[cfg(target_os = "linux")]
+        use std::os::linux::net::SocketAddrExt;
+
+        let addr_pathname = SocketAddr::new("/tmp/test_mirror.socket").unwrap();
+        let addr_abstract = addr_pathname.mirror().unwrap();
+
+        assert_eq!(
+            addr_abstract.as_abstract_name().unwrap(),
+            b"/tmp/test_mirror.socket"
+        );
+        assert_eq!(addr_abstract.mirror().unwrap(), addr_pathname);
+
+        let _ = SocketAddr::new_unnamed().mirror().unwrap_err();
+        let _ = SocketAddr::new_abstract(b"nul\0")
+            .unwrap()
+            .mirror()
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+    fn test_bind_with_mirror() {
+        use std::os::unix::net::UnixStream;
+
+        let addr = SocketAddr::new("@/tmp/test_bind_with_mirror.socket").unwrap();
+
+        let _ = fs::remove_file("/tmp/test_bind_with_mirror.socket");
+
+        let (listener, mirror_listener) = addr.bind_with_mirror().unwrap();
+
+        let _stream = UnixStream::connect_addr(&addr).unwrap();
+        let _ = listener.accept().unwrap();
+
+        let _stream = UnixStream::connect("/tmp/test_bind_with_mirror.socket").unwrap();
+        let _ = mirror_listener.accept().unwrap();
+
+        let _ = fs::remove_file("/tmp/test_bind_with_mirror.socket");
+    }
+
     #[test]
     #[should_panic]
     fn test_pathname_with_null_byte() {
@@ -450,7 +1297,7 @@ mod tests {
 
         #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
         {
-            use core::hash::{BuildHasher, Hash, Hasher};
+            use core::hash::BuildHasher;
 
             use foldhash::fast::RandomState;
 
@@ -472,17 +1319,106 @@ mod tests {
             // Abstract unnamed address `@(unamed)\0`' hash should not be equal to unname
             // ones'
             let state = RandomState::default();
-            let addr_unnamed_hash = {
-                let mut state = state.build_hasher();
-                addr_unnamed.hash(&mut state);
-                state.finish()
-            };
-            let addr_abstract_unnamed_hash = {
-                let mut state = state.build_hasher();
-                addr_abstract_unnamed.hash(&mut state);
-                state.finish()
-            };
+            let addr_unnamed_hash = state.hash_one(&addr_unnamed);
+            let addr_abstract_unnamed_hash = state.hash_one(&addr_abstract_unnamed);
             assert_ne!(addr_unnamed_hash, addr_abstract_unnamed_hash);
         }
     }
+
+    #[test]
+    fn test_check_privileged_port() {
+        check_privileged_port(0).unwrap();
+        check_privileged_port(u16::MAX).unwrap();
+
+        assert_eq!(
+            check_privileged_port(80).is_ok(),
+            80 >= unprivileged_port_start() || can_bind_privileged_ports()
+        );
+
+        crate::UniAddr::new("unix:///tmp/test_check_privileged_port.socket")
+            .unwrap()
+            .check_privileged_port()
+            .unwrap();
+        crate::UniAddr::new("localhost:8080")
+            .unwrap()
+            .check_privileged_port()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_peer_cred() {
+        let (a, _b) = std::os::unix::net::UnixStream::pair().unwrap();
+
+        let ucred = peer_cred(&a).unwrap();
+
+        assert_eq!(
+            ucred.pid,
+            Some(std::process::id()).filter(|_| cfg!(any(
+                target_os = "android",
+                target_os = "linux",
+                target_os = "ios",
+                target_os = "macos"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_bind_to_device() {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+        assert_eq!(
+            bind_to_device(&socket, "").unwrap_err().kind(),
+            io::ErrorKind::InvalidInput
+        );
+        assert_eq!(
+            bind_to_device(&socket, "an-interface-name-too-long")
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+
+        #[cfg(any(target_os = "android", target_os = "linux"))]
+        match bind_to_device(&socket, "lo") {
+            Ok(()) => {}
+            Err(err) => assert_eq!(err.kind(), io::ErrorKind::PermissionDenied),
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    #[test]
+    fn test_peer_security_context() {
+        let (a, _b) = std::os::unix::net::UnixStream::pair().unwrap();
+
+        match peer_security_context(&a) {
+            Ok(context) => assert!(!context.ends_with(&[0])),
+            Err(err) => assert_eq!(err.kind(), io::ErrorKind::Unsupported),
+        }
+    }
+
+    #[rstest]
+    #[case(b"app.socket", "app.socket")]
+    #[case(b"a/b\0c d", "a%2Fb%00c%20d")]
+    #[case(b"..", "%2E%2E")]
+    fn test_abstract_fallback_path(#[case] name: &[u8], #[case] expected: &str) {
+        let path = abstract_fallback_path(name).unwrap();
+
+        assert!(path.is_absolute());
+        assert_eq!(path.file_name().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_new_portable() {
+        let _ = abstract_fallback_path(b"").unwrap_err();
+
+        let addr = SocketAddr::new_portable("@test_new_portable.socket").unwrap();
+
+        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+        assert_eq!(addr, SocketAddr::new("@test_new_portable.socket").unwrap());
+
+        #[cfg(not(any(target_os = "android", target_os = "linux", target_os = "cygwin")))]
+        assert_eq!(
+            addr.as_pathname().unwrap(),
+            abstract_fallback_path(b"test_new_portable.socket").unwrap()
+        );
+    }
 }
@@ -0,0 +1,245 @@
+//! Passing sockets to another process over a Unix domain socket connection,
+//! with `SCM_RIGHTS`.
+//!
+//! Zero-downtime restarts hand the listening sockets of the old process over
+//! to the new one. The receiving side gets an [`OwnedFd`], which converts back
+//! into the right variant of [`StdDatagram`], [`InheritedStream`] or
+//! `ActivatedListener` with [`TryFrom`].
+//!
+//! [`StdDatagram`]: crate::datagram::StdDatagram
+//! [`InheritedStream`]: crate::unix::inetd::InheritedStream
+//!
+//! # Examples
+//!
+//! ```rust
+//! use std::net::UdpSocket;
+//! use std::os::unix::net::UnixStream;
+//!
+//! use uni_addr::datagram::StdDatagram;
+//! use uni_addr::unix::fd_passing::{recv_fd, send_fd};
+//!
+//! let (old, new) = UnixStream::pair().unwrap();
+//!
+//! let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+//! send_fd(&old, &socket).unwrap();
+//!
+//! let received = StdDatagram::try_from(recv_fd(&new).unwrap()).unwrap();
+//! assert!(matches!(received, StdDatagram::Udp(_)));
+//! ```
+
+#![allow(unsafe_code)]
+
+use std::ffi::c_int;
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+use std::{io, mem, ptr};
+
+use super::socklen_of;
+
+/// Large enough and suitably aligned for a control message carrying one file
+/// descriptor.
+type ControlBuffer = [u64; 4];
+
+/// Sends the file descriptor over the connection, along with a single byte of
+/// payload.
+///
+/// The file descriptor stays open in this process; close it once the peer has
+/// received it, as it is shared from now on.
+///
+/// # Errors
+///
+/// See `sendmsg(2)`.
+pub fn send_fd<F: AsFd>(conn: &UnixStream, fd: F) -> io::Result<()> {
+    let payload = [0u8];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_ptr() as *mut _,
+        iov_len: payload.len(),
+    };
+
+    let mut control: ControlBuffer = [0; 4];
+
+    // SAFETY: an all-zero `msghdr` is valid, and the control buffer is large
+    // enough for one file descriptor, see `test_control_buffer`.
+    let ret = unsafe {
+        let mut msg: libc::msghdr = mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = control.as_mut_ptr().cast();
+        msg.msg_controllen = libc::CMSG_SPACE(socklen_of::<c_int>()) as _;
+
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(socklen_of::<c_int>()) as _;
+        ptr::write_unaligned(
+            libc::CMSG_DATA(cmsg).cast::<c_int>(),
+            fd.as_fd().as_raw_fd(),
+        );
+
+        libc::sendmsg(conn.as_raw_fd(), &msg, SEND_FLAGS)
+    };
+
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+/// Receives a file descriptor sent with [`send_fd`] over the connection.
+///
+/// The file descriptor is close-on-exec. Extra file descriptors in the same
+/// message are closed.
+///
+/// # Errors
+///
+/// - [`io::ErrorKind::UnexpectedEof`] if the peer closed the connection.
+/// - [`io::ErrorKind::InvalidData`] if the message carries no file descriptor.
+/// - See `recvmsg(2)`.
+pub fn recv_fd(conn: &UnixStream) -> io::Result<OwnedFd> {
+    let mut payload = [0u8];
+    let mut iov = libc::iovec {
+        iov_base: payload.as_mut_ptr().cast(),
+        iov_len: payload.len(),
+    };
+
+    let mut control: ControlBuffer = [0; 4];
+
+    // SAFETY: an all-zero `msghdr` is valid, and the buffers outlive the call.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = mem::size_of::<ControlBuffer>() as _;
+
+    // SAFETY: see above.
+    let ret = unsafe { libc::recvmsg(conn.as_raw_fd(), &mut msg, RECV_FLAGS) };
+
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut received = Vec::new();
+
+    // SAFETY: the kernel filled in the control messages, and each
+    // `SCM_RIGHTS` one carries file descriptors now owned by this process.
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg);
+                let len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+
+                // The data is not necessarily aligned for `c_int`.
+                for idx in 0..len / mem::size_of::<c_int>() {
+                    let fd = ptr::read_unaligned(
+                        data.add(idx * mem::size_of::<c_int>()).cast::<c_int>(),
+                    );
+
+                    received.push(OwnedFd::from_raw_fd(fd));
+                }
+            }
+
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    if ret == 0 && received.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the connection is closed",
+        ));
+    }
+
+    if msg.msg_flags & libc::MSG_CTRUNC != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the control message is truncated",
+        ));
+    }
+
+    let Some(fd) = received.into_iter().next() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "no file descriptor received",
+        ));
+    };
+
+    #[cfg(not(any(target_os = "android", target_os = "linux")))]
+    // SAFETY: `fd` is a valid, owned file descriptor.
+    if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(fd)
+}
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const SEND_FLAGS: c_int = libc::MSG_NOSIGNAL;
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+const SEND_FLAGS: c_int = 0;
+
+#[cfg(any(target_os = "android", target_os = "linux"))]
+const RECV_FLAGS: c_int = libc::MSG_CMSG_CLOEXEC;
+
+#[cfg(not(any(target_os = "android", target_os = "linux")))]
+const RECV_FLAGS: c_int = 0;
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    use super::*;
+    use crate::unix::inetd::InheritedStream;
+
+    #[test]
+    fn test_control_buffer() {
+        // SAFETY: `CMSG_SPACE` only does arithmetic.
+        let space = unsafe { libc::CMSG_SPACE(mem::size_of::<c_int>() as _) } as usize;
+
+        assert!(space <= mem::size_of::<ControlBuffer>());
+    }
+
+    #[test]
+    fn test_send_recv_fd() {
+        let (tx, rx) = UnixStream::pair().unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        send_fd(&tx, &server).unwrap();
+        drop(server);
+
+        let mut received = InheritedStream::try_from(recv_fd(&rx).unwrap()).unwrap();
+
+        assert!(matches!(received, InheritedStream::Tcp(_)));
+        assert_eq!(received.peer_addr().unwrap(), client.local_addr().unwrap());
+
+        received.write_all(b"ping").unwrap();
+
+        let mut buf = [0; 4];
+        client.read_exact(&mut buf).unwrap();
+
+        assert_eq!(&buf, b"ping");
+    }
+
+    #[test]
+    fn test_recv_fd_without_fd() {
+        let (mut tx, rx) = UnixStream::pair().unwrap();
+
+        tx.write_all(b"x").unwrap();
+
+        assert_eq!(recv_fd(&rx).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        drop(tx);
+
+        assert_eq!(
+            recv_fd(&rx).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
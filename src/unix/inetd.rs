@@ -0,0 +1,240 @@
+//! inetd-style services, where the accepted connection is passed as stdin.
+//!
+//! This covers classic inetd / xinetd deployments and systemd socket units
+//! with `Accept=yes`.
+//!
+//! # Examples
+//!
+//! ```rust,no_run
+//! use std::io::Write;
+//!
+//! let mut stream = uni_addr::unix::inetd::from_stdin().unwrap();
+//!
+//! let peer_addr = stream.peer_addr().unwrap();
+//! writeln!(stream, "hello, {peer_addr}").unwrap();
+//! ```
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, IntoRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixStream;
+
+use crate::UniAddr;
+
+#[derive(Debug)]
+/// A connected stream socket inherited from the super-server.
+pub enum InheritedStream {
+    /// A TCP connection.
+    Tcp(TcpStream),
+
+    /// A Unix domain socket connection.
+    Unix(UnixStream),
+}
+
+impl InheritedStream {
+    /// Returns the local address of the connection.
+    ///
+    /// # Errors
+    ///
+    /// See [`TcpStream::local_addr`] and [`UnixStream::local_addr`].
+    pub fn local_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Tcp(stream) => stream.local_addr().map(UniAddr::from),
+            Self::Unix(stream) => stream.local_addr().map(UniAddr::from),
+        }
+    }
+
+    /// Returns the address of the remote peer of the connection.
+    ///
+    /// # Errors
+    ///
+    /// See [`TcpStream::peer_addr`] and [`UnixStream::peer_addr`].
+    pub fn peer_addr(&self) -> io::Result<UniAddr> {
+        match self {
+            Self::Tcp(stream) => stream.peer_addr().map(UniAddr::from),
+            Self::Unix(stream) => stream.peer_addr().map(UniAddr::from),
+        }
+    }
+
+    /// Returns the credentials of the peer process of a Unix domain socket
+    /// connection, see [`peer_cred`](super::peer_cred).
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::Unsupported`] for TCP connections, otherwise see
+    /// [`peer_cred`](super::peer_cred).
+    pub fn peer_cred(&self) -> io::Result<super::UCred> {
+        match self {
+            Self::Tcp(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "peer credentials are only available for Unix domain sockets",
+            )),
+            Self::Unix(stream) => super::peer_cred(stream),
+        }
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux"))]
+    /// Returns the security context of the peer process of a Unix domain
+    /// socket connection, see
+    /// [`peer_security_context`](super::peer_security_context).
+    ///
+    /// # Errors
+    ///
+    /// [`io::ErrorKind::Unsupported`] for TCP connections, otherwise see
+    /// [`peer_security_context`](super::peer_security_context).
+    pub fn peer_security_context(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::Tcp(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "peer security contexts are only available for Unix domain sockets",
+            )),
+            Self::Unix(stream) => super::peer_security_context(stream),
+        }
+    }
+}
+
+impl AsFd for InheritedStream {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        match self {
+            Self::Tcp(stream) => stream.as_fd(),
+            Self::Unix(stream) => stream.as_fd(),
+        }
+    }
+}
+
+impl AsRawFd for InheritedStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.as_fd().as_raw_fd()
+    }
+}
+
+impl IntoRawFd for InheritedStream {
+    fn into_raw_fd(self) -> RawFd {
+        OwnedFd::from(self).into_raw_fd()
+    }
+}
+
+impl From<InheritedStream> for OwnedFd {
+    fn from(stream: InheritedStream) -> Self {
+        match stream {
+            InheritedStream::Tcp(stream) => stream.into(),
+            InheritedStream::Unix(stream) => stream.into(),
+        }
+    }
+}
+
+impl TryFrom<OwnedFd> for InheritedStream {
+    type Error = io::Error;
+
+    /// Wraps a connected stream socket file descriptor, detecting whether it
+    /// is a TCP or a Unix domain socket connection.
+    fn try_from(fd: OwnedFd) -> Result<Self, Self::Error> {
+        from_fd(fd)
+    }
+}
+
+impl Read for InheritedStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.read(buf),
+            Self::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for InheritedStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Tcp(stream) => stream.write(buf),
+            Self::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Tcp(stream) => stream.flush(),
+            Self::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Creates an [`InheritedStream`] from stdin (fd 0), detecting whether it is a
+/// TCP or a Unix domain socket connection.
+///
+/// The file descriptor is duplicated, so stdin itself is left untouched.
+///
+/// # Errors
+///
+/// Stdin is not a connected TCP or Unix domain socket.
+pub fn from_stdin() -> io::Result<InheritedStream> {
+    from_fd(io::stdin().as_fd().try_clone_to_owned()?)
+}
+
+fn from_fd(fd: OwnedFd) -> io::Result<InheritedStream> {
+    if super::getsockopt_int(fd.as_fd(), libc::SO_TYPE)? != libc::SOCK_STREAM {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the file descriptor is not a stream socket",
+        ));
+    }
+
+    let stream = TcpStream::from(fd);
+
+    // `getpeername` fails for a non-socket, or a socket of another address
+    // family.
+    if stream.peer_addr().is_ok() {
+        return Ok(InheritedStream::Tcp(stream));
+    }
+
+    let stream = UnixStream::from(OwnedFd::from(stream));
+
+    if stream.peer_addr().is_ok() {
+        return Ok(InheritedStream::Unix(stream));
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the file descriptor is not a connected TCP or Unix domain socket",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn test_from_fd_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (accepted, peer_addr) = listener.accept().unwrap();
+
+        let mut stream = from_fd(OwnedFd::from(accepted)).unwrap();
+
+        assert!(matches!(stream, InheritedStream::Tcp(_)));
+        assert_eq!(stream.peer_addr().unwrap(), UniAddr::from(peer_addr));
+
+        client.write_all(b"ping").unwrap();
+
+        let mut buf = [0; 4];
+        stream.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+
+    #[test]
+    fn test_from_fd_unix() {
+        let (accepted, _client) = UnixStream::pair().unwrap();
+
+        let stream = from_fd(OwnedFd::from(accepted)).unwrap();
+
+        assert!(matches!(stream, InheritedStream::Unix(_)));
+    }
+
+    #[test]
+    fn test_from_fd_not_a_socket() {
+        let file = std::fs::File::open("/dev/null").unwrap();
+
+        let _ = from_fd(OwnedFd::from(file)).unwrap_err();
+    }
+}
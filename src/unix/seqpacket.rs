@@ -0,0 +1,296 @@
+//! `SOCK_SEQPACKET` Unix domain sockets.
+//!
+//! Sequenced-packet sockets are connection-oriented like stream sockets, but
+//! preserve message boundaries like datagram sockets: each
+//! [`send`](SeqPacketStream::send) is received by exactly one
+//! [`recv`](SeqPacketStream::recv).
+//!
+//! # Examples
+//!
+//! ```rust
+//! use uni_addr::unix::seqpacket::{SeqPacketListener, SeqPacketStream};
+//! use uni_addr::unix::SocketAddr;
+//!
+//! let addr = SocketAddr::new("@seqpacket.example.socket").unwrap();
+//!
+//! let listener = SeqPacketListener::bind(&addr).unwrap();
+//! let client = SeqPacketStream::connect(&addr).unwrap();
+//! let (server, _) = listener.accept().unwrap();
+//!
+//! client.send(b"hello").unwrap();
+//! client.send(b"world").unwrap();
+//!
+//! let mut buf = [0; 16];
+//! assert_eq!(server.recv(&mut buf).unwrap(), 5);
+//! assert_eq!(&buf[..5], b"hello");
+//! ```
+
+use std::io::{Read, Write};
+use std::net::Shutdown;
+use std::time::Duration;
+use std::{fmt, io};
+
+use socket2::{Domain, SockAddr, Socket, Type};
+
+use super::SocketAddr;
+
+/// The default backlog of [`SeqPacketListener::bind`].
+const DEFAULT_BACKLOG: i32 = 128;
+
+fn new_socket() -> io::Result<Socket> {
+    Socket::new(Domain::UNIX, Type::from(libc::SOCK_SEQPACKET), None)
+}
+
+fn to_sock_addr(addr: &SocketAddr) -> io::Result<SockAddr> {
+    SockAddr::unix(addr.to_os_string())
+}
+
+/// A `SOCK_SEQPACKET` Unix domain socket server, listening for connections.
+pub struct SeqPacketListener {
+    inner: Socket,
+}
+
+impl fmt::Debug for SeqPacketListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqPacketListener")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl SeqPacketListener {
+    /// Creates a new [`SeqPacketListener`] bound to the given address.
+    ///
+    /// # Errors
+    ///
+    /// Failed to create the socket, or to bind and listen on the address.
+    pub fn bind(addr: &SocketAddr) -> io::Result<Self> {
+        let inner = new_socket()?;
+
+        inner.bind(&to_sock_addr(addr)?)?;
+        inner.listen(DEFAULT_BACKLOG)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Accepts a new incoming connection, returning the connected stream and
+    /// the address of the peer.
+    ///
+    /// # Errors
+    ///
+    /// See `accept(2)`.
+    pub fn accept(&self) -> io::Result<(SeqPacketStream, SocketAddr)> {
+        let (inner, addr) = self.inner.accept()?;
+
+        Ok((SeqPacketStream { inner }, SocketAddr::try_from(&addr)?))
+    }
+
+    /// Returns the local address of the listener.
+    ///
+    /// # Errors
+    ///
+    /// See `getsockname(2)`.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        SocketAddr::try_from(&self.inner.local_addr()?)
+    }
+
+    /// Moves the listener into or out of nonblocking mode.
+    ///
+    /// # Errors
+    ///
+    /// See `fcntl(2)`.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+
+    /// Returns the value of the `SO_ERROR` option, clearing it.
+    ///
+    /// # Errors
+    ///
+    /// See `getsockopt(2)`.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+}
+
+impl From<SeqPacketListener> for Socket {
+    fn from(listener: SeqPacketListener) -> Self {
+        listener.inner
+    }
+}
+
+/// A connected `SOCK_SEQPACKET` Unix domain socket.
+pub struct SeqPacketStream {
+    inner: Socket,
+}
+
+impl fmt::Debug for SeqPacketStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeqPacketStream")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl SeqPacketStream {
+    /// Connects to the socket listening on the given address.
+    ///
+    /// # Errors
+    ///
+    /// Failed to create the socket, or to connect to the address.
+    pub fn connect(addr: &SocketAddr) -> io::Result<Self> {
+        let inner = new_socket()?;
+
+        inner.connect(&to_sock_addr(addr)?)?;
+
+        Ok(Self { inner })
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    ///
+    /// # Errors
+    ///
+    /// See `socketpair(2)`.
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (a, b) = Socket::pair(Domain::UNIX, Type::from(libc::SOCK_SEQPACKET), None)?;
+
+        Ok((Self { inner: a }, Self { inner: b }))
+    }
+
+    /// Sends a single packet to the peer, returning the number of bytes
+    /// written.
+    ///
+    /// # Errors
+    ///
+    /// See `send(2)`.
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        (&self.inner).write(buf)
+    }
+
+    /// Receives a single packet from the peer, returning the number of bytes
+    /// read.
+    ///
+    /// If the packet is larger than `buf`, the excess bytes are discarded.
+    /// Returns `0` once the peer has shut down the connection.
+    ///
+    /// # Errors
+    ///
+    /// See `recv(2)`.
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.inner).read(buf)
+    }
+
+    /// Returns the local address of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See `getsockname(2)`.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        SocketAddr::try_from(&self.inner.local_addr()?)
+    }
+
+    /// Returns the address of the peer.
+    ///
+    /// # Errors
+    ///
+    /// See `getpeername(2)`.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        SocketAddr::try_from(&self.inner.peer_addr()?)
+    }
+
+    /// Shuts down the read, write, or both halves of the connection.
+    ///
+    /// # Errors
+    ///
+    /// See `shutdown(2)`.
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        self.inner.shutdown(how)
+    }
+
+    /// Moves the socket into or out of nonblocking mode.
+    ///
+    /// # Errors
+    ///
+    /// See `fcntl(2)`.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.inner.set_nonblocking(nonblocking)
+    }
+
+    /// Sets the read timeout of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See `setsockopt(2)`.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_read_timeout(timeout)
+    }
+
+    /// Sets the write timeout of the socket.
+    ///
+    /// # Errors
+    ///
+    /// See `setsockopt(2)`.
+    pub fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.inner.set_write_timeout(timeout)
+    }
+
+    /// Returns the value of the `SO_ERROR` option, clearing it.
+    ///
+    /// # Errors
+    ///
+    /// See `getsockopt(2)`.
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.inner.take_error()
+    }
+}
+
+impl From<SeqPacketStream> for Socket {
+    fn from(stream: SeqPacketStream) -> Self {
+        stream.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("/tmp/test_seqpacket.socket")]
+    #[case("@test_seqpacket.socket")]
+    #[case("")]
+    fn test_seqpacket(#[case] addr: &str) {
+        let listener = SeqPacketListener::bind(&SocketAddr::new(addr).unwrap()).unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let client = SeqPacketStream::connect(&local_addr).unwrap();
+        let (server, _) = listener.accept().unwrap();
+
+        assert_eq!(client.peer_addr().unwrap(), local_addr);
+
+        client.send(b"hello").unwrap();
+        client.send(b"world!").unwrap();
+
+        // Message boundaries are preserved.
+        let mut buf = [0; 16];
+        assert_eq!(server.recv(&mut buf).unwrap(), 5);
+        assert_eq!(&buf[..5], b"hello");
+        assert_eq!(server.recv(&mut buf).unwrap(), 6);
+        assert_eq!(&buf[..6], b"world!");
+
+        client.shutdown(Shutdown::Write).unwrap();
+        assert_eq!(server.recv(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_seqpacket_pair() {
+        let (a, b) = SeqPacketStream::pair().unwrap();
+
+        a.send(b"ping").unwrap();
+
+        let mut buf = [0; 2];
+        assert_eq!(b.recv(&mut buf).unwrap(), 2);
+        assert_eq!(&buf, b"pi");
+    }
+}
@@ -0,0 +1,331 @@
+//! A stable, versioned binary encoding of [`UniAddr`], for embedding into
+//! custom protocols, see [`UniAddr::to_wire_bytes`].
+
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::{UniAddr, UniAddrInner};
+
+/// The current version of the encoding.
+const VERSION: u8 = 0x01;
+
+const TAG_IPV4: u8 = 0x01;
+const TAG_IPV6: u8 = 0x02;
+const TAG_UNIX_PATHNAME: u8 = 0x03;
+const TAG_UNIX_ABSTRACT: u8 = 0x04;
+const TAG_UNIX_UNNAMED: u8 = 0x05;
+const TAG_VSOCK: u8 = 0x06;
+const TAG_FD: u8 = 0x07;
+const TAG_PIPE: u8 = 0x08;
+const TAG_HOST: u8 = 0x09;
+
+/// See [`UniAddr::to_wire_bytes`].
+pub(crate) fn encode(addr: &UniAddr) -> Vec<u8> {
+    let mut bytes = vec![VERSION];
+
+    match addr.as_inner() {
+        UniAddrInner::Inet(SocketAddr::V4(addr)) => {
+            bytes.push(TAG_IPV4);
+            bytes.extend_from_slice(&addr.ip().octets());
+            bytes.extend_from_slice(&addr.port().to_be_bytes());
+        }
+        UniAddrInner::Inet(SocketAddr::V6(addr)) => {
+            bytes.push(TAG_IPV6);
+            bytes.extend_from_slice(&addr.ip().octets());
+            bytes.extend_from_slice(&addr.port().to_be_bytes());
+            bytes.extend_from_slice(&addr.flowinfo().to_be_bytes());
+            bytes.extend_from_slice(&addr.scope_id().to_be_bytes());
+        }
+        #[cfg(unix)]
+        UniAddrInner::Unix(addr) => {
+            use std::os::unix::ffi::OsStrExt;
+
+            if let Some(pathname) = addr.as_pathname() {
+                bytes.push(TAG_UNIX_PATHNAME);
+                push_len_prefixed(&mut bytes, pathname.as_os_str().as_bytes());
+
+                return bytes;
+            }
+
+            #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+            {
+                #[cfg(target_os = "android")]
+                use std::os::android::net::SocketAddrExt;
+                #[cfg(target_os = "cygwin")]
+                use std::os::cygwin::net::SocketAddrExt;
+                #[cfg(target_os = "linux")]
+                use std::os::linux::net::SocketAddrExt;
+
+                if let Some(abstract_name) = addr.as_abstract_name() {
+                    bytes.push(TAG_UNIX_ABSTRACT);
+                    push_len_prefixed(&mut bytes, abstract_name);
+
+                    return bytes;
+                }
+            }
+
+            // An unnamed one...
+            bytes.push(TAG_UNIX_UNNAMED);
+        }
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        UniAddrInner::Vsock(cid, port) => {
+            bytes.push(TAG_VSOCK);
+            bytes.extend_from_slice(&cid.to_be_bytes());
+            bytes.extend_from_slice(&port.to_be_bytes());
+        }
+        #[cfg(unix)]
+        UniAddrInner::Fd(fd) => {
+            bytes.push(TAG_FD);
+            bytes.extend_from_slice(&fd.to_be_bytes());
+        }
+        #[cfg(windows)]
+        UniAddrInner::Pipe(path) => {
+            bytes.push(TAG_PIPE);
+            push_len_prefixed(&mut bytes, path.as_bytes());
+        }
+        UniAddrInner::Host(addr) => {
+            bytes.push(TAG_HOST);
+            push_len_prefixed(&mut bytes, addr.as_bytes());
+        }
+    }
+
+    bytes
+}
+
+/// Appends `data` with its length as a `u16` prefix.
+fn push_len_prefixed(bytes: &mut Vec<u8>, data: &[u8]) {
+    // Socket paths, pipe names and host names are far shorter.
+    #[allow(clippy::cast_possible_truncation)]
+    bytes.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(data);
+}
+
+/// See [`UniAddr::from_wire_bytes`].
+pub(crate) fn decode(bytes: &[u8]) -> io::Result<(UniAddr, usize)> {
+    let mut reader = Reader { bytes, pos: 0 };
+
+    if reader.take::<1>()? != [VERSION] {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unknown wire encoding version",
+        ));
+    }
+
+    let [tag] = reader.take()?;
+
+    let addr = match tag {
+        TAG_IPV4 => {
+            let ip = Ipv4Addr::from(reader.take::<4>()?);
+            let port = u16::from_be_bytes(reader.take()?);
+
+            UniAddr::from(SocketAddrV4::new(ip, port))
+        }
+        TAG_IPV6 => {
+            let ip = Ipv6Addr::from(reader.take::<16>()?);
+            let port = u16::from_be_bytes(reader.take()?);
+            let flowinfo = u32::from_be_bytes(reader.take()?);
+            let scope_id = u32::from_be_bytes(reader.take()?);
+
+            UniAddr::from(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+        }
+        #[cfg(unix)]
+        TAG_UNIX_PATHNAME => {
+            use std::os::unix::ffi::OsStrExt;
+
+            let pathname = reader.take_len_prefixed()?;
+
+            // Not `new_pathname`, which removes the socket file.
+            std::os::unix::net::SocketAddr::from_pathname(std::ffi::OsStr::from_bytes(pathname))
+                .map(UniAddr::from)
+                .map_err(invalid_data)?
+        }
+        #[cfg(any(target_os = "android", target_os = "linux", target_os = "cygwin"))]
+        TAG_UNIX_ABSTRACT => crate::unix::SocketAddr::new_abstract(reader.take_len_prefixed()?)
+            .map(UniAddr::from)
+            .map_err(invalid_data)?,
+        #[cfg(unix)]
+        TAG_UNIX_UNNAMED => UniAddr::from(crate::unix::SocketAddr::new_unnamed()),
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        TAG_VSOCK => {
+            let cid = u32::from_be_bytes(reader.take()?);
+            let port = u32::from_be_bytes(reader.take()?);
+
+            UniAddr::new_vsock(cid, port)
+        }
+        #[cfg(unix)]
+        TAG_FD => match i32::from_be_bytes(reader.take()?) {
+            fd if fd >= 0 => UniAddr::from_inner(UniAddrInner::Fd(fd)),
+            _ => return Err(invalid_data("invalid file descriptor number")),
+        },
+        #[cfg(windows)]
+        TAG_PIPE => UniAddr::new_pipe(reader.take_str()?).map_err(invalid_data)?,
+        // Not `UniAddr::new`, which accepts other types of addresses, and
+        // removes the file of a Unix socket.
+        TAG_HOST => UniAddr::from_host_str(reader.take_str()?).map_err(invalid_data)?,
+        #[cfg(not(unix))]
+        TAG_UNIX_PATHNAME | TAG_UNIX_UNNAMED | TAG_FD => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported address type on this platform",
+            ));
+        }
+        #[cfg(not(any(target_os = "android", target_os = "linux", target_os = "cygwin")))]
+        TAG_UNIX_ABSTRACT => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported address type on this platform",
+            ));
+        }
+        #[cfg(not(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        )))]
+        TAG_VSOCK => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported address type on this platform",
+            ));
+        }
+        #[cfg(not(windows))]
+        TAG_PIPE => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "unsupported address type on this platform",
+            ));
+        }
+        _ => return Err(invalid_data("unknown wire encoding address type")),
+    };
+
+    Ok((addr, reader.pos))
+}
+
+fn invalid_data<E>(err: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take_slice(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos + len).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::UnexpectedEof, "truncated wire address")
+        })?;
+
+        self.pos += len;
+
+        Ok(slice)
+    }
+
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take_slice(N)?);
+
+        Ok(array)
+    }
+
+    fn take_len_prefixed(&mut self) -> io::Result<&'a [u8]> {
+        let len = u16::from_be_bytes(self.take()?);
+
+        self.take_slice(usize::from(len))
+    }
+
+    fn take_str(&mut self) -> io::Result<&'a str> {
+        std::str::from_utf8(self.take_len_prefixed()?).map_err(invalid_data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(UniAddr::new("192.0.2.1:8080").unwrap(), &[1, 1, 192, 0, 2, 1, 0x1f, 0x90])]
+    #[case(
+        UniAddr::from(SocketAddrV6::new(Ipv6Addr::LOCALHOST, 8080, 1, 2)),
+        &[1, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0x1f, 0x90, 0, 0, 0, 1, 0, 0, 0, 2]
+    )]
+    #[case(UniAddr::new("a.io:80").unwrap(), &[1, 9, 0, 7, b'a', b'.', b'i', b'o', b':', b'8', b'0'])]
+    #[case(UniAddr::new_optional_port("a.io").unwrap(), &[1, 9, 0, 4, b'a', b'.', b'i', b'o'])]
+    #[case(UniAddr::new_optional_port("192.0.2.1").unwrap(), &[1, 9, 0, 9, b'1', b'9', b'2', b'.', b'0', b'.', b'2', b'.', b'1'])]
+    #[case(UniAddr::new_optional_port("::1").unwrap(), &[1, 9, 0, 5, b'[', b':', b':', b'1', b']'])]
+    #[case(UniAddr::new_optional_port("[fe80::1%2]").unwrap(), &[1, 9, 0, 11, b'[', b'f', b'e', b'8', b'0', b':', b':', b'1', b'%', b'2', b']'])]
+    #[cfg_attr(unix, case(UniAddr::new("unix:///a").unwrap(), &[1, 3, 0, 2, b'/', b'a']))]
+    #[cfg_attr(unix, case(UniAddr::from(crate::unix::SocketAddr::new_unnamed()), &[1, 5]))]
+    #[cfg_attr(unix, case(UniAddr::new("fd://3").unwrap(), &[1, 7, 0, 0, 0, 3]))]
+    #[cfg_attr(
+        any(target_os = "android", target_os = "linux"),
+        case(UniAddr::new("unix://@a\0").unwrap(), &[1, 4, 0, 2, b'a', 0])
+    )]
+    #[cfg_attr(
+        all(feature = "feat-vsock", any(target_os = "android", target_os = "linux")),
+        case(UniAddr::new_vsock(2, 1234), &[1, 6, 0, 0, 0, 2, 0, 0, 0x04, 0xd2])
+    )]
+    #[cfg_attr(
+        windows,
+        case(UniAddr::new(r"pipe://\\.\pipe\a").unwrap(), &[1, 8, 0, 10, b'\\', b'\\', b'.', b'\\', b'p', b'i', b'p', b'e', b'\\', b'a'])
+    )]
+    fn test_roundtrip(#[case] addr: UniAddr, #[case] expected: &[u8]) {
+        let bytes = encode(&addr);
+        assert_eq!(bytes, expected);
+
+        let mut with_trailing = bytes.clone();
+        with_trailing.extend_from_slice(b"rest");
+        assert_eq!(decode(&with_trailing).unwrap(), (addr, bytes.len()));
+
+        for len in 0..bytes.len() {
+            assert_eq!(
+                decode(&bytes[..len]).unwrap_err().kind(),
+                io::ErrorKind::UnexpectedEof
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(&[2, 1, 192, 0, 2, 1, 0x1f, 0x90])]
+    #[case(&[1, 0])]
+    #[case(&[1, 0xff])]
+    #[case(&[1, 9, 0, 4, b'-', b'.', b'i', b'o'])]
+    #[case(&[1, 9, 0, 3, b':', b':', b'1'])]
+    #[case(&[1, 9, 0, 2, 0xff, 0xfe])]
+    #[case(&[1, 9, 0, 12, b'1', b'9', b'2', b'.', b'0', b'.', b'2', b'.', b'1', b':', b'8', b'0'])]
+    #[case(&[1, 9, 0, 13, b'd', b'n', b's', b':', b'/', b'/', b'a', b'.', b'i', b'o', b':', b'8', b'0'])]
+    #[cfg_attr(unix, case(&[1, 9, 0, 6, b'f', b'd', b':', b'/', b'/', b'3']))]
+    #[cfg_attr(unix, case(&[1, 7, 0xff, 0xff, 0xff, 0xff]))]
+    fn test_decode_invalid(#[case] bytes: &[u8]) {
+        assert_eq!(
+            decode(bytes).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_decode_host_keeps_unix_file() {
+        let path = std::env::temp_dir().join(format!("uni-addr-wire-{}", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+
+        let mut bytes = vec![VERSION, TAG_HOST];
+        push_len_prefixed(&mut bytes, format!("unix://{}", path.display()).as_bytes());
+
+        let err = decode(&bytes).unwrap_err();
+        let exists = path.exists();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(exists);
+    }
+}
@@ -0,0 +1,123 @@
+//! Helpers for X.509 certificate subjectAltName entries.
+//!
+//! See [`UniAddr::as_san`] and [`UniAddr::matches_san`].
+
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use crate::{matches_dns_name, split_host_port, UniAddr, UniAddrInner};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A subjectAltName entry of a certificate.
+pub enum SubjectAltName<'a> {
+    /// A `dNSName` entry, which may contain a leading wildcard label, like
+    /// `*.example.com`.
+    DnsName(&'a str),
+
+    /// An `iPAddress` entry.
+    IpAddress(IpAddr),
+}
+
+impl SubjectAltName<'_> {
+    /// Checks if this entry, taken from a certificate, is valid for the
+    /// `expected` one.
+    ///
+    /// DNS names are compared case-insensitively. A wildcard label `*` is only
+    /// allowed as the complete leftmost label of this entry and matches exactly
+    /// one label of the expected DNS name, so `*.example.com` matches
+    /// `www.example.com` but neither `example.com` nor `a.b.example.com`.
+    pub fn matches(&self, expected: &SubjectAltName<'_>) -> bool {
+        match (self, expected) {
+            (Self::IpAddress(ip), SubjectAltName::IpAddress(expected)) => ip == expected,
+            (Self::DnsName(name), SubjectAltName::DnsName(expected)) => {
+                matches_dns_name(name, expected)
+            }
+            _ => false,
+        }
+    }
+}
+
+pub(crate) fn as_san(addr: &UniAddr) -> Option<SubjectAltName<'_>> {
+    match addr.as_inner() {
+        UniAddrInner::Inet(addr) => Some(SubjectAltName::IpAddress(addr.ip())),
+        #[cfg(unix)]
+        UniAddrInner::Unix(_) => None,
+        #[cfg(all(
+            feature = "feat-vsock",
+            any(target_os = "android", target_os = "linux")
+        ))]
+        UniAddrInner::Vsock(..) => None,
+        #[cfg(unix)]
+        UniAddrInner::Fd(_) => None,
+        #[cfg(windows)]
+        UniAddrInner::Pipe(_) => None,
+        UniAddrInner::Host(addr) => {
+            let (host, _) = split_host_port(addr);
+
+            // A port-less IP address, see `UniAddr::new_optional_port`.
+            let ip = host
+                .strip_prefix('[')
+                .and_then(|host| host.strip_suffix(']'))
+                .map_or(host, |host| host.split_once('%').map_or(host, |(ip, _)| ip));
+
+            match IpAddr::from_str(ip) {
+                Ok(ip) => Some(SubjectAltName::IpAddress(ip)),
+                Err(_) => Some(SubjectAltName::DnsName(host)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("127.0.0.1:443", Some(SubjectAltName::IpAddress([127, 0, 0, 1].into())))]
+    #[case("[::1]:443", Some(SubjectAltName::IpAddress(std::net::Ipv6Addr::LOCALHOST.into())))]
+    #[case("example.com:443", Some(SubjectAltName::DnsName("example.com")))]
+    #[cfg_attr(unix, case("unix:///tmp/test_as_san.socket", None))]
+    fn test_as_san(#[case] addr: &str, #[case] expected: Option<SubjectAltName<'_>>) {
+        assert_eq!(UniAddr::new(addr).unwrap().as_san(), expected);
+    }
+
+    #[rstest]
+    #[case("example.com", SubjectAltName::DnsName("example.com"))]
+    #[case("192.0.2.1", SubjectAltName::IpAddress([192, 0, 2, 1].into()))]
+    #[case("::1", SubjectAltName::IpAddress(std::net::Ipv6Addr::LOCALHOST.into()))]
+    #[case("[fe80::1%2]", SubjectAltName::IpAddress("fe80::1".parse().unwrap()))]
+    fn test_as_san_optional_port(#[case] addr: &str, #[case] expected: SubjectAltName<'_>) {
+        assert_eq!(
+            UniAddr::new_optional_port(addr).unwrap().as_san(),
+            Some(expected)
+        );
+    }
+
+    #[rstest]
+    #[case("example.com:443", "example.com", true)]
+    #[case("example.com:443", "EXAMPLE.com", true)]
+    #[case("example.com:443", "example.org", false)]
+    #[case("www.example.com:443", "*.example.com", true)]
+    #[case("example.com:443", "*.example.com", false)]
+    #[case("a.b.example.com:443", "*.example.com", false)]
+    #[case("www.example.com:443", "www.*.com", false)]
+    #[case("127.0.0.1:443", "127.0.0.1", false)]
+    fn test_matches_san_dns_name(#[case] addr: &str, #[case] san: &str, #[case] expected: bool) {
+        let addr = UniAddr::new(addr).unwrap();
+
+        assert_eq!(addr.matches_san([SubjectAltName::DnsName(san)]), expected);
+    }
+
+    #[test]
+    fn test_matches_san_ip_address() {
+        let addr = UniAddr::new("127.0.0.1:443").unwrap();
+
+        assert!(addr.matches_san([
+            SubjectAltName::DnsName("localhost"),
+            SubjectAltName::IpAddress([127, 0, 0, 1].into()),
+        ]));
+        assert!(!addr.matches_san([SubjectAltName::IpAddress([127, 0, 0, 2].into())]));
+    }
+}